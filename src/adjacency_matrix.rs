@@ -59,5 +59,4 @@ impl AdjacencyMatrix {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
 }
@@ -0,0 +1,1011 @@
+//! A simple, unbalanced binary search tree stored in an `ArenaTree`.
+//!
+//! `ArenaBinarySearchTree` offers the same operations as `BinarySearchTree`,
+//! with the same algorithms, but its nodes live in a single `Vec` and are
+//! linked by `NodeId` handles instead of `Rc<RefCell<_>>` references. Queries
+//! return `NodeId`s where `BinarySearchTree` returns `RefNode`s, and the values
+//! are read through `value` as plain references. Iterating over the tree
+//! yields clones of the values, as it does for `BinarySearchTree`.
+//!
+//! As with `BinarySearchTree`, operations have a worst case complexity of
+//! *O(n)*, since nothing keeps the tree balanced.
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::ops::{Bound, RangeBounds};
+use crate::arena_tree::{ArenaTree, InOrder, NodeId};
+use crate::interfaces::SSet;
+
+
+/// A binary search tree data structure whose nodes are stored in an arena.
+#[derive(Debug)]
+pub struct ArenaBinarySearchTree<T> {
+    arena: ArenaTree<T>,
+    root: Option<NodeId>,
+}
+
+impl<T> ArenaBinarySearchTree<T> {
+    /// Creates a new, empty binary search tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let tree: ArenaBinarySearchTree<i32> = ArenaBinarySearchTree::new();
+    /// ```
+    pub fn new() -> Self {
+        Self { arena: ArenaTree::new(), root: None }
+    }
+
+    /// Returns the number of nodes contained in `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let tree: ArenaBinarySearchTree<i32> = ArenaBinarySearchTree::new();
+    /// assert_eq!(tree.size(), 0);
+    /// ```
+    pub fn size(&self) -> usize {
+        self.arena.len()
+    }
+
+    /// Returns `true` if the size of the tree is zero, `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let tree: ArenaBinarySearchTree<i32> = ArenaBinarySearchTree::new();
+    /// assert!(tree.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Returns the number of nodes in the longest path from the root to a
+    /// leaf, or 0 if the tree is empty.
+    pub fn height(&self) -> usize {
+        self.root.map_or(0, |root| self.arena.height(root))
+    }
+
+    /// Returns a reference to the value stored in the node `u` of `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let mut tree = ArenaBinarySearchTree::new();
+    /// tree.add(0);
+    /// let u = tree.find(&0).unwrap();
+    /// assert_eq!(*tree.value(u), 0);
+    /// ```
+    pub fn value(&self, u: NodeId) -> &T {
+        self.arena.value(u)
+    }
+
+    /// Returns the node in `self` with the smallest value, or `None` if `self`
+    /// is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let tree: ArenaBinarySearchTree<i32> = [1, 0].into_iter().collect();
+    /// assert_eq!(*tree.value(tree.min().unwrap()), 0);
+    /// ```
+    pub fn min(&self) -> Option<NodeId> {
+        self.root.map(|root| self.arena.leftmost(root))
+    }
+
+    /// Returns the node in `self` with the largest value, or `None` if `self`
+    /// is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let tree: ArenaBinarySearchTree<i32> = [1, 0].into_iter().collect();
+    /// assert_eq!(*tree.value(tree.max().unwrap()), 1);
+    /// ```
+    pub fn max(&self) -> Option<NodeId> {
+        self.root.map(|root| self.arena.rightmost(root))
+    }
+
+    /// Returns the node that follows `u` in the sorted order of `self`, or
+    /// `None` if `u` holds the largest value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let tree: ArenaBinarySearchTree<i32> = [1, 0, 2].into_iter().collect();
+    /// let u = tree.successor(tree.find(&0).unwrap()).unwrap();
+    /// assert_eq!(*tree.value(u), 1);
+    /// ```
+    pub fn successor(&self, u: NodeId) -> Option<NodeId> {
+        self.arena.successor(u)
+    }
+
+    /// Returns the node that precedes `u` in the sorted order of `self`, or
+    /// `None` if `u` holds the smallest value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let tree: ArenaBinarySearchTree<i32> = [1, 0, 2].into_iter().collect();
+    /// let u = tree.predecessor(tree.find(&2).unwrap()).unwrap();
+    /// assert_eq!(*tree.value(u), 1);
+    /// ```
+    pub fn predecessor(&self, u: NodeId) -> Option<NodeId> {
+        self.arena.predecessor(u)
+    }
+
+    /// Returns an iterator over the values in `self`, in increasing order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let tree: ArenaBinarySearchTree<i32> = [2, 0, 1].into_iter().collect();
+    /// assert_eq!(tree.iter().collect::<Vec<i32>>(), [0, 1, 2]);
+    /// ```
+    pub fn iter(&self) -> ArenaBinarySearchTreeIterator<'_, T> {
+        ArenaBinarySearchTreeIterator {
+            arena: &self.arena,
+            nodes: self.root.map(|root| self.arena.in_order(root)),
+        }
+    }
+
+    /// Returns the node with the `k`-th smallest value in `self`, counting
+    /// from 0, or `None` if `k` is not less than the size of `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let tree: ArenaBinarySearchTree<i32> = [30, 10, 20].into_iter().collect();
+    /// assert_eq!(*tree.value(tree.select(1).unwrap()), 20);
+    /// assert!(tree.select(3).is_none());
+    /// ```
+    pub fn select(&self, k: usize) -> Option<NodeId> {
+        let mut k = k;
+        let mut node_opt = self.root;
+
+        while let Some(node) = node_opt {
+            let left_size = self.left_size(node);
+            node_opt = if k < left_size {
+                self.arena.left(node)
+            } else if k > left_size {
+                k -= left_size + 1;
+                self.arena.right(node)
+            } else {
+                return Some(node);
+            };
+        }
+        None
+    }
+
+    fn left_size(&self, u: NodeId) -> usize {
+        self.arena.left(u).map_or(0, |left| self.arena.size(left))
+    }
+
+    /// Takes out `u`, which must have at most one child, and links its child
+    /// in its place.
+    fn splice(&mut self, u: NodeId) {
+        let child_opt = self.arena.left(u).or(self.arena.right(u));
+        let parent_opt = self.arena.parent(u);
+        match parent_opt {
+            None => self.root = child_opt,
+            Some(parent) => {
+                if self.arena.is_left_child(u) {
+                    self.arena.set_left(parent, child_opt);
+                } else {
+                    self.arena.set_right(parent, child_opt);
+                }
+                self.arena.update_sizes_to_root(parent);
+            },
+        }
+        if let Some(child) = child_opt {
+            self.arena.set_parent(child, parent_opt);
+        }
+    }
+
+    /// Links `subtree` in the place of `u`.
+    fn replace(&mut self, u: NodeId, subtree: Option<NodeId>) {
+        let parent_opt = self.arena.parent(u);
+        match parent_opt {
+            None => self.root = subtree,
+            Some(parent) => {
+                if self.arena.is_left_child(u) {
+                    self.arena.set_left(parent, subtree);
+                } else {
+                    self.arena.set_right(parent, subtree);
+                }
+            },
+        }
+        if let Some(subtree) = subtree {
+            self.arena.set_parent(subtree, parent_opt);
+        }
+    }
+
+    /// Deallocates every node under `u`, `u` included, and returns how many
+    /// there were.
+    fn dealloc_subtree(&mut self, u: NodeId) -> usize {
+        let size = self.arena.size(u);
+        let mut stack = vec![u];
+        while let Some(node) = stack.pop() {
+            stack.extend(self.arena.left(node));
+            stack.extend(self.arena.right(node));
+            self.arena.dealloc(node);
+        }
+        size
+    }
+}
+
+impl<T> Default for ArenaBinarySearchTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialOrd> ArenaBinarySearchTree<T> {
+    /// Compares `value` with the value stored in `u`.
+    fn compare<Q>(&self, value: &Q, u: NodeId) -> Ordering
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        value.cmp(self.arena.value(u).borrow())
+    }
+
+    /// Returns the node in `self` that contains `value`, or `None` if `value`
+    /// is not in `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let mut tree = ArenaBinarySearchTree::new();
+    /// assert!(tree.find(&0).is_none());
+    /// tree.add(0);
+    /// assert!(tree.find(&0).is_some());
+    /// ```
+    pub fn find<Q>(&self, value: &Q) -> Option<NodeId>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut node_opt = self.root;
+
+        while let Some(node) = node_opt {
+            node_opt = match self.compare(value, node) {
+                Ordering::Less => self.arena.left(node),
+                Ordering::Greater => self.arena.right(node),
+                Ordering::Equal => return Some(node),
+            };
+        }
+        None
+    }
+
+    /// Returns `true` if `value` is in `self`, otherwise `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let mut tree = ArenaBinarySearchTree::new();
+    /// tree.add(String::from("a"));
+    /// assert!(tree.contains("a"));
+    /// assert!(!tree.contains("b"));
+    /// ```
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(value).is_some()
+    }
+
+    /// Returns the number of values in `self` that are less than `value`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let tree: ArenaBinarySearchTree<i32> = [30, 10, 20].into_iter().collect();
+    /// assert_eq!(tree.rank(&25), 2);
+    /// ```
+    pub fn rank<Q>(&self, value: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut rank = 0;
+        let mut node_opt = self.root;
+
+        while let Some(node) = node_opt {
+            node_opt = match self.compare(value, node) {
+                Ordering::Less => self.arena.left(node),
+                Ordering::Greater => {
+                    rank += self.left_size(node) + 1;
+                    self.arena.right(node)
+                },
+                Ordering::Equal => return rank + self.left_size(node),
+            };
+        }
+        rank
+    }
+
+    /// Returns the node in `self` with the largest value that is less than or
+    /// equal to `value`, or `None` if there is no such node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let tree: ArenaBinarySearchTree<i32> = [0, 10].into_iter().collect();
+    /// assert_eq!(*tree.value(tree.floor(&5).unwrap()), 0);
+    /// assert!(tree.floor(&-5).is_none());
+    /// ```
+    pub fn floor<Q>(&self, value: &Q) -> Option<NodeId>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut node_opt = self.root;
+        let mut candidate = None;
+
+        while let Some(node) = node_opt {
+            node_opt = match self.compare(value, node) {
+                Ordering::Less => self.arena.left(node),
+                Ordering::Greater => {
+                    candidate = Some(node);
+                    self.arena.right(node)
+                },
+                Ordering::Equal => return Some(node),
+            };
+        }
+        candidate
+    }
+
+    /// Returns the node in `self` with the smallest value that is greater than
+    /// or equal to `value`, or `None` if there is no such node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let tree: ArenaBinarySearchTree<i32> = [0, 10].into_iter().collect();
+    /// assert_eq!(*tree.value(tree.ceiling(&5).unwrap()), 10);
+    /// assert!(tree.ceiling(&15).is_none());
+    /// ```
+    pub fn ceiling<Q>(&self, value: &Q) -> Option<NodeId>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut node_opt = self.root;
+        let mut candidate = None;
+
+        while let Some(node) = node_opt {
+            node_opt = match self.compare(value, node) {
+                Ordering::Less => {
+                    candidate = Some(node);
+                    self.arena.left(node)
+                },
+                Ordering::Greater => self.arena.right(node),
+                Ordering::Equal => return Some(node),
+            };
+        }
+        candidate
+    }
+
+    /// Adds `value` to the tree `self`. Returns `false` if `value` is already
+    /// in `self`, otherwise `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let mut tree = ArenaBinarySearchTree::new();
+    /// assert!(tree.add(0));
+    /// assert!(!tree.add(0));
+    /// ```
+    pub fn add(&mut self, value: T) -> bool {
+        let mut node_opt = self.root;
+        let mut last_opt = None;
+
+        while let Some(node) = node_opt {
+            last_opt = Some(node);
+            node_opt = match value.partial_cmp(self.arena.value(node)) {
+                Some(Ordering::Less) => self.arena.left(node),
+                Some(Ordering::Greater) => self.arena.right(node),
+                _ => return false,
+            };
+        }
+        let Some(last) = last_opt else {
+            self.root = Some(self.arena.alloc(value));
+            return true;
+        };
+        let is_left = value < *self.arena.value(last);
+        let new_node = self.arena.alloc(value);
+        if is_left {
+            self.arena.set_left(last, Some(new_node));
+        } else {
+            self.arena.set_right(last, Some(new_node));
+        }
+        self.arena.set_parent(new_node, Some(last));
+        self.arena.update_sizes_to_root(last);
+        true
+    }
+
+    /// Removes `value` from the tree `self`. Returns `false` if `value` is not
+    /// in `self`, otherwise `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let mut tree = ArenaBinarySearchTree::new();
+    /// tree.add(0);
+    /// assert!(tree.remove(&0));
+    /// assert!(!tree.remove(&0));
+    /// ```
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let Some(node) = self.find(value) else {
+            return false;
+        };
+        match (self.arena.left(node), self.arena.right(node)) {
+            (Some(_), Some(right)) => {
+                let min_node = self.arena.leftmost(right);
+                self.splice(min_node);
+                *self.arena.value_mut(node) = self.arena.dealloc(min_node);
+            },
+            _ => {
+                self.splice(node);
+                self.arena.dealloc(node);
+            },
+        }
+        true
+    }
+
+    /// Checks that `self` is a valid binary search tree: its values are sorted
+    /// with no duplicates, every child links back to its parent, the root has
+    /// no parent, and the size stored in every node, as well as the number of
+    /// nodes in the arena, match the number of nodes counted.
+    ///
+    /// # Panics
+    ///
+    /// Panics, with a message naming the broken invariant, if `self` is not
+    /// valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let mut tree: ArenaBinarySearchTree<i32> = [3, 1, 4, 5, 9, 2, 6].into_iter().collect();
+    /// tree.remove(&4);
+    /// tree.check_invariants();
+    /// ```
+    pub fn check_invariants(&self) {
+        if let Some(root) = self.root {
+            assert!(self.arena.parent(root).is_none(), "the root has a parent");
+        }
+        let mut count = 0;
+        let mut previous: Option<NodeId> = None;
+        let mut stack = Vec::new();
+        let mut node_opt = self.root;
+
+        while node_opt.is_some() || !stack.is_empty() {
+            while let Some(node) = node_opt {
+                node_opt = self.arena.left(node);
+                stack.push(node);
+            }
+            let node = stack.pop().expect("the stack should not be empty");
+            count += 1;
+
+            let mut size = 1;
+            for child in [self.arena.left(node), self.arena.right(node)].into_iter().flatten() {
+                assert_eq!(
+                    self.arena.parent(child), Some(node),
+                    "a child does not link back to its parent",
+                );
+                size += self.arena.size(child);
+            }
+            assert_eq!(self.arena.size(node), size, "a node size is out of date");
+            if let Some(previous) = previous {
+                assert!(
+                    *self.arena.value(previous) < *self.arena.value(node),
+                    "values are not sorted",
+                );
+            }
+            node_opt = self.arena.right(node);
+            previous = Some(node);
+        }
+        assert_eq!(count, self.arena.len(), "the arena holds unlinked nodes");
+    }
+
+    /// Returns an iterator over the values of `self` that are within `range`,
+    /// in increasing order, found lazily in *O(h + k)* time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let tree: ArenaBinarySearchTree<i32> = (0..10).collect();
+    /// assert_eq!(tree.range(3..6).collect::<Vec<i32>>(), [3, 4, 5]);
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> ArenaBinarySearchTreeRange<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let next = self
+            .first_in_range(&range)
+            .filter(|&node| is_before_end(&range, self.arena.value(node)));
+        ArenaBinarySearchTreeRange { arena: &self.arena, next, end: self.first_after_range(&range) }
+    }
+
+    /// Returns the number of values of `self` that are within `range`, in
+    /// *O(h + k)* time, where `k` is that number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let tree: ArenaBinarySearchTree<i32> = (0..10).collect();
+    /// assert_eq!(tree.count_range(3..6), 3);
+    /// ```
+    pub fn count_range<Q, R>(&self, range: R) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let mut count = 0;
+        let mut node_opt = self.first_in_range(&range);
+
+        while let Some(node) = node_opt {
+            if !is_before_end(&range, self.arena.value(node)) {
+                break;
+            }
+            count += 1;
+            node_opt = self.arena.successor(node);
+        }
+        count
+    }
+
+    /// Removes every value of `self` that is within `range`, and returns how
+    /// many were removed, in *O(h + k)* time. See
+    /// `BinarySearchTree::remove_range` for how the tree is pruned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_binary_search_tree::ArenaBinarySearchTree;
+    /// let mut tree: ArenaBinarySearchTree<i32> = (0..10).collect();
+    /// assert_eq!(tree.remove_range(..4), 4);
+    /// assert_eq!(tree.iter().collect::<Vec<i32>>(), [4, 5, 6, 7, 8, 9]);
+    /// ```
+    pub fn remove_range<Q, R>(&mut self, range: R) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let mut node_opt = self.root;
+        let split = loop {
+            let Some(node) = node_opt else {
+                return 0;
+            };
+            let value = self.arena.value(node);
+            node_opt = if !is_after_start(&range, value) {
+                self.arena.right(node)
+            } else if !is_before_end(&range, value) {
+                self.arena.left(node)
+            } else {
+                break node;
+            };
+        };
+
+        let mut removed = 0;
+        let left = self.prune_after_start(self.arena.left(split), &range, &mut removed);
+        let right = self.prune_before_end(self.arena.right(split), &range, &mut removed);
+        let deepest = right
+            .map(|right| self.arena.leftmost(right))
+            .or_else(|| left.map(|left| self.arena.rightmost(left)));
+        let joined = match left {
+            None => right,
+            Some(left) => {
+                let last = self.arena.rightmost(left);
+                self.arena.set_right(last, right);
+                if let Some(right) = right {
+                    self.arena.set_parent(right, Some(last));
+                }
+                Some(left)
+            },
+        };
+
+        let parent_opt = self.arena.parent(split);
+        self.replace(split, joined);
+        self.arena.dealloc(split);
+        removed += 1;
+        if let Some(node) = deepest.or(parent_opt) {
+            self.arena.update_sizes_to_root(node);
+        }
+        removed
+    }
+
+    /// Returns the node with the smallest value of `self` within the start
+    /// bound of `range`, which may be after its end bound.
+    fn first_in_range<Q, R>(&self, range: &R) -> Option<NodeId>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let mut node_opt = self.root;
+        let mut candidate = None;
+
+        while let Some(node) = node_opt {
+            node_opt = if is_after_start(range, self.arena.value(node)) {
+                candidate = Some(node);
+                self.arena.left(node)
+            } else {
+                self.arena.right(node)
+            };
+        }
+        candidate
+    }
+
+    /// Returns the node with the smallest value of `self` past the end bound
+    /// of `range`, where iterating over the range stops.
+    fn first_after_range<Q, R>(&self, range: &R) -> Option<NodeId>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let mut node_opt = self.root;
+        let mut candidate = None;
+
+        while let Some(node) = node_opt {
+            node_opt = if is_before_end(range, self.arena.value(node)) {
+                self.arena.right(node)
+            } else {
+                candidate = Some(node);
+                self.arena.left(node)
+            };
+        }
+        candidate
+    }
+
+    /// Removes the values within the start bound of `range` from `subtree`,
+    /// all of whose values are within the end bound, and returns the root of
+    /// what is left. Adds the number of nodes removed to `removed`.
+    fn prune_after_start<Q, R>(
+        &mut self,
+        subtree: Option<NodeId>,
+        range: &R,
+        removed: &mut usize,
+    ) -> Option<NodeId>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let mut root = None;
+        let mut last: Option<NodeId> = None;
+        let mut node_opt = subtree;
+
+        while let Some(node) = node_opt {
+            node_opt = self.arena.left(node);
+            if is_after_start(range, self.arena.value(node)) {
+                if let Some(right) = self.arena.right(node) {
+                    *removed += self.dealloc_subtree(right);
+                }
+                self.arena.dealloc(node);
+                *removed += 1;
+            } else {
+                match last {
+                    None => root = Some(node),
+                    Some(last) => self.arena.set_right(last, Some(node)),
+                }
+                self.arena.set_parent(node, last);
+                node_opt = self.arena.right(node);
+                last = Some(node);
+            }
+        }
+        if let Some(last) = last {
+            self.arena.set_right(last, None);
+        }
+        root
+    }
+
+    /// Removes the values within the end bound of `range` from `subtree`, all
+    /// of whose values are within the start bound, and returns the root of
+    /// what is left. Adds the number of nodes removed to `removed`.
+    fn prune_before_end<Q, R>(
+        &mut self,
+        subtree: Option<NodeId>,
+        range: &R,
+        removed: &mut usize,
+    ) -> Option<NodeId>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let mut root = None;
+        let mut last: Option<NodeId> = None;
+        let mut node_opt = subtree;
+
+        while let Some(node) = node_opt {
+            node_opt = self.arena.right(node);
+            if is_before_end(range, self.arena.value(node)) {
+                if let Some(left) = self.arena.left(node) {
+                    *removed += self.dealloc_subtree(left);
+                }
+                self.arena.dealloc(node);
+                *removed += 1;
+            } else {
+                match last {
+                    None => root = Some(node),
+                    Some(last) => self.arena.set_left(last, Some(node)),
+                }
+                self.arena.set_parent(node, last);
+                node_opt = self.arena.left(node);
+                last = Some(node);
+            }
+        }
+        if let Some(last) = last {
+            self.arena.set_left(last, None);
+        }
+        root
+    }
+}
+
+/// Returns `true` if `value` is not excluded by the start bound of `range`.
+fn is_after_start<T, Q, R>(range: &R, value: &T) -> bool
+where
+    T: Borrow<Q>,
+    Q: Ord + ?Sized,
+    R: RangeBounds<Q>,
+{
+    let value = value.borrow();
+    match range.start_bound() {
+        Bound::Included(start) => value >= start,
+        Bound::Excluded(start) => value > start,
+        Bound::Unbounded => true,
+    }
+}
+
+/// Returns `true` if `value` is not excluded by the end bound of `range`.
+fn is_before_end<T, Q, R>(range: &R, value: &T) -> bool
+where
+    T: Borrow<Q>,
+    Q: Ord + ?Sized,
+    R: RangeBounds<Q>,
+{
+    let value = value.borrow();
+    match range.end_bound() {
+        Bound::Included(end) => value <= end,
+        Bound::Excluded(end) => value < end,
+        Bound::Unbounded => true,
+    }
+}
+
+impl<T: PartialOrd> FromIterator<T> for ArenaBinarySearchTree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut tree = Self::new();
+        for value in iter {
+            tree.add(value);
+        }
+        tree
+    }
+}
+
+impl<'a, T: Clone> IntoIterator for &'a ArenaBinarySearchTree<T> {
+    type Item = T;
+    type IntoIter = ArenaBinarySearchTreeIterator<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> IntoIterator for ArenaBinarySearchTree<T> {
+    type Item = T;
+    type IntoIter = ArenaBinarySearchTreeIntoIterator<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let nodes: Vec<NodeId> = self.root
+            .into_iter()
+            .flat_map(|root| self.arena.in_order(root))
+            .collect();
+        ArenaBinarySearchTreeIntoIterator { arena: self.arena, nodes: nodes.into_iter() }
+    }
+}
+
+pub struct ArenaBinarySearchTreeIterator<'a, T> {
+    arena: &'a ArenaTree<T>,
+    nodes: Option<InOrder<'a, T>>,
+}
+
+impl<T: Clone> Iterator for ArenaBinarySearchTreeIterator<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.nodes.as_mut()?.next()?;
+        Some(self.arena.value(node).clone())
+    }
+}
+
+pub struct ArenaBinarySearchTreeIntoIterator<T> {
+    arena: ArenaTree<T>,
+    nodes: std::vec::IntoIter<NodeId>,
+}
+
+impl<T> Iterator for ArenaBinarySearchTreeIntoIterator<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.nodes.next()?;
+        Some(self.arena.dealloc(node))
+    }
+}
+
+pub struct ArenaBinarySearchTreeRange<'a, T> {
+    arena: &'a ArenaTree<T>,
+    next: Option<NodeId>,
+    end: Option<NodeId>,
+}
+
+impl<T: Clone> Iterator for ArenaBinarySearchTreeRange<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next.take()?;
+        if self.end == Some(node) {
+            return None;
+        }
+        self.next = self.arena.successor(node);
+        Some(self.arena.value(node).clone())
+    }
+}
+
+impl<T: Ord + Clone> SSet<T> for ArenaBinarySearchTree<T> {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn add(&mut self, x: T) -> bool {
+        self.add(x)
+    }
+
+    fn remove(&mut self, x: &T) -> bool {
+        self.remove(x)
+    }
+
+    fn find(&self, x: &T) -> Option<T> {
+        self.ceiling(x).map(|node| self.value(node).clone())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+    use rand::{Rng, SeedableRng};
+    use rand_pcg::Pcg64Mcg;
+
+    fn build_test_tree() -> ArenaBinarySearchTree<i32> {
+        [4, 0, 12, 7, 5, 9].into_iter().collect()
+    }
+
+    #[test]
+    fn add_find_remove() {
+        let mut tree = build_test_tree();
+        assert_eq!(tree.size(), 6);
+        assert!(!tree.add(7));
+        assert_eq!(*tree.value(tree.find(&9).unwrap()), 9);
+        assert!(tree.find(&1).is_none());
+
+        assert!(tree.remove(&12));
+        assert!(!tree.remove(&12));
+        assert!(tree.remove(&4));
+        tree.check_invariants();
+        assert_eq!(tree.iter().collect::<Vec<i32>>(), [0, 5, 7, 9]);
+    }
+
+    #[test]
+    fn ordered_queries() {
+        let tree = build_test_tree();
+        let values = [0, 4, 5, 7, 9, 12];
+        for (k, value) in values.into_iter().enumerate() {
+            assert_eq!(*tree.value(tree.select(k).unwrap()), value);
+            assert_eq!(tree.rank(&value), k);
+            assert_eq!(tree.floor(&value), tree.find(&value));
+            assert_eq!(tree.ceiling(&value), tree.find(&value));
+        }
+        assert_eq!(*tree.value(tree.floor(&11).unwrap()), 9);
+        assert_eq!(*tree.value(tree.ceiling(&1).unwrap()), 4);
+        assert!(tree.floor(&-1).is_none());
+        assert!(tree.ceiling(&13).is_none());
+        let mut node_opt = tree.max();
+        let mut descending = vec![];
+        while let Some(node) = node_opt {
+            descending.push(*tree.value(node));
+            node_opt = tree.predecessor(node);
+        }
+        assert_eq!(descending, [12, 9, 7, 5, 4, 0]);
+    }
+
+    #[test]
+    fn freed_slots_are_reused() {
+        let mut tree = build_test_tree();
+        tree.remove(&7);
+        tree.remove(&0);
+        tree.add(8);
+        tree.add(1);
+        assert_eq!(tree.arena.len(), 6);
+        tree.check_invariants();
+    }
+
+    #[test]
+    fn into_iter_moves_values() {
+        let tree: ArenaBinarySearchTree<String> = ["b", "c", "a"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(tree.into_iter().collect::<Vec<String>>(), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn remove_range_matches_retain() {
+        let values = [50, 25, 75, 12, 37, 62, 87, 6, 18, 31, 43, 56, 68, 81, 93];
+        for lo in (0..100).step_by(7) {
+            for hi in (lo..100).step_by(11) {
+                let mut tree: ArenaBinarySearchTree<i32> = values.into_iter().collect();
+                let mut expected: Vec<i32> = tree.iter().collect();
+                expected.retain(|x| !(lo..hi).contains(x));
+
+                assert_eq!(tree.count_range(lo..hi), values.len() - expected.len());
+                let removed = tree.remove_range(lo..hi);
+                assert_eq!(removed, values.len() - expected.len());
+                tree.check_invariants();
+                assert_eq!(tree.iter().collect::<Vec<i32>>(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_btree_set() {
+        let mut rng = Pcg64Mcg::seed_from_u64(1);
+        let mut tree = ArenaBinarySearchTree::new();
+        let mut btree = BTreeSet::new();
+        for i in 0..20000 {
+            let x = rng.random_range(0..300);
+            match rng.random_range(0..3) {
+                0 => assert_eq!(tree.add(x), btree.insert(x)),
+                1 => assert_eq!(tree.remove(&x), btree.remove(&x)),
+                _ => assert_eq!(tree.contains(&x), btree.contains(&x)),
+            }
+            assert_eq!(tree.size(), btree.len());
+            if i % 100 == 0 {
+                tree.check_invariants();
+                assert!(tree.iter().eq(btree.iter().copied()));
+            }
+        }
+        tree.check_invariants();
+        assert!(tree.iter().eq(btree.iter().copied()));
+    }
+}
@@ -0,0 +1,462 @@
+//! Index-based storage for binary tree structures.
+//!
+//! `binary_tree::RefNode` puts every node in its own `Rc<RefCell<_>>`, so that
+//! walking a tree pays for reference counting and runtime borrow checks, and
+//! values can only be read through `Ref` guards. An `ArenaTree` instead keeps
+//! all its nodes in a single `Vec`, and links them with `NodeId` handles, which
+//! are plain `u32` indices into it. The slots of removed nodes are kept in a
+//! free list and reused by the next nodes added.
+//!
+//! Like with `RefNode`, setting a child recomputes the size stored in the
+//! parent, and the trees built on an arena fix the sizes of the other ancestors
+//! as they add and remove nodes.
+
+/// A handle to a node in an `ArenaTree`. It stays valid until the node is
+/// deallocated, after which its slot may be given to another node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(u32);
+
+impl NodeId {
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[derive(Debug)]
+struct Node<T> {
+    value: T,
+    size: usize,
+    parent: Option<NodeId>,
+    left: Option<NodeId>,
+    right: Option<NodeId>,
+}
+
+/// The nodes of one or more binary trees, stored in a `Vec` and linked by
+/// `NodeId`.
+#[derive(Debug)]
+pub struct ArenaTree<T> {
+    nodes: Vec<Option<Node<T>>>,
+    free: Vec<u32>,
+}
+
+impl<T> ArenaTree<T> {
+    /// Creates a new, empty arena.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_tree::ArenaTree;
+    /// let arena: ArenaTree<i32> = ArenaTree::new();
+    /// ```
+    pub fn new() -> Self {
+        Self { nodes: Vec::new(), free: Vec::new() }
+    }
+
+    /// Returns the number of nodes allocated in `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_tree::ArenaTree;
+    /// let mut arena = ArenaTree::new();
+    /// arena.alloc(0);
+    /// assert_eq!(arena.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.nodes.len() - self.free.len()
+    }
+
+    /// Returns `true` if no node is allocated in `self`, `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_tree::ArenaTree;
+    /// let arena: ArenaTree<i32> = ArenaTree::new();
+    /// assert!(arena.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn node(&self, u: NodeId) -> &Node<T> {
+        self.nodes[u.index()].as_ref().expect("a `NodeId` should not outlive its node")
+    }
+
+    fn node_mut(&mut self, u: NodeId) -> &mut Node<T> {
+        self.nodes[u.index()].as_mut().expect("a `NodeId` should not outlive its node")
+    }
+
+    /// Allocates a new node containing `value`, with no children or ancestors,
+    /// and returns its handle. Reuses the slot of a deallocated node if there
+    /// is any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arena already holds `u32::MAX` nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_tree::ArenaTree;
+    /// let mut arena = ArenaTree::new();
+    /// let u = arena.alloc(0);
+    /// assert_eq!(*arena.value(u), 0);
+    /// ```
+    pub fn alloc(&mut self, value: T) -> NodeId {
+        let node = Some(Node { value, size: 1, parent: None, left: None, right: None });
+        match self.free.pop() {
+            Some(i) => {
+                self.nodes[i as usize] = node;
+                NodeId(i)
+            },
+            None => {
+                let i = u32::try_from(self.nodes.len())
+                    .ok()
+                    .filter(|&i| i < u32::MAX)
+                    .expect("an arena holds at most `u32::MAX` nodes");
+                self.nodes.push(node);
+                NodeId(i)
+            },
+        }
+    }
+
+    /// Deallocates the node `u` and returns its value. The node should have
+    /// been unlinked from any tree beforehand, since the links of other nodes
+    /// to it are not updated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_tree::ArenaTree;
+    /// let mut arena = ArenaTree::new();
+    /// let u = arena.alloc(0);
+    /// assert_eq!(arena.dealloc(u), 0);
+    /// assert!(arena.is_empty());
+    /// ```
+    pub fn dealloc(&mut self, u: NodeId) -> T {
+        let node = self.nodes[u.index()]
+            .take()
+            .expect("a `NodeId` should not outlive its node");
+        self.free.push(u.0);
+        node.value
+    }
+
+    /// Returns a reference to the value stored in `u`.
+    pub fn value(&self, u: NodeId) -> &T {
+        &self.node(u).value
+    }
+
+    /// Returns a mutable reference to the value stored in `u`.
+    pub fn value_mut(&mut self, u: NodeId) -> &mut T {
+        &mut self.node_mut(u).value
+    }
+
+    /// Returns the parent of `u`, `None` if there are no ancestors.
+    pub fn parent(&self, u: NodeId) -> Option<NodeId> {
+        self.node(u).parent
+    }
+
+    /// Returns the left child of `u`, `None` if there is no child.
+    pub fn left(&self, u: NodeId) -> Option<NodeId> {
+        self.node(u).left
+    }
+
+    /// Returns the right child of `u`, `None` if there is no child.
+    pub fn right(&self, u: NodeId) -> Option<NodeId> {
+        self.node(u).right
+    }
+
+    /// Sets `parent` as the new parent of `u`.
+    pub fn set_parent(&mut self, u: NodeId, parent: Option<NodeId>) {
+        self.node_mut(u).parent = parent;
+    }
+
+    /// Sets `child` as the new left child of `u`, and recomputes the size of
+    /// `u`. The sizes of the ancestors of `u` are left as they were.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_tree::ArenaTree;
+    /// let mut arena = ArenaTree::new();
+    /// let parent = arena.alloc(1);
+    /// let child = arena.alloc(0);
+    /// arena.set_left(parent, Some(child));
+    /// arena.set_parent(child, Some(parent));
+    /// assert_eq!(arena.size(parent), 2);
+    /// ```
+    pub fn set_left(&mut self, u: NodeId, child: Option<NodeId>) {
+        self.node_mut(u).left = child;
+        self.update_size(u);
+    }
+
+    /// Sets `child` as the new right child of `u`, and recomputes the size of
+    /// `u`. The sizes of the ancestors of `u` are left as they were.
+    pub fn set_right(&mut self, u: NodeId, child: Option<NodeId>) {
+        self.node_mut(u).right = child;
+        self.update_size(u);
+    }
+
+    /// Returns `true` if `u` is the left child of its parent.
+    pub fn is_left_child(&self, u: NodeId) -> bool {
+        self.parent(u).is_some_and(|p| self.left(p) == Some(u))
+    }
+
+    /// Returns the number of nodes under `u`, including itself, in *O(1)*
+    /// time.
+    pub fn size(&self, u: NodeId) -> usize {
+        self.node(u).size
+    }
+
+    fn update_size(&mut self, u: NodeId) {
+        let node = self.node(u);
+        let size = 1
+            + node.left.map_or(0, |left| self.size(left))
+            + node.right.map_or(0, |right| self.size(right));
+        self.node_mut(u).size = size;
+    }
+
+    /// Recomputes the sizes stored in `u` and in all its ancestors, after the
+    /// subtree under `u` gained or lost nodes.
+    pub fn update_sizes_to_root(&mut self, u: NodeId) {
+        let mut node_opt = Some(u);
+        while let Some(node) = node_opt {
+            self.update_size(node);
+            node_opt = self.parent(node);
+        }
+    }
+
+    /// Returns the number of nodes to reach the root from `u`.
+    pub fn depth(&self, u: NodeId) -> usize {
+        let mut depth = 0;
+        let mut node_opt = self.parent(u);
+        while let Some(node) = node_opt {
+            depth += 1;
+            node_opt = self.parent(node);
+        }
+        depth
+    }
+
+    /// Returns the number of nodes in the longest path from `u` to any of the
+    /// leaves under it. The nodes are walked with an explicit stack, so that
+    /// there is no risk of overflowing the call stack in deep trees.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_tree::ArenaTree;
+    /// let mut arena = ArenaTree::new();
+    /// let parent = arena.alloc(1);
+    /// let child = arena.alloc(0);
+    /// arena.set_left(parent, Some(child));
+    /// arena.set_parent(child, Some(parent));
+    /// assert_eq!(arena.height(parent), 2);
+    /// ```
+    pub fn height(&self, u: NodeId) -> usize {
+        let mut height = 0;
+        let mut stack = vec![(u, 1)];
+        while let Some((node, depth)) = stack.pop() {
+            height = height.max(depth);
+            stack.extend(self.left(node).map(|left| (left, depth + 1)));
+            stack.extend(self.right(node).map(|right| (right, depth + 1)));
+        }
+        height
+    }
+
+    /// Returns the node with the smallest value in the subtree under `u`.
+    pub fn leftmost(&self, u: NodeId) -> NodeId {
+        let mut node = u;
+        while let Some(left) = self.left(node) {
+            node = left;
+        }
+        node
+    }
+
+    /// Returns the node with the largest value in the subtree under `u`.
+    pub fn rightmost(&self, u: NodeId) -> NodeId {
+        let mut node = u;
+        while let Some(right) = self.right(node) {
+            node = right;
+        }
+        node
+    }
+
+    /// Returns the node that follows `u` in in-order in the whole tree it
+    /// belongs to, or `None` if it is the last one.
+    pub fn successor(&self, u: NodeId) -> Option<NodeId> {
+        if let Some(right) = self.right(u) {
+            return Some(self.leftmost(right));
+        }
+        let mut child = u;
+        while let Some(parent) = self.parent(child) {
+            if self.left(parent) == Some(child) {
+                return Some(parent);
+            }
+            child = parent;
+        }
+        None
+    }
+
+    /// Returns the node that precedes `u` in in-order in the whole tree it
+    /// belongs to, or `None` if it is the first one.
+    pub fn predecessor(&self, u: NodeId) -> Option<NodeId> {
+        if let Some(left) = self.left(u) {
+            return Some(self.rightmost(left));
+        }
+        let mut child = u;
+        while let Some(parent) = self.parent(child) {
+            if self.right(parent) == Some(child) {
+                return Some(parent);
+            }
+            child = parent;
+        }
+        None
+    }
+
+    /// Returns an iterator over the nodes under `u`, `u` included, in
+    /// in-order, following the parent links like `RefNode::in_order`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::arena_tree::ArenaTree;
+    /// let mut arena = ArenaTree::new();
+    /// let parent = arena.alloc(1);
+    /// let child = arena.alloc(0);
+    /// arena.set_left(parent, Some(child));
+    /// arena.set_parent(child, Some(parent));
+    /// let values: Vec<i32> = arena.in_order(parent).map(|u| *arena.value(u)).collect();
+    /// assert_eq!(values, [0, 1]);
+    /// ```
+    pub fn in_order(&self, u: NodeId) -> InOrder<'_, T> {
+        InOrder { tree: self, root: u, next: Some(self.leftmost(u)) }
+    }
+}
+
+impl<T> Default for ArenaTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An in-order iterator over the nodes of a subtree, created by
+/// `ArenaTree::in_order`.
+pub struct InOrder<'a, T> {
+    tree: &'a ArenaTree<T>,
+    root: NodeId,
+    next: Option<NodeId>,
+}
+
+impl<T> Iterator for InOrder<'_, T> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next.take()?;
+        self.next = if let Some(right) = self.tree.right(node) {
+            Some(self.tree.leftmost(right))
+        } else {
+            // Climbs until coming up from a left child, whose parent is next.
+            let mut child = node;
+            loop {
+                if child == self.root {
+                    break None;
+                }
+                let parent = self.tree.parent(child).expect("only the root has no parent");
+                if self.tree.left(parent) == Some(child) {
+                    break Some(parent);
+                }
+                child = parent;
+            }
+        };
+        Some(node)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds the tree of `binary_tree`'s tests:
+    ///
+    /// ```text
+    ///   a
+    ///  / \
+    /// b   c
+    ///    /
+    ///   d
+    ///  / \
+    /// e   f
+    /// ```
+    fn build_test_tree() -> (ArenaTree<char>, Vec<NodeId>) {
+        let mut arena = ArenaTree::new();
+        let ids: Vec<NodeId> = "abcdef".chars().map(|c| arena.alloc(c)).collect();
+        let [a, b, c, d, e, f] = ids[..] else { unreachable!() };
+        for (parent, left, right) in [(d, Some(e), Some(f)), (c, Some(d), None), (a, Some(b), Some(c))] {
+            arena.set_left(parent, left);
+            arena.set_right(parent, right);
+            for child in left.into_iter().chain(right) {
+                arena.set_parent(child, Some(parent));
+            }
+        }
+        (arena, ids)
+    }
+
+    #[test]
+    fn alloc_reuses_freed_slots() {
+        let mut arena = ArenaTree::new();
+        let u = arena.alloc(0);
+        let v = arena.alloc(1);
+        assert_eq!(arena.len(), 2);
+        assert_eq!(arena.dealloc(u), 0);
+        assert_eq!(arena.len(), 1);
+
+        let w = arena.alloc(2);
+        assert_eq!(w, u);
+        assert_eq!(*arena.value(w), 2);
+        assert_eq!(*arena.value(v), 1);
+        assert_eq!(arena.nodes.len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "a `NodeId` should not outlive its node")]
+    fn dealloc_invalidates_handle() {
+        let mut arena = ArenaTree::new();
+        let u = arena.alloc(0);
+        arena.dealloc(u);
+        arena.value(u);
+    }
+
+    #[test]
+    fn sizes_depths_heights() {
+        let (mut arena, ids) = build_test_tree();
+        let sizes: Vec<usize> = ids.iter().map(|&u| arena.size(u)).collect();
+        assert_eq!(sizes, [6, 1, 4, 3, 1, 1]);
+        let depths: Vec<usize> = ids.iter().map(|&u| arena.depth(u)).collect();
+        assert_eq!(depths, [0, 1, 1, 2, 3, 3]);
+        let heights: Vec<usize> = ids.iter().map(|&u| arena.height(u)).collect();
+        assert_eq!(heights, [4, 1, 3, 2, 1, 1]);
+
+        arena.set_right(ids[3], None);
+        arena.update_sizes_to_root(ids[3]);
+        assert_eq!(arena.size(ids[0]), 5);
+    }
+
+    #[test]
+    fn in_order_and_neighbours() {
+        let (arena, ids) = build_test_tree();
+        let values = |u| arena.in_order(u).map(|v| *arena.value(v)).collect::<String>();
+        assert_eq!(values(ids[0]), "baedfc");
+        assert_eq!(values(ids[2]), "edfc");
+        assert_eq!(values(ids[4]), "e");
+
+        assert_eq!(arena.successor(ids[1]), Some(ids[0]));
+        assert_eq!(arena.successor(ids[5]), Some(ids[2]));
+        assert_eq!(arena.successor(ids[2]), None);
+        assert_eq!(arena.predecessor(ids[4]), Some(ids[0]));
+        assert_eq!(arena.predecessor(ids[1]), None);
+        assert!(arena.is_left_child(ids[3]));
+        assert!(!arena.is_left_child(ids[2]));
+    }
+}
@@ -1,10 +1,12 @@
+use crate::interfaces::{Deque, List};
+
 pub struct ArrayDeque<T> {
     storage: Vec<Option<T>>,
     head: usize,
     size: usize,
 }
 
-impl<T: std::fmt::Debug> ArrayDeque<T> {
+impl<T> ArrayDeque<T> {
     pub fn initialize() -> Self {
         Self { storage: vec![None], head: 0, size: 0 }
     }
@@ -134,12 +136,59 @@ impl<T: std::fmt::Debug> ArrayDeque<T> {
 }
 
 
+impl<T> List<T> for ArrayDeque<T> {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        self.get(i)
+    }
+
+    fn set(&mut self, i: usize, x: T) -> Option<T> {
+        let y = self.get_mut(i)?;
+        Some(std::mem::replace(y, x))
+    }
+
+    fn add(&mut self, i: usize, x: T) {
+        self.add(i, x)
+    }
+
+    fn remove(&mut self, i: usize) -> Option<T> {
+        self.remove(i)
+    }
+}
+
+
+impl<T> Deque<T> for ArrayDeque<T> {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn add_first(&mut self, x: T) {
+        self.add(0, x)
+    }
+
+    fn add_last(&mut self, x: T) {
+        self.add(self.size(), x)
+    }
+
+    fn remove_first(&mut self) -> Option<T> {
+        self.remove(0)
+    }
+
+    fn remove_last(&mut self) -> Option<T> {
+        self.remove(self.size().checked_sub(1)?)
+    }
+}
+
+
 pub struct Iter<'a, T> {
     deque: &'a ArrayDeque<T>,
     index: usize,
 }
 
-impl<'a, T: std::fmt::Debug> Iterator for Iter<'a, T> {
+impl<'a, T> Iterator for Iter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -1,3 +1,5 @@
+use crate::interfaces::Queue;
+
 pub struct ArrayQueue<T> {
     storage: Vec<Option<T>>,
     head: usize,
@@ -71,6 +73,21 @@ impl<T> ArrayQueue<T> {
 }
 
 
+impl<T> Queue<T> for ArrayQueue<T> {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn add(&mut self, x: T) {
+        self.add(x)
+    }
+
+    fn remove(&mut self) -> Option<T> {
+        self.remove()
+    }
+}
+
+
 impl<T> IntoIterator for ArrayQueue<T> {
     type Item = T;
     type IntoIter = IntoIter<T>;
@@ -5,6 +5,7 @@
 //! return elements from bottom to top.
 
 use std::slice::{Iter, IterMut};
+use crate::interfaces::List;
 
 
 /// A stack backed by an array as internal storage.
@@ -85,6 +86,29 @@ impl<T> ArrayStack<T> {
 }
 
 
+impl<T> List<T> for ArrayStack<T> {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        self.get(i)
+    }
+
+    fn set(&mut self, i: usize, x: T) -> Option<T> {
+        self.set(i, x)
+    }
+
+    fn add(&mut self, i: usize, x: T) {
+        self.add(i, x)
+    }
+
+    fn remove(&mut self, i: usize) -> Option<T> {
+        self.remove(i)
+    }
+}
+
+
 impl<T> IntoIterator for ArrayStack<T> {
     type Item = T;
     type IntoIter = std::vec::IntoIter<T>;
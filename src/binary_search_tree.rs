@@ -7,8 +7,16 @@
 //! 
 //! Since this implementation features no mean to balance the trees, operations 
 //! on `BinarySearchTree` have a worse case algorithmical complexity of *O(n)*.
+//! 
+//! Like the standard collections, lookups take any borrowed form of the values, 
+//! so a tree of `String` can be searched with a `&str`.
 
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
 use crate::binary_tree::*;
+use crate::interfaces::SSet;
 
 /// A binary search tree data structure.
 pub struct BinarySearchTree<T> {
@@ -54,6 +62,137 @@ impl<T> BinarySearchTree<T> {
     pub fn is_empty(&self) -> bool {
         self.root.is_none()
     }
+
+    /// Returns a reference to the node in `self` with the smallest value, or 
+    /// `None` if `self` is empty.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_search_tree::BinarySearchTree;
+    /// let mut tree = BinarySearchTree::new();
+    /// assert!(tree.min().is_none());
+    /// tree.add(1);
+    /// tree.add(0);
+    /// assert_eq!(*tree.min().unwrap().value(), 0);
+    /// ```
+    pub fn min(&self) -> Option<RefNode<T>> {
+        self.root.as_ref().map(leftmost)
+    }
+
+    /// Returns a reference to the node in `self` with the largest value, or 
+    /// `None` if `self` is empty.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_search_tree::BinarySearchTree;
+    /// let mut tree = BinarySearchTree::new();
+    /// assert!(tree.max().is_none());
+    /// tree.add(1);
+    /// tree.add(0);
+    /// assert_eq!(*tree.max().unwrap().value(), 1);
+    /// ```
+    pub fn max(&self) -> Option<RefNode<T>> {
+        self.root.as_ref().map(rightmost)
+    }
+
+    /// Returns a reference to the node that follows `node` in the sorted order 
+    /// of `self`, or `None` if `node` holds the largest value. `node` must be 
+    /// a node of `self`, as returned by `find` or any other query.
+    /// 
+    /// Climbs through the parent links when `node` has no right subtree, so it 
+    /// takes *O(1)* amortized time when walking through the whole tree.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_search_tree::BinarySearchTree;
+    /// let mut tree = BinarySearchTree::new();
+    /// tree.add(1);
+    /// tree.add(0);
+    /// tree.add(2);
+    /// let node = tree.successor(&tree.find(&0).unwrap()).unwrap();
+    /// assert_eq!(*node.value(), 1);
+    /// assert!(tree.successor(&tree.find(&2).unwrap()).is_none());
+    /// ```
+    pub fn successor(&self, node: &RefNode<T>) -> Option<RefNode<T>> {
+        successor(node)
+    }
+
+    /// Returns a reference to the node that precedes `node` in the sorted 
+    /// order of `self`, or `None` if `node` holds the smallest value. `node` 
+    /// must be a node of `self`, as returned by `find` or any other query.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_search_tree::BinarySearchTree;
+    /// let mut tree = BinarySearchTree::new();
+    /// tree.add(1);
+    /// tree.add(0);
+    /// tree.add(2);
+    /// let node = tree.predecessor(&tree.find(&2).unwrap()).unwrap();
+    /// assert_eq!(*node.value(), 1);
+    /// assert!(tree.predecessor(&tree.find(&0).unwrap()).is_none());
+    /// ```
+    pub fn predecessor(&self, node: &RefNode<T>) -> Option<RefNode<T>> {
+        predecessor(node)
+    }
+
+    /// Returns an iterator over the values in `self`, in increasing order.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_search_tree::BinarySearchTree;
+    /// let tree: BinarySearchTree<i32> = [2, 0, 1].into_iter().collect();
+    /// assert_eq!(tree.iter().collect::<Vec<i32>>(), [0, 1, 2]);
+    /// ```
+    pub fn iter(&self) -> BinarySearchTreeIterator<'_, T> {
+        BinarySearchTreeIterator {
+            nodes: self.root.as_ref().map(RefNode::in_order),
+            tree: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the node with the `k`-th smallest value in 
+    /// `self`, counting from 0, or `None` if `k` is not less than the size of 
+    /// `self`. Uses the subtree sizes stored in the nodes to take *O(h)* time, 
+    /// where `h` is the height of the tree.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_search_tree::BinarySearchTree;
+    /// let tree: BinarySearchTree<i32> = [30, 10, 20].into_iter().collect();
+    /// assert_eq!(*tree.select(0).unwrap().value(), 10);
+    /// assert_eq!(*tree.select(2).unwrap().value(), 30);
+    /// assert!(tree.select(3).is_none());
+    /// ```
+    pub fn select(&self, k: usize) -> Option<RefNode<T>> {
+        let mut k = k;
+        let mut node_opt = self.root.clone();
+
+        while let Some(node) = node_opt {
+            let left_size = node.get_left().map_or(0, |left| left.size());
+            node_opt = if k < left_size {
+                node.get_left()
+            } else if k > left_size {
+                k -= left_size + 1;
+                node.get_right()
+            } else {
+                return Some(node);
+            };
+        }
+        None
+    }
+}
+
+impl<T> Default for BinarySearchTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T: PartialOrd> BinarySearchTree<T> {
@@ -65,42 +204,153 @@ impl<T: PartialOrd> BinarySearchTree<T> {
     /// ```
     /// # use ods::binary_search_tree::BinarySearchTree;
     /// let mut tree = BinarySearchTree::new();
-    /// assert!(tree.find(0).is_none());
+    /// assert!(tree.find(&0).is_none());
     /// tree.add(0);
-    /// assert!(tree.find(0).is_some());
+    /// assert!(tree.find(&0).is_some());
+    /// 
+    /// let mut tree = BinarySearchTree::new();
+    /// tree.add(String::from("a"));
+    /// assert!(tree.find("a").is_some());
     /// ```
-    pub fn find(&self, value: T) -> Option<RefNode<T>> {
-        let value_node = RefNode::new(value);
+    pub fn find<Q>(&self, value: &Q) -> Option<RefNode<T>> 
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
         let mut node_opt = self.root.clone();
 
-        while let Some(ref node) = node_opt  {
-            node_opt = if value_node < *node {
-                node.get_left()
-            } else if value_node > *node {
-                node.get_right()
-            } else {
-                break;
+        while let Some(node) = node_opt {
+            node_opt = match compare(value, &node) {
+                Ordering::Less => node.get_left(),
+                Ordering::Greater => node.get_right(),
+                Ordering::Equal => return Some(node),
             };
         }
-        node_opt
+        None
     }
 
-    fn find_last(&self, value_node: &RefNode<T>) -> Option<RefNode<T>> {
+    /// Returns `true` if `value` is in `self`, otherwise `false`.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_search_tree::BinarySearchTree;
+    /// let mut tree = BinarySearchTree::new();
+    /// assert!(!tree.contains(&0));
+    /// tree.add(0);
+    /// assert!(tree.contains(&0));
+    /// ```
+    pub fn contains<Q>(&self, value: &Q) -> bool 
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(value).is_some()
+    }
+
+    /// Returns the number of values in `self` that are less than `value`, 
+    /// which is the position `value` has, or would have, in the sorted order. 
+    /// Takes *O(h)* time, where `h` is the height of the tree.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_search_tree::BinarySearchTree;
+    /// let tree: BinarySearchTree<i32> = [30, 10, 20].into_iter().collect();
+    /// assert_eq!(tree.rank(&20), 1);
+    /// assert_eq!(tree.rank(&25), 2);
+    /// assert_eq!(tree.rank(&5), 0);
+    /// ```
+    pub fn rank<Q>(&self, value: &Q) -> usize 
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut rank = 0;
         let mut node_opt = self.root.clone();
-        let mut last_node = None;
 
-        while let Some(node) = node_opt  {
-            last_node = Some(RefNode::clone(&node));
+        while let Some(node) = node_opt {
+            let left_size = node.get_left().map_or(0, |left| left.size());
+            node_opt = match compare(value, &node) {
+                Ordering::Less => node.get_left(),
+                Ordering::Greater => {
+                    rank += left_size + 1;
+                    node.get_right()
+                },
+                Ordering::Equal => return rank + left_size,
+            };
+        }
+        rank
+    }
 
-            node_opt = if *value_node < node {
-                node.get_left()
-            } else if *value_node > node {
-                node.get_right()
-            } else {
-                break;
+    /// Returns a reference to the node in `self` with the largest value that 
+    /// is less than or equal to `value`, or `None` if there is no such node.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_search_tree::BinarySearchTree;
+    /// let mut tree = BinarySearchTree::new();
+    /// tree.add(0);
+    /// tree.add(10);
+    /// assert_eq!(*tree.floor(&5).unwrap().value(), 0);
+    /// assert_eq!(*tree.floor(&10).unwrap().value(), 10);
+    /// assert!(tree.floor(&-5).is_none());
+    /// ```
+    pub fn floor<Q>(&self, value: &Q) -> Option<RefNode<T>> 
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut node_opt = self.root.clone();
+        let mut candidate = None;
+
+        while let Some(node) = node_opt {
+            node_opt = match compare(value, &node) {
+                Ordering::Less => node.get_left(),
+                Ordering::Greater => {
+                    candidate = Some(RefNode::clone(&node));
+                    node.get_right()
+                },
+                Ordering::Equal => return Some(node),
             };
         }
-        last_node
+        candidate
+    }
+
+    /// Returns a reference to the node in `self` with the smallest value that 
+    /// is greater than or equal to `value`, or `None` if there is no such node.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_search_tree::BinarySearchTree;
+    /// let mut tree = BinarySearchTree::new();
+    /// tree.add(0);
+    /// tree.add(10);
+    /// assert_eq!(*tree.ceiling(&5).unwrap().value(), 10);
+    /// assert_eq!(*tree.ceiling(&0).unwrap().value(), 0);
+    /// assert!(tree.ceiling(&15).is_none());
+    /// ```
+    pub fn ceiling<Q>(&self, value: &Q) -> Option<RefNode<T>> 
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut node_opt = self.root.clone();
+        let mut candidate = None;
+
+        while let Some(node) = node_opt {
+            node_opt = match compare(value, &node) {
+                Ordering::Less => {
+                    candidate = Some(RefNode::clone(&node));
+                    node.get_left()
+                },
+                Ordering::Greater => node.get_right(),
+                Ordering::Equal => return Some(node),
+            };
+        }
+        candidate
     }
 
     /// Adds `value` to the tree `self`. Returns `false` if `value` is already 
@@ -115,28 +365,16 @@ impl<T: PartialOrd> BinarySearchTree<T> {
     /// assert!(!tree.add(0));
     /// ```
     pub fn add(&mut self, value: T) -> bool {
-        let new_node = RefNode::new(value);
-
-        match self.find_last(&new_node) {
-            None => {
-                self.root = Some(new_node);
-            },
-            Some(last_node) => {
-                if new_node < last_node {
-                    last_node.set_left(Some(&new_node));
-                } else if new_node > last_node {
-                    last_node.set_right(Some(&new_node));
-                } else {
-                    return false;
-                }
-                new_node.set_parent(Some(&last_node));
-            },
-        };
+        if add_leaf(&mut self.root, value).is_none() {
+            return false;
+        }
         self.size += 1;
         true
     }
 
-    fn remove_partially_branched(&mut self, node: RefNode<T>) {
+    /// Takes out `node`, which must have at most one child, and links its 
+    /// child in its place.
+    fn remove_partially_branched(&mut self, node: &RefNode<T>) {
         let mut child_opt = node.get_left();
         if child_opt.is_none() {
             child_opt = node.get_right();
@@ -149,13 +387,14 @@ impl<T: PartialOrd> BinarySearchTree<T> {
                 .get_parent()
                 .expect("`node` should not be a root one");
 
-            if let Some(left) = parent.get_left() {
-                if left == node {
-                    parent.set_left(child_opt.as_ref());
-                }
+            // The side must be found by identity: `node` may be the right 
+            // child of a parent that also has a left one.
+            if node.is_left_child_of(&parent) {
+                parent.set_left(child_opt.as_ref());
             } else {
                 parent.set_right(child_opt.as_ref());
             }
+            parent.update_sizes_to_root();
             Some(parent)
         };
         if let Some(child) = child_opt {
@@ -163,30 +402,19 @@ impl<T: PartialOrd> BinarySearchTree<T> {
         }
     }
 
-    fn remove_fully_branched(&self, node: RefNode<T>) {
-        let mut min_node = node
+    /// Replaces the value of `node`, which has both children, with the 
+    /// smallest value of its right subtree, and takes out the node that held 
+    /// it. That node has no left child, but it may have a right one, which 
+    /// takes its place.
+    fn remove_fully_branched(&mut self, node: &RefNode<T>) {
+        let min_node = leftmost(&node
             .get_right()
-            .expect("`node` should have both children");
-
-        let mut iterated = false;
-        while let Some(left) = min_node.get_left() {
-            min_node = left;
-            iterated = true;
-        }
-        let last = min_node
-            .get_parent()
-            .expect("`min_node` should have a parent");
-
-        if iterated {
-            last.set_left(None);
-        } else {
-            last.set_right(None);
-        }
-        let min_value = min_node
-            .into_inner_value()
-            .expect("`min_node` should have only 1 reference");
+            .expect("`node` should have both children"));
 
-        node.set(min_value);
+        // Swapping rather than moving the value out keeps this correct when
+        // a handle to `min_node` is still held elsewhere.
+        self.remove_partially_branched(&min_node);
+        node.swap(&min_node);
     }
 
     /// Removes `value` from the tree `self`. Returns `false` if `value` is not 
@@ -198,28 +426,570 @@ impl<T: PartialOrd> BinarySearchTree<T> {
     /// # use ods::binary_search_tree::BinarySearchTree;
     /// let mut tree = BinarySearchTree::new();
     /// tree.add(0);
-    /// assert!(tree.remove(0));
-    /// assert!(!tree.remove(0));
+    /// assert!(tree.remove(&0));
+    /// assert!(!tree.remove(&0));
     /// ```
-    pub fn remove(&mut self, value: T) -> bool {
+    pub fn remove<Q>(&mut self, value: &Q) -> bool 
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
         let node = match self.find(value) {
             None => return false,
             Some(node) => node,
         };
         if node.is_fully_branched() {
-            self.remove_fully_branched(node);
+            self.remove_fully_branched(&node);
         } else {
-            self.remove_partially_branched(node);
+            self.remove_partially_branched(&node);
         }
         self.size -= 1;
         true
     }
+
+    /// Checks that `self` is a valid binary search tree: its values are sorted 
+    /// with no duplicates, every child links back to its parent, the root has 
+    /// no parent, and the size stored in every node, as well as the size of 
+    /// `self`, match the number of nodes counted. The tree is walked with an 
+    /// explicit stack, so that broken parent links cannot lead it astray.
+    /// 
+    /// # Panics
+    /// 
+    /// Panics, with a message naming the broken invariant, if `self` is not 
+    /// valid.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_search_tree::BinarySearchTree;
+    /// let mut tree: BinarySearchTree<i32> = [3, 1, 4, 5, 9, 2, 6].into_iter().collect();
+    /// tree.remove(&4);
+    /// tree.check_invariants();
+    /// ```
+    pub fn check_invariants(&self) {
+        if let Some(root) = &self.root {
+            assert!(root.is_root(), "the root has a parent");
+        }
+        let mut count = 0;
+        let mut previous: Option<RefNode<T>> = None;
+        let mut stack = Vec::new();
+        let mut node_opt = self.root.clone();
+
+        while node_opt.is_some() || !stack.is_empty() {
+            while let Some(node) = node_opt {
+                node_opt = node.get_left();
+                stack.push(node);
+            }
+            let node = stack.pop().expect("the stack should not be empty");
+            count += 1;
+
+            let mut size = 1;
+            for child in [node.get_left(), node.get_right()].into_iter().flatten() {
+                assert!(
+                    child.get_parent().is_some_and(|parent| parent.ptr_eq(&node)), 
+                    "a child does not link back to its parent",
+                );
+                size += child.size();
+            }
+            assert_eq!(node.size(), size, "a node size is out of date");
+            if let Some(previous) = previous {
+                assert!(*previous.value() < *node.value(), "values are not sorted");
+            }
+            node_opt = node.get_right();
+            previous = Some(node);
+        }
+        assert_eq!(count, self.size, "the tree size is out of date");
+    }
+
+    /// Returns an iterator over the values of `self` that are within `range`, 
+    /// in increasing order. The values are found lazily, one successor at a 
+    /// time, so getting `k` of them takes *O(h + k)* time, where `h` is the 
+    /// height of the tree.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_search_tree::BinarySearchTree;
+    /// let tree: BinarySearchTree<i32> = (0..10).collect();
+    /// assert_eq!(tree.range(3..6).collect::<Vec<i32>>(), [3, 4, 5]);
+    /// assert_eq!(tree.range(..=1).collect::<Vec<i32>>(), [0, 1]);
+    /// assert_eq!(tree.range(8..).collect::<Vec<i32>>(), [8, 9]);
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> BinarySearchTreeRange<'_, T> 
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let next = self
+            .first_in_range(&range)
+            .filter(|node| is_before_end(&range, &*node.value()));
+        BinarySearchTreeRange { next, end: self.first_after_range(&range), tree: PhantomData }
+    }
+
+    /// Returns the number of values of `self` that are within `range`, in 
+    /// *O(h + k)* time, where `k` is that number.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_search_tree::BinarySearchTree;
+    /// let tree: BinarySearchTree<i32> = (0..10).collect();
+    /// assert_eq!(tree.count_range(3..6), 3);
+    /// assert_eq!(tree.count_range(20..), 0);
+    /// ```
+    pub fn count_range<Q, R>(&self, range: R) -> usize 
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let mut count = 0;
+        let mut node_opt = self.first_in_range(&range);
+
+        while let Some(node) = node_opt {
+            if !is_before_end(&range, &*node.value()) {
+                break;
+            }
+            count += 1;
+            node_opt = successor(&node);
+        }
+        count
+    }
+
+    /// Removes every value of `self` that is within `range`, and returns how 
+    /// many were removed.
+    /// 
+    /// The values in the range hang from their highest node, the split node. 
+    /// Its left subtree is pruned of the values after the start of the range, 
+    /// its right subtree of the values before the end, and both are joined in 
+    /// its place. Only the paths that lead to the bounds are walked, besides 
+    /// the `k` values removed, so it takes *O(h + k)* time.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_search_tree::BinarySearchTree;
+    /// let mut tree: BinarySearchTree<i32> = (0..10).collect();
+    /// assert_eq!(tree.remove_range(..4), 4);
+    /// assert_eq!(tree.iter().collect::<Vec<i32>>(), [4, 5, 6, 7, 8, 9]);
+    /// ```
+    pub fn remove_range<Q, R>(&mut self, range: R) -> usize 
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let mut node_opt = self.root.clone();
+        let split = loop {
+            let Some(node) = node_opt else {
+                return 0;
+            };
+            node_opt = if !is_after_start(&range, &*node.value()) {
+                node.get_right()
+            } else if !is_before_end(&range, &*node.value()) {
+                node.get_left()
+            } else {
+                break node;
+            };
+        };
+
+        let mut removed = 1;
+        let left = prune_after_start(split.get_left(), &range, &mut removed);
+        let right = prune_before_end(split.get_right(), &range, &mut removed);
+        // The sizes are stale along the spines walked by the pruning, which 
+        // all end up on the path from the deepest node of the joined spines.
+        let deepest = right.as_ref().map(leftmost).or_else(|| left.as_ref().map(rightmost));
+        let joined = match left {
+            None => right,
+            Some(left) => {
+                let last = rightmost(&left);
+                last.set_right(right.as_ref());
+                if let Some(right) = right {
+                    right.set_parent(Some(&last));
+                }
+                Some(left)
+            },
+        };
+
+        let parent_opt = split.get_parent();
+        match parent_opt {
+            None => self.root = joined.clone(),
+            Some(ref parent) => {
+                if split.is_left_child_of(parent) {
+                    parent.set_left(joined.as_ref());
+                } else {
+                    parent.set_right(joined.as_ref());
+                }
+            },
+        }
+        if let Some(joined) = joined {
+            joined.set_parent(parent_opt.as_ref());
+        }
+        if let Some(node) = deepest.or(parent_opt) {
+            node.update_sizes_to_root();
+        }
+        self.size -= removed;
+        removed
+    }
+
+    /// Returns the node with the smallest value of `self` within the start 
+    /// bound of `range`, which may be after its end bound.
+    fn first_in_range<Q, R>(&self, range: &R) -> Option<RefNode<T>> 
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let mut node_opt = self.root.clone();
+        let mut candidate = None;
+
+        while let Some(node) = node_opt {
+            node_opt = if is_after_start(range, &*node.value()) {
+                candidate = Some(RefNode::clone(&node));
+                node.get_left()
+            } else {
+                node.get_right()
+            };
+        }
+        candidate
+    }
+
+    /// Returns the node with the smallest value of `self` past the end bound 
+    /// of `range`, where iterating over the range stops.
+    fn first_after_range<Q, R>(&self, range: &R) -> Option<RefNode<T>> 
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let mut node_opt = self.root.clone();
+        let mut candidate = None;
+
+        while let Some(node) = node_opt {
+            node_opt = if is_before_end(range, &*node.value()) {
+                node.get_right()
+            } else {
+                candidate = Some(RefNode::clone(&node));
+                node.get_left()
+            };
+        }
+        candidate
+    }
+}
+
+/// Compares `value` with the value stored in `node`.
+fn compare<T: Borrow<Q>, Q: Ord + ?Sized>(value: &Q, node: &RefNode<T>) -> Ordering {
+    value.cmp((*node.value()).borrow())
+}
+
+/// Returns `true` if `value` is not excluded by the start bound of `range`.
+fn is_after_start<T, Q, R>(range: &R, value: &T) -> bool 
+where
+    T: Borrow<Q>,
+    Q: Ord + ?Sized,
+    R: RangeBounds<Q>,
+{
+    let value = value.borrow();
+    match range.start_bound() {
+        Bound::Included(start) => value >= start,
+        Bound::Excluded(start) => value > start,
+        Bound::Unbounded => true,
+    }
+}
+
+/// Returns `true` if `value` is not excluded by the end bound of `range`.
+fn is_before_end<T, Q, R>(range: &R, value: &T) -> bool 
+where
+    T: Borrow<Q>,
+    Q: Ord + ?Sized,
+    R: RangeBounds<Q>,
+{
+    let value = value.borrow();
+    match range.end_bound() {
+        Bound::Included(end) => value <= end,
+        Bound::Excluded(end) => value < end,
+        Bound::Unbounded => true,
+    }
+}
+
+/// Removes the values within the start bound of `range` from `subtree`, all 
+/// of whose values are within the end bound, and returns the root of what is 
+/// left. Adds the number of nodes removed to `removed`.
+/// 
+/// Walks down the right spine of the kept nodes: a kept node keeps its left 
+/// subtree whole, while a removed node takes its right subtree with it.
+fn prune_after_start<T, Q, R>(
+    subtree: Option<RefNode<T>>, 
+    range: &R, 
+    removed: &mut usize,
+) -> Option<RefNode<T>> 
+where
+    T: Borrow<Q>,
+    Q: Ord + ?Sized,
+    R: RangeBounds<Q>,
+{
+    let mut root = None;
+    let mut last: Option<RefNode<T>> = None;
+    let mut node_opt = subtree;
+
+    while let Some(node) = node_opt {
+        if is_after_start(range, &*node.value()) {
+            *removed += 1 + node.get_right().map_or(0, |right| right.size());
+            node_opt = node.get_left();
+        } else {
+            match last {
+                None => root = Some(RefNode::clone(&node)),
+                Some(ref last) => last.set_right(Some(&node)),
+            }
+            node.set_parent(last.as_ref());
+            node_opt = node.get_right();
+            last = Some(node);
+        }
+    }
+    if let Some(last) = last {
+        last.set_right(None);
+    }
+    root
+}
+
+/// Removes the values within the end bound of `range` from `subtree`, all of 
+/// whose values are within the start bound, and returns the root of what is 
+/// left. Adds the number of nodes removed to `removed`.
+fn prune_before_end<T, Q, R>(
+    subtree: Option<RefNode<T>>, 
+    range: &R, 
+    removed: &mut usize,
+) -> Option<RefNode<T>> 
+where
+    T: Borrow<Q>,
+    Q: Ord + ?Sized,
+    R: RangeBounds<Q>,
+{
+    let mut root = None;
+    let mut last: Option<RefNode<T>> = None;
+    let mut node_opt = subtree;
+
+    while let Some(node) = node_opt {
+        if is_before_end(range, &*node.value()) {
+            *removed += 1 + node.get_left().map_or(0, |left| left.size());
+            node_opt = node.get_right();
+        } else {
+            match last {
+                None => root = Some(RefNode::clone(&node)),
+                Some(ref last) => last.set_left(Some(&node)),
+            }
+            node.set_parent(last.as_ref());
+            node_opt = node.get_left();
+            last = Some(node);
+        }
+    }
+    if let Some(last) = last {
+        last.set_left(None);
+    }
+    root
+}
+
+/// Adds `value` as a new leaf of the tree rooted at `root`, following the 
+/// search path of `value`. Returns the new node, or `None` if `value` is 
+/// already in the tree.
+/// 
+/// This is the insertion path of `BinarySearchTree`, shared with the trees 
+/// that rebalance themselves after adding a leaf, like `ScapegoatTree`.
+pub(crate) fn add_leaf<T: PartialOrd>(
+    root: &mut Option<RefNode<T>>, 
+    value: T,
+) -> Option<RefNode<T>> {
+    match find_last(root, &value) {
+        None => {
+            let new_node = RefNode::new(value);
+            *root = Some(RefNode::clone(&new_node));
+            Some(new_node)
+        },
+        Some(last_node) => {
+            let ordering = value.partial_cmp(&last_node.value());
+            let new_node = match ordering {
+                Some(Ordering::Less) => {
+                    let new_node = RefNode::new(value);
+                    last_node.set_left(Some(&new_node));
+                    new_node
+                },
+                Some(Ordering::Greater) => {
+                    let new_node = RefNode::new(value);
+                    last_node.set_right(Some(&new_node));
+                    new_node
+                },
+                _ => return None,
+            };
+            new_node.set_parent(Some(&last_node));
+            last_node.update_sizes_to_root();
+            Some(new_node)
+        },
+    }
+}
+
+fn find_last<T: PartialOrd>(
+    root: &Option<RefNode<T>>, 
+    value: &T,
+) -> Option<RefNode<T>> {
+    let mut node_opt = root.clone();
+    let mut last_node = None;
+
+    while let Some(node) = node_opt  {
+        last_node = Some(RefNode::clone(&node));
+
+        node_opt = if *value < *node.value() {
+            node.get_left()
+        } else if *value > *node.value() {
+            node.get_right()
+        } else {
+            break;
+        };
+    }
+    last_node
+}
+
+impl<T: PartialOrd> FromIterator<T> for BinarySearchTree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut tree = Self::new();
+        for value in iter {
+            tree.add(value);
+        }
+        tree
+    }
+}
+
+impl<'a, T: Clone> IntoIterator for &'a BinarySearchTree<T> {
+    type Item = T;
+    type IntoIter = BinarySearchTreeIterator<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Clone> IntoIterator for BinarySearchTree<T> {
+    type Item = T;
+    type IntoIter = BinarySearchTreeIntoIterator<T>;
+
+    /// Unlinks every node from the tree, so that the value of each node that 
+    /// is not referenced elsewhere can be moved out. The values of nodes 
+    /// still referenced elsewhere are cloned.
+    fn into_iter(self) -> Self::IntoIter {
+        let nodes: Vec<RefNode<T>> = self.root.iter().flat_map(RefNode::in_order).collect();
+        for node in &nodes {
+            node.set_parent(None);
+            node.set_left(None);
+            node.set_right(None);
+        }
+        BinarySearchTreeIntoIterator { nodes: nodes.into_iter() }
+    }
+}
+
+/// An iterator over the values of a `BinarySearchTree`, which borrows the tree
+/// so that it can't be changed during the iteration.
+/// 
+/// ```compile_fail
+/// # use ods::binary_search_tree::BinarySearchTree;
+/// let mut tree: BinarySearchTree<i32> = (0..10).collect();
+/// for value in tree.iter() {
+///     tree.remove(&value);
+/// }
+/// ```
+pub struct BinarySearchTreeIterator<'a, T> {
+    nodes: Option<InOrder<T>>,
+    tree: PhantomData<&'a BinarySearchTree<T>>,
+}
+
+impl<T: Clone> Iterator for BinarySearchTreeIterator<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.nodes.as_mut()?.next()?;
+        Some(node.value().clone())
+    }
+}
+
+pub struct BinarySearchTreeIntoIterator<T> {
+    nodes: std::vec::IntoIter<RefNode<T>>,
+}
+
+impl<T: Clone> Iterator for BinarySearchTreeIntoIterator<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.nodes.next()?.into_value_or_clone())
+    }
+}
+
+/// An iterator over the values of a `BinarySearchTree` within a range, which 
+/// borrows the tree so that it can't be changed during the iteration.
+/// 
+/// ```compile_fail
+/// # use ods::binary_search_tree::BinarySearchTree;
+/// let mut tree: BinarySearchTree<i32> = (0..10).collect();
+/// for value in tree.range(2..8) {
+///     tree.remove(&value);
+/// }
+/// ```
+pub struct BinarySearchTreeRange<'a, T> {
+    next: Option<RefNode<T>>,
+    end: Option<RefNode<T>>,
+    tree: PhantomData<&'a BinarySearchTree<T>>,
+}
+
+impl<T: Clone> Iterator for BinarySearchTreeRange<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next.take()?;
+        if self.end.as_ref().is_some_and(|end| end.ptr_eq(&node)) {
+            return None;
+        }
+        self.next = successor(&node);
+        let value = node.value().clone();
+        Some(value)
+    }
+}
+
+impl<T: Ord + Clone> SSet<T> for BinarySearchTree<T> {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn add(&mut self, x: T) -> bool {
+        self.add(x)
+    }
+
+    fn remove(&mut self, x: &T) -> bool {
+        self.remove(x)
+    }
+
+    fn find(&self, x: &T) -> Option<T> {
+        self.ceiling(x).map(|node| node.value().clone())
+    }
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::BTreeSet;
+    use rand::{Rng, SeedableRng};
+    use rand_pcg::Pcg64Mcg;
+
+    /// Links the values `0..n` into a single right spine, the shape that adding 
+    /// them in increasing order gives, without the quadratic time it takes.
+    fn build_degenerate_tree(n: usize) -> BinarySearchTree<usize> {
+        let mut root = RefNode::new(n - 1);
+        for value in (0..n - 1).rev() {
+            let node = RefNode::new(value);
+            node.set_right(Some(&root));
+            root.set_parent(Some(&node));
+            root = node;
+        }
+        BinarySearchTree { root: Some(root), size: n }
+    }
 
     fn build_test_tree() -> BinarySearchTree<i32> {
         let root = RefNode::new(4);
@@ -240,32 +1010,93 @@ mod tests {
         rll.set_parent(Some(&rl));
         rlr.set_parent(Some(&rl));
 
+        rll.update_sizes_to_root();
+
         BinarySearchTree { root: Some(root), size: 6 }
     }
 
     #[test]
     fn find_empty_returns_none() {
-        let tree = BinarySearchTree { root: None, size: 0 };
-        assert!(tree.find(1).is_none());
+        let tree: BinarySearchTree<i32> = BinarySearchTree { root: None, size: 0 };
+        assert!(tree.find(&1).is_none());
     }
 
     #[test]
     fn find_non_emtpy_returns_some() {
         let tree = build_test_tree();
-        assert_eq!(tree.find(4).unwrap(), RefNode::new(4));
-        assert_eq!(tree.find(0).unwrap(), RefNode::new(0));
-        assert_eq!(tree.find(12).unwrap(), RefNode::new(12));
-        assert_eq!(tree.find(7).unwrap(), RefNode::new(7));
-        assert_eq!(tree.find(5).unwrap(), RefNode::new(5));
-        assert_eq!(tree.find(9).unwrap(), RefNode::new(9));
+        assert_eq!(tree.find(&4).unwrap(), RefNode::new(4));
+        assert_eq!(tree.find(&0).unwrap(), RefNode::new(0));
+        assert_eq!(tree.find(&12).unwrap(), RefNode::new(12));
+        assert_eq!(tree.find(&7).unwrap(), RefNode::new(7));
+        assert_eq!(tree.find(&5).unwrap(), RefNode::new(5));
+        assert_eq!(tree.find(&9).unwrap(), RefNode::new(9));
     }
 
     #[test]
     fn find_non_empty_returns_none() {
         let tree = build_test_tree();
-        assert!(tree.find(-1).is_none());
-        assert!(tree.find(101).is_none());
-        assert!(tree.find(1).is_none());
+        assert!(tree.find(&-1).is_none());
+        assert!(tree.find(&101).is_none());
+        assert!(tree.find(&1).is_none());
+    }
+
+    #[test]
+    fn lookups_take_borrowed_values() {
+        let mut tree: BinarySearchTree<String> = ["kiwi", "apple", "pear"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert!(tree.contains("kiwi"));
+        assert!(!tree.contains("plum"));
+        assert_eq!(*tree.find("apple").unwrap().value(), "apple");
+        assert_eq!(*tree.floor("orange").unwrap().value(), "kiwi");
+        assert_eq!(*tree.ceiling("orange").unwrap().value(), "pear");
+        let fruits_b_to_m = (Bound::Included("b"), Bound::Excluded("m"));
+        assert_eq!(tree.count_range::<str, _>(fruits_b_to_m), 1);
+
+        assert!(tree.remove("kiwi"));
+        assert!(!tree.remove("kiwi"));
+        assert_eq!(tree.iter().collect::<Vec<String>>(), ["apple", "pear"]);
+    }
+
+    /// Checks that the size stored in every node under `node` matches the 
+    /// number of nodes in its subtree, and returns that number.
+    fn check_sizes<T>(node_opt: Option<RefNode<T>>) -> usize {
+        let Some(node) = node_opt else { return 0 };
+        let size = 1 + check_sizes(node.get_left()) + check_sizes(node.get_right());
+        assert_eq!(node.size(), size);
+        size
+    }
+
+    #[test]
+    fn select_rank() {
+        let tree = build_test_tree();
+        let values = [0, 4, 5, 7, 9, 12];
+        for (k, value) in values.into_iter().enumerate() {
+            assert_eq!(*tree.select(k).unwrap().value(), value);
+            assert_eq!(tree.rank(&value), k);
+            assert_eq!(tree.rank(&(value + 1)), k + 1);
+        }
+        assert!(tree.select(6).is_none());
+        assert_eq!(tree.rank(&-1), 0);
+    }
+
+    #[test]
+    fn sizes_follow_updates() {
+        let mut tree = build_test_tree();
+        assert_eq!(check_sizes(tree.root.clone()), 6);
+        for x in [8, 6, 15, -3] {
+            tree.add(x);
+            assert_eq!(check_sizes(tree.root.clone()), tree.size());
+        }
+        for x in [12, 0, 7, 4] {
+            tree.remove(&x);
+            assert_eq!(check_sizes(tree.root.clone()), tree.size());
+        }
+        tree.remove_range(5..9);
+        assert_eq!(check_sizes(tree.root.clone()), tree.size());
+        assert_eq!(tree.iter().collect::<Vec<i32>>(), [-3, 9, 15]);
+        assert_eq!(*tree.select(1).unwrap().value(), 9);
     }
 
     #[test]
@@ -314,41 +1145,278 @@ mod tests {
         assert!(leaf.get_parent().is_some());
     }
 
+    #[test]
+    fn min_max() {
+        let tree = build_test_tree();
+        assert_eq!(tree.min().unwrap(), RefNode::new(0));
+        assert_eq!(tree.max().unwrap(), RefNode::new(12));
+
+        let empty: BinarySearchTree<i32> = BinarySearchTree::new();
+        assert!(empty.min().is_none());
+        assert!(empty.max().is_none());
+    }
+
+    #[test]
+    fn floor_ceiling() {
+        let tree = build_test_tree();
+        for (value, floor, ceiling) in [
+            (-1, None, Some(0)),
+            (0, Some(0), Some(0)),
+            (3, Some(0), Some(4)),
+            (6, Some(5), Some(7)),
+            (8, Some(7), Some(9)),
+            (10, Some(9), Some(12)),
+            (13, Some(12), None),
+        ] {
+            assert_eq!(tree.floor(&value).map(|node| *node.value()), floor);
+            assert_eq!(tree.ceiling(&value).map(|node| *node.value()), ceiling);
+        }
+    }
+
+    #[test]
+    fn successor_predecessor_walk_in_order() {
+        let tree = build_test_tree();
+        let mut ascending = vec![];
+        let mut node_opt = tree.min();
+        while let Some(node) = node_opt {
+            ascending.push(*node.value());
+            node_opt = tree.successor(&node);
+        }
+        assert_eq!(ascending, [0, 4, 5, 7, 9, 12]);
+
+        let mut descending = vec![];
+        let mut node_opt = tree.max();
+        while let Some(node) = node_opt {
+            descending.push(*node.value());
+            node_opt = tree.predecessor(&node);
+        }
+        assert_eq!(descending, [12, 9, 7, 5, 4, 0]);
+    }
+
+    #[test]
+    fn iter_is_sorted() {
+        let tree = build_test_tree();
+        assert_eq!(tree.iter().collect::<Vec<i32>>(), [0, 4, 5, 7, 9, 12]);
+        assert_eq!((&tree).into_iter().count(), 6);
+
+        let empty: BinarySearchTree<i32> = BinarySearchTree::new();
+        assert_eq!(empty.iter().next(), None);
+    }
+
+    #[test]
+    fn from_iter_into_iter() {
+        let tree: BinarySearchTree<String> = ["b", "c", "a", "b"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(tree.size(), 3);
+        assert_eq!(tree.into_iter().collect::<Vec<String>>(), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn into_iter_clones_values_of_held_nodes() {
+        let tree: BinarySearchTree<String> = ["b", "c", "a"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let held = tree.find("b").unwrap();
+        assert_eq!(tree.into_iter().collect::<Vec<String>>(), ["a", "b", "c"]);
+        assert_eq!(*held.value(), "b");
+    }
+
+    #[test]
+    fn range_bounds() {
+        let tree = build_test_tree();
+        let range = |r: (Bound<i32>, Bound<i32>)| tree.range(r).collect::<Vec<i32>>();
+        assert_eq!(range((Bound::Included(4), Bound::Excluded(9))), [4, 5, 7]);
+        assert_eq!(range((Bound::Excluded(4), Bound::Included(9))), [5, 7, 9]);
+        assert_eq!(range((Bound::Unbounded, Bound::Excluded(5))), [0, 4]);
+        assert_eq!(range((Bound::Excluded(9), Bound::Unbounded)), [12]);
+        assert_eq!(range((Bound::Unbounded, Bound::Unbounded)), [0, 4, 5, 7, 9, 12]);
+        assert_eq!(range((Bound::Included(1), Bound::Included(3))), []);
+        assert_eq!(range((Bound::Included(13), Bound::Unbounded)), []);
+        assert_eq!(tree.count_range(1..=9), 4);
+        assert_eq!(tree.count_range(..), 6);
+    }
+
+    #[test]
+    fn remove_range_matches_retain() {
+        let values = [50, 25, 75, 12, 37, 62, 87, 6, 18, 31, 43, 56, 68, 81, 93];
+        for lo in (0..100).step_by(7) {
+            for hi in (lo..100).step_by(11) {
+                let mut tree: BinarySearchTree<i32> = values.into_iter().collect();
+                let mut expected: Vec<i32> = tree.iter().collect();
+                expected.retain(|x| !(lo..hi).contains(x));
+
+                let removed = tree.remove_range(lo..hi);
+                assert_eq!(removed, values.len() - expected.len());
+                assert_eq!(check_sizes(tree.root.clone()), expected.len());
+                assert_eq!(tree.size(), expected.len());
+                assert_eq!(tree.iter().collect::<Vec<i32>>(), expected);
+                // The parent links must have followed the relinking.
+                let mut from_max = vec![];
+                let mut node_opt = tree.max();
+                while let Some(node) = node_opt {
+                    from_max.push(*node.value());
+                    node_opt = tree.predecessor(&node);
+                }
+                from_max.reverse();
+                assert_eq!(from_max, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn remove_range_everything() {
+        let mut tree = build_test_tree();
+        assert_eq!(tree.remove_range(..), 6);
+        assert!(tree.is_empty());
+        assert_eq!(tree.remove_range(..), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "values are not sorted")]
+    fn check_invariants_catches_unsorted_values() {
+        let tree = build_test_tree();
+        tree.find(&5).unwrap().set(8);
+        tree.check_invariants();
+    }
+
+    #[test]
+    #[should_panic(expected = "a child does not link back to its parent")]
+    fn check_invariants_catches_broken_parent_link() {
+        let tree = build_test_tree();
+        tree.find(&9).unwrap().set_parent(None);
+        tree.check_invariants();
+    }
+
+    #[test]
+    fn remove_right_child_of_parent_with_two_children() {
+        // 7 is the right child of 4, which also has a left child, and has a 
+        // single child itself.
+        let mut tree: BinarySearchTree<i32> = [4, 2, 7, 9].into_iter().collect();
+        assert!(tree.remove(&7));
+        tree.check_invariants();
+        assert_eq!(tree.iter().collect::<Vec<i32>>(), [2, 4, 9]);
+    }
+
+    #[test]
+    fn remove_keeps_right_subtree_of_min_node() {
+        // The smallest value right of 4 is 5, whose right subtree holds 6.
+        let mut tree: BinarySearchTree<i32> = [4, 2, 8, 5, 6, 9].into_iter().collect();
+        assert!(tree.remove(&4));
+        tree.check_invariants();
+        assert_eq!(tree.iter().collect::<Vec<i32>>(), [2, 5, 6, 8, 9]);
+
+        // The smallest value right of 5 is its right child 8 itself.
+        let mut tree: BinarySearchTree<i32> = [5, 2, 8, 9].into_iter().collect();
+        assert!(tree.remove(&5));
+        tree.check_invariants();
+        assert_eq!(tree.iter().collect::<Vec<i32>>(), [2, 8, 9]);
+    }
+
+    #[test]
+    fn matches_btree_set() {
+        let mut rng = Pcg64Mcg::seed_from_u64(1);
+        let mut tree = BinarySearchTree::new();
+        let mut btree = BTreeSet::new();
+        for i in 0..20000 {
+            let x = rng.random_range(0..300);
+            match rng.random_range(0..3) {
+                0 => assert_eq!(tree.add(x), btree.insert(x)),
+                1 => assert_eq!(tree.remove(&x), btree.remove(&x)),
+                _ => assert_eq!(tree.contains(&x), btree.contains(&x)),
+            }
+            assert_eq!(tree.size(), btree.len());
+            if i % 100 == 0 {
+                tree.check_invariants();
+                assert!(tree.iter().eq(btree.iter().copied()));
+            }
+        }
+        tree.check_invariants();
+        assert!(tree.iter().eq(btree.iter().copied()));
+    }
+
     #[test]
     fn remove_returns_outcome() {
         let mut tree = build_test_tree();
-        assert!(tree.remove(0));
-        assert!(!tree.remove(0));
-        assert!(tree.remove(12));
-        assert!(!tree.remove(12));
-        assert!(tree.remove(7));
-        assert!(!tree.remove(7));
+        assert!(tree.remove(&0));
+        assert!(!tree.remove(&0));
+        assert!(tree.remove(&12));
+        assert!(!tree.remove(&12));
+        assert!(tree.remove(&7));
+        assert!(!tree.remove(&7));
     }
 
     #[test]
     fn remove_keeps_track_of_size() {
         let mut tree = build_test_tree();
         assert_eq!(tree.size(), 6);
-        tree.remove(0);
+        tree.remove(&0);
         assert_eq!(tree.size(), 5);
-        tree.remove(12);
+        tree.remove(&12);
         assert_eq!(tree.size(), 4);
-        tree.remove(12);
+        tree.remove(&12);
         assert_eq!(tree.size(), 4);
-        tree.remove(7);
+        tree.remove(&7);
         assert_eq!(tree.size(), 3);
     }
 
     #[test]
     fn remove_takes_value_out_of_tree() {
         let mut tree = build_test_tree();
-        tree.remove(0);
+        tree.remove(&0);
         assert!(tree.root.clone().unwrap().get_left().is_none());
-        tree.remove(12);
+        tree.remove(&12);
         assert_eq!(tree.root.clone().unwrap().get_right().unwrap(), RefNode::new(7));
-        tree.remove(7);
+        tree.remove(&7);
         assert_eq!(tree.root.clone().unwrap().get_right().unwrap(), RefNode::new(9));
-        tree.remove(4);
+        tree.remove(&4);
         assert_eq!(tree.root.clone().unwrap(), RefNode::new(9));
     }
+
+    #[test]
+    fn remove_while_holding_min_node() {
+        let mut tree: BinarySearchTree<i32> = [4, 2, 6, 5, 7].into_iter().collect();
+        let held = tree.find(&5).unwrap();
+        assert!(tree.remove(&4));
+        tree.check_invariants();
+        assert_eq!(tree.iter().collect::<Vec<i32>>(), [2, 5, 6, 7]);
+        drop(held);
+        assert!(tree.remove(&5));
+        assert_eq!(tree.iter().collect::<Vec<i32>>(), [2, 6, 7]);
+    }
+
+    #[test]
+    fn degenerate_tree_queries_and_drop() {
+        const N: usize = 1_000_000;
+        let tree = build_degenerate_tree(N);
+        tree.check_invariants();
+        let root = tree.root.clone().unwrap();
+        assert_eq!(root.size(), N);
+        assert_eq!(root.height(), N);
+        assert_eq!(*tree.select(N - 1).unwrap().value(), N - 1);
+        assert_eq!(tree.rank(&N), N);
+        drop(root);
+
+        // A node returned by a query outlives the tree, with its subtree.
+        let middle = tree.select(N / 2).unwrap();
+        drop(tree);
+        assert!(middle.get_parent().is_none());
+        assert_eq!(middle.size(), N - N / 2);
+        assert_eq!(middle.in_order().count(), N - N / 2);
+    }
+
+    #[test]
+    fn degenerate_tree_remove_range() {
+        const N: usize = 1_000_000;
+        let mut tree = build_degenerate_tree(N);
+        assert_eq!(tree.remove_range(10..N - 10), N - 20);
+        tree.check_invariants();
+        assert_eq!(tree.iter().collect::<Vec<usize>>().len(), 20);
+
+        let mut tree = build_degenerate_tree(N);
+        assert_eq!(tree.remove_range(..N - 1), N - 1);
+        assert_eq!(tree.iter().collect::<Vec<usize>>(), [N - 1]);
+    }
 }
@@ -4,14 +4,30 @@
 //! `Rc` references to its children. However, all the public funcionality is done 
 //! through `RefNode<T>`, which are a wrapper around the well known 
 //! `Rc<RefCell<Node<T>>>` structures.
+//! 
+//! Every node stores the size of its subtree, so that `RefNode::size` takes 
+//! *O(1)* time. Setting a child recomputes the size of the parent from its 
+//! children, and the trees built on these nodes fix the sizes of the other 
+//! ancestors with `RefNode::update_sizes_to_root` as they add and remove nodes.
+//! 
+//! The subtree under any `RefNode` can be walked in in-order, pre-order, 
+//! post-order or level-order. The first three follow the parent links instead 
+//! of keeping a stack, like the book's `traverse2`, so they use *O(1)* extra 
+//! space however deep the tree is.
+//! 
+//! Dropping the last reference to a node frees its subtree iteratively, so even 
+//! a degenerate tree does not overflow the stack. Nodes that are referenced 
+//! from elsewhere survive, and keep their whole subtree.
 
+use std::collections::VecDeque;
 use std::rc::{Rc, Weak};
-use std::cell::RefCell;
+use std::cell::{Ref, RefCell};
 
 
 #[derive(Debug)]
 struct Node<T> {
     value: T,
+    size: usize,
     parent: WeakRefNode<T>,
     left: Option<RefNode<T>>,
     right: Option<RefNode<T>>,
@@ -19,7 +35,7 @@ struct Node<T> {
 
 impl<T> Node<T> {
     fn new(value: T) -> Node<T> {
-        Self { value, parent: WeakRefNode::new(), left: None, right: None }
+        Self { value, size: 1, parent: WeakRefNode::new(), left: None, right: None }
     }
 }
 
@@ -134,10 +150,11 @@ impl<T> RefNode<T> {
     /// assert!(node.get_left().is_none());
     /// ```
     pub fn get_left(&self) -> Option<RefNode<T>> {
-        self.0.borrow().left.as_ref().map(RefNode::clone)
+        self.0.borrow().left.clone()
     }
 
-    /// Sets `node` as the new left child.
+    /// Sets `node` as the new left child, and recomputes the size of `self`. 
+    /// The sizes of the ancestors of `self` are left as they were.
     /// 
     /// # Examples
     /// 
@@ -148,7 +165,8 @@ impl<T> RefNode<T> {
     /// parent_node.set_left(Some(&child_node));
     /// ```
     pub fn set_left(&self, node: Option<&RefNode<T>>) {
-        self.0.borrow_mut().left = node.map(RefNode::clone);
+        self.0.borrow_mut().left = node.cloned();
+        self.update_size();
     }
 
     /// Returns a reference to the right node, `None` if there is no child.
@@ -161,10 +179,11 @@ impl<T> RefNode<T> {
     /// assert!(node.get_right().is_none());
     /// ```
     pub fn get_right(&self) -> Option<RefNode<T>> {
-        self.0.borrow().right.as_ref().map(RefNode::clone)
+        self.0.borrow().right.clone()
     }
 
-    /// Sets `node` as the new right child.
+    /// Sets `node` as the new right child, and recomputes the size of `self`. 
+    /// The sizes of the ancestors of `self` are left as they were.
     /// 
     /// # Examples
     /// 
@@ -175,7 +194,45 @@ impl<T> RefNode<T> {
     /// parent_node.set_right(Some(&child_node));
     /// ```
     pub fn set_right(&self, node: Option<&RefNode<T>>) {
-        self.0.borrow_mut().right = node.map(RefNode::clone);
+        self.0.borrow_mut().right = node.cloned();
+        self.update_size();
+    }
+
+    /// Recomputes the size stored in `self` from the sizes of its children.
+    fn update_size(&self) {
+        let size = 1 
+            + self.get_left().map_or(0, |left| left.size()) 
+            + self.get_right().map_or(0, |right| right.size());
+        self.0.borrow_mut().size = size;
+    }
+
+    /// Recomputes the sizes stored in `self` and in all its ancestors, after 
+    /// the subtree under `self` gained or lost nodes. The parent links must be 
+    /// set, since this climbs through them.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_tree::RefNode;
+    /// let root = RefNode::new(0);
+    /// let child = RefNode::new(1);
+    /// let grandchild = RefNode::new(2);
+    /// 
+    /// root.set_left(Some(&child));
+    /// child.set_parent(Some(&root));
+    /// child.set_left(Some(&grandchild));
+    /// grandchild.set_parent(Some(&child));
+    /// assert_eq!(root.size(), 2);
+    /// 
+    /// child.update_sizes_to_root();
+    /// assert_eq!(root.size(), 3);
+    /// ```
+    pub fn update_sizes_to_root(&self) {
+        let mut node_opt = Some(RefNode::clone(self));
+        while let Some(node) = node_opt {
+            node.update_size();
+            node_opt = node.get_parent();
+        }
     }
 
     /// Returns the value stored in the node if `self` is the only reference to it,
@@ -190,7 +247,71 @@ impl<T> RefNode<T> {
     /// assert_eq!(value, 0);
     /// ```
     pub fn into_inner_value(self) -> Option<T> {
-        Some(Rc::into_inner(self.0)?.into_inner().value)
+        // `RefNode` implements `Drop`, so its `Rc` can't be moved out of it. 
+        // This clone keeps the node alive while `self` goes away.
+        let rc = Rc::clone(&self.0);
+        drop(self);
+        Some(Rc::into_inner(rc)?.into_inner().value)
+    }
+
+    /// Returns the value of `self`, moved out of the node if `self` is its last
+    /// reference, or cloned otherwise.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_tree::RefNode;
+    /// let node = RefNode::new(0);
+    /// let other = node.clone();
+    /// assert_eq!(node.into_value_or_clone(), 0);
+    /// assert_eq!(other.into_value_or_clone(), 0);
+    /// ```
+    pub fn into_value_or_clone(self) -> T
+    where
+        T: Clone,
+    {
+        let rc = Rc::clone(&self.0);
+        drop(self);
+        match Rc::try_unwrap(rc) {
+            Ok(cell) => cell.into_inner().value,
+            Err(rc) => rc.borrow().value.clone(),
+        }
+    }
+
+    /// Swaps the values stored in `self` and `other`, which must be different
+    /// nodes.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_tree::RefNode;
+    /// let node = RefNode::new(0);
+    /// let other = RefNode::new(1);
+    /// node.swap(&other);
+    /// assert_eq!(*node.value(), 1);
+    /// assert_eq!(*other.value(), 0);
+    /// ```
+    pub fn swap(&self, other: &RefNode<T>) {
+        std::mem::swap(&mut self.0.borrow_mut().value, &mut other.0.borrow_mut().value);
+    }
+
+    /// Returns `true` if `self` and `other` reference the same node, which may 
+    /// not be the case for nodes with equal values.
+    pub(crate) fn ptr_eq(&self, other: &RefNode<T>) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Returns a shared reference to the value stored in the node.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_tree::RefNode;
+    /// let node = RefNode::new(0);
+    /// assert_eq!(*node.value(), 0);
+    /// ```
+    pub fn value(&self) -> Ref<'_, T> {
+        Ref::map(self.0.borrow(), |node| &node.value)
     }
 
     /// Sets the value stored in the node. 
@@ -231,7 +352,14 @@ impl<T> RefNode<T> {
         depth
     }
 
-    /// Returns the number of nodes under `self`, including itself.
+    /// Returns the number of nodes under `self`, including itself. The size is 
+    /// stored in the node, so this takes *O(1)* time.
+    /// 
+    /// The stored size is only kept up to date by `set_left` and `set_right` 
+    /// on the node they are called on. Linking a subtree below a node that 
+    /// already has a parent leaves the sizes of its ancestors stale, until 
+    /// `update_sizes_to_root` is called on that node. Building a tree bottom 
+    /// up, children before their parents, keeps every size right.
     /// 
     /// # Examples
     /// 
@@ -249,26 +377,44 @@ impl<T> RefNode<T> {
     /// assert_eq!(parent.size(), 3);
     /// ```
     pub fn size(&self) -> usize {
-        let mut size = 0;
-        let mut nodes = vec![RefNode::clone(&self)];
+        self.0.borrow().size
+    }
 
-        while !nodes.is_empty() {
-            size += 1;
-            let node = nodes.remove(0);
+    /// Returns the number of nodes in the longest path from `self` to any of 
+    /// the leafs under it. The subtree is walked with an explicit stack, so 
+    /// even a degenerate tree as deep as it is large does not overflow the 
+    /// call stack.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_tree::RefNode;
+    /// let parent = RefNode::new(0);
+    /// let left = RefNode::new(-1);
+    /// let right = RefNode::new(1);
+    /// 
+    /// parent.set_left(Some(&left));
+    /// parent.set_right(Some(&right));
+    /// left.set_parent(Some(&parent));
+    /// right.set_parent(Some(&parent));
+    /// 
+    /// assert_eq!(parent.height(), 2);
+    /// ```
+    pub fn height(&self) -> usize {
+        let mut height = 0;
+        let mut stack = vec![(RefNode::clone(self), 1)];
 
-            if let Some(left_node) = node.get_left() {
-                nodes.push(left_node);
-            }
-            if let Some(right_node) = node.get_right() {
-                nodes.push(right_node);
-            }
+        while let Some((node, depth)) = stack.pop() {
+            height = usize::max(height, depth);
+            stack.extend(node.get_left().map(|left| (left, depth + 1)));
+            stack.extend(node.get_right().map(|right| (right, depth + 1)));
         }
-        size
+        height
     }
 
-    /// Returns the maximum distance from `self` to any of the leafs under it. 
-    /// This implementation is recursive, and therefore there is a risk of panic 
-    /// if the tree is too large.
+    /// Returns an iterator over the nodes under `self`, `self` included, in 
+    /// in-order: every node comes after its left subtree and before its right 
+    /// subtree. In a binary search tree, that is the sorted order.
     /// 
     /// # Examples
     /// 
@@ -283,19 +429,102 @@ impl<T> RefNode<T> {
     /// left.set_parent(Some(&parent));
     /// right.set_parent(Some(&parent));
     /// 
-    /// assert_eq!(parent.height(), 2);
+    /// let values: Vec<i32> = parent.in_order().map(|node| *node.value()).collect();
+    /// assert_eq!(values, [-1, 0, 1]);
     /// ```
-    pub fn height(&self) -> usize {
-        fn recurse<T>(node_opt: Option<RefNode<T>>) -> usize {
-            match node_opt {
-                None => 0,
-                Some(node) => 1 + usize::max(
-                    recurse(node.get_left()), 
-                    recurse(node.get_right()),
-                )
-            }
+    pub fn in_order(&self) -> InOrder<T> {
+        InOrder { root: RefNode::clone(self), next: Some(leftmost(self)) }
+    }
+
+    /// Returns an iterator over the nodes under `self`, `self` included, in 
+    /// pre-order: every node comes before its left subtree, which comes before 
+    /// its right subtree.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_tree::RefNode;
+    /// let parent = RefNode::new(0);
+    /// let left = RefNode::new(-1);
+    /// let right = RefNode::new(1);
+    /// 
+    /// parent.set_left(Some(&left));
+    /// parent.set_right(Some(&right));
+    /// left.set_parent(Some(&parent));
+    /// right.set_parent(Some(&parent));
+    /// 
+    /// let values: Vec<i32> = parent.pre_order().map(|node| *node.value()).collect();
+    /// assert_eq!(values, [0, -1, 1]);
+    /// ```
+    pub fn pre_order(&self) -> PreOrder<T> {
+        PreOrder { root: RefNode::clone(self), next: Some(RefNode::clone(self)) }
+    }
+
+    /// Returns an iterator over the nodes under `self`, `self` included, in 
+    /// post-order: every node comes after its left subtree, which comes after 
+    /// its right subtree.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_tree::RefNode;
+    /// let parent = RefNode::new(0);
+    /// let left = RefNode::new(-1);
+    /// let right = RefNode::new(1);
+    /// 
+    /// parent.set_left(Some(&left));
+    /// parent.set_right(Some(&right));
+    /// left.set_parent(Some(&parent));
+    /// right.set_parent(Some(&parent));
+    /// 
+    /// let values: Vec<i32> = parent.post_order().map(|node| *node.value()).collect();
+    /// assert_eq!(values, [-1, 1, 0]);
+    /// ```
+    pub fn post_order(&self) -> PostOrder<T> {
+        PostOrder { root: RefNode::clone(self), next: Some(deepest_first(self)) }
+    }
+
+    /// Returns an iterator over the nodes under `self`, `self` included, in 
+    /// level-order: by increasing depth, and from left to right within a 
+    /// level. Unlike the other traversals, it keeps a queue of the nodes to 
+    /// visit, which holds up to a whole level of the tree.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use ods::binary_tree::RefNode;
+    /// let parent = RefNode::new(0);
+    /// let left = RefNode::new(-1);
+    /// let left_left = RefNode::new(-2);
+    /// let right = RefNode::new(1);
+    /// 
+    /// parent.set_left(Some(&left));
+    /// parent.set_right(Some(&right));
+    /// left.set_left(Some(&left_left));
+    /// left.set_parent(Some(&parent));
+    /// right.set_parent(Some(&parent));
+    /// left_left.set_parent(Some(&left));
+    /// 
+    /// let values: Vec<i32> = parent.level_order().map(|node| *node.value()).collect();
+    /// assert_eq!(values, [0, -1, 1, -2]);
+    /// ```
+    pub fn level_order(&self) -> LevelOrder<T> {
+        LevelOrder { queue: VecDeque::from([RefNode::clone(self)]) }
+    }
+
+    /// Moves the children of the node into `stack` if `self` is its last 
+    /// reference, so that dropping `self` drops no subtree along with it.
+    fn take_children_if_last(&self, stack: &mut Vec<RefNode<T>>) {
+        if Rc::strong_count(&self.0) == 1 {
+            let mut node = self.0.borrow_mut();
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
         }
-        recurse(Some(self).cloned())
+    }
+
+    /// Returns `true` if `self` is the left child of `parent`.
+    pub(crate) fn is_left_child_of(&self, parent: &RefNode<T>) -> bool {
+        parent.get_left().is_some_and(|left| left.ptr_eq(self))
     }
 }
 
@@ -305,6 +534,188 @@ impl<T> Clone for RefNode<T> {
     }
 }
 
+impl<T> Drop for RefNode<T> {
+    /// Frees the node if `self` is its last reference, along with every node 
+    /// under it that is referenced by nothing else, one node at a time. The 
+    /// default drop of nested `Rc` references would recurse once per level, 
+    /// and a degenerate tree is deep enough to overflow the stack that way.
+    /// Nodes still referenced elsewhere are left whole, with their subtrees.
+    fn drop(&mut self) {
+        let mut stack = vec![];
+        self.take_children_if_last(&mut stack);
+        while let Some(node) = stack.pop() {
+            node.take_children_if_last(&mut stack);
+        }
+    }
+}
+
+/// Returns the node with the smallest value in the subtree under `node`.
+pub(crate) fn leftmost<T>(node: &RefNode<T>) -> RefNode<T> {
+    let mut node = RefNode::clone(node);
+    while let Some(left) = node.get_left() {
+        node = left;
+    }
+    node
+}
+
+/// Returns the node with the largest value in the subtree under `node`.
+pub(crate) fn rightmost<T>(node: &RefNode<T>) -> RefNode<T> {
+    let mut node = RefNode::clone(node);
+    while let Some(right) = node.get_right() {
+        node = right;
+    }
+    node
+}
+
+/// Returns the node that follows `node` in in-order in the whole tree it 
+/// belongs to, or `None` if it is the last one.
+pub(crate) fn successor<T>(node: &RefNode<T>) -> Option<RefNode<T>> {
+    if let Some(right) = node.get_right() {
+        return Some(leftmost(&right));
+    }
+    let mut child = RefNode::clone(node);
+    while let Some(parent) = child.get_parent() {
+        if child.is_left_child_of(&parent) {
+            return Some(parent);
+        }
+        child = parent;
+    }
+    None
+}
+
+/// Returns the node that precedes `node` in in-order in the whole tree it 
+/// belongs to, or `None` if it is the first one.
+pub(crate) fn predecessor<T>(node: &RefNode<T>) -> Option<RefNode<T>> {
+    if let Some(left) = node.get_left() {
+        return Some(rightmost(&left));
+    }
+    let mut child = RefNode::clone(node);
+    while let Some(parent) = child.get_parent() {
+        if parent.get_right().is_some_and(|right| right.ptr_eq(&child)) {
+            return Some(parent);
+        }
+        child = parent;
+    }
+    None
+}
+
+/// Returns the first node of the subtree under `node` in post-order, reached 
+/// by going left whenever possible, and right otherwise.
+fn deepest_first<T>(node: &RefNode<T>) -> RefNode<T> {
+    let mut node = RefNode::clone(node);
+    while let Some(child) = node.get_left().or_else(|| node.get_right()) {
+        node = child;
+    }
+    node
+}
+
+
+/// An in-order iterator over the nodes of a subtree, created by 
+/// `RefNode::in_order`.
+pub struct InOrder<T> {
+    root: RefNode<T>,
+    next: Option<RefNode<T>>,
+}
+
+impl<T> Iterator for InOrder<T> {
+    type Item = RefNode<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next.take()?;
+        self.next = if let Some(right) = node.get_right() {
+            Some(leftmost(&right))
+        } else {
+            // Climbs until coming up from a left child, whose parent is next.
+            let mut child = RefNode::clone(&node);
+            loop {
+                if child.ptr_eq(&self.root) {
+                    break None;
+                }
+                let parent = child.get_parent().expect("only the root has no parent");
+                if child.is_left_child_of(&parent) {
+                    break Some(parent);
+                }
+                child = parent;
+            }
+        };
+        Some(node)
+    }
+}
+
+/// A pre-order iterator over the nodes of a subtree, created by 
+/// `RefNode::pre_order`.
+pub struct PreOrder<T> {
+    root: RefNode<T>,
+    next: Option<RefNode<T>>,
+}
+
+impl<T> Iterator for PreOrder<T> {
+    type Item = RefNode<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next.take()?;
+        self.next = if let Some(child) = node.get_left().or_else(|| node.get_right()) {
+            Some(child)
+        } else {
+            // Climbs until coming up from a left child whose sibling is next.
+            let mut child = RefNode::clone(&node);
+            loop {
+                if child.ptr_eq(&self.root) {
+                    break None;
+                }
+                let parent = child.get_parent().expect("only the root has no parent");
+                if child.is_left_child_of(&parent) && let Some(right) = parent.get_right() {
+                    break Some(right);
+                }
+                child = parent;
+            }
+        };
+        Some(node)
+    }
+}
+
+/// A post-order iterator over the nodes of a subtree, created by 
+/// `RefNode::post_order`.
+pub struct PostOrder<T> {
+    root: RefNode<T>,
+    next: Option<RefNode<T>>,
+}
+
+impl<T> Iterator for PostOrder<T> {
+    type Item = RefNode<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next.take()?;
+        if !node.ptr_eq(&self.root) {
+            // Coming up from a left child, the right sibling's subtree goes 
+            // first, otherwise the parent is next.
+            let parent = node.get_parent().expect("only the root has no parent");
+            self.next = match parent.get_right() {
+                Some(right) if node.is_left_child_of(&parent) => Some(deepest_first(&right)),
+                _ => Some(parent),
+            };
+        }
+        Some(node)
+    }
+}
+
+/// A level-order iterator over the nodes of a subtree, created by 
+/// `RefNode::level_order`.
+pub struct LevelOrder<T> {
+    queue: VecDeque<RefNode<T>>,
+}
+
+impl<T> Iterator for LevelOrder<T> {
+    type Item = RefNode<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.queue.pop_front()?;
+        self.queue.extend(node.get_left());
+        self.queue.extend(node.get_right());
+        Some(node)
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -313,7 +724,7 @@ mod tests {
 
     fn build_test_node<T>(value: T) -> RefNode<T> {
         RefNode(Rc::new(RefCell::new(
-            Node { value, parent: WeakRefNode(Weak::new()), left: None, right: None }
+            Node { value, size: 1, parent: WeakRefNode(Weak::new()), left: None, right: None }
         )))
     }
 
@@ -340,6 +751,10 @@ mod tests {
         rll.0.borrow_mut().parent = WeakRefNode(Rc::downgrade(&rl.0));
         rlr.0.borrow_mut().parent = WeakRefNode(Rc::downgrade(&rl.0));
 
+        for node in [&rl, &r, &root] {
+            node.update_size();
+        }
+
         let mut nodes = HashMap::new();
         nodes.insert(String::from(""), root);
         nodes.insert(String::from("R"), r);
@@ -404,6 +819,68 @@ mod tests {
         assert_eq!(nodes["RLR"].size(), 1);
     }
 
+    #[test]
+    fn set_child_updates_size() {
+        let nodes = build_test_nodes();
+        let leaf = build_test_node('g');
+        nodes["L"].set_left(Some(&leaf));
+        leaf.set_parent(Some(&nodes["L"]));
+        assert_eq!(nodes["L"].size(), 2);
+        assert_eq!(nodes[""].size(), 6);
+
+        nodes["L"].update_sizes_to_root();
+        assert_eq!(nodes[""].size(), 7);
+
+        nodes["RL"].set_right(None);
+        nodes["RL"].update_sizes_to_root();
+        assert_eq!(nodes["RL"].size(), 2);
+        assert_eq!(nodes["R"].size(), 3);
+        assert_eq!(nodes[""].size(), 6);
+    }
+
+    fn values(nodes: impl Iterator<Item = RefNode<char>>) -> String {
+        nodes.map(|node| *node.value()).collect()
+    }
+
+    #[test]
+    fn traversals_whole_tree() {
+        let nodes = build_test_nodes();
+        assert_eq!(values(nodes[""].in_order()), "baedfc");
+        assert_eq!(values(nodes[""].pre_order()), "abcdef");
+        assert_eq!(values(nodes[""].post_order()), "befdca");
+        assert_eq!(values(nodes[""].level_order()), "abcdef");
+    }
+
+    #[test]
+    fn traversals_stay_in_subtree() {
+        let nodes = build_test_nodes();
+        assert_eq!(values(nodes["R"].in_order()), "edfc");
+        assert_eq!(values(nodes["R"].pre_order()), "cdef");
+        assert_eq!(values(nodes["R"].post_order()), "efdc");
+        assert_eq!(values(nodes["R"].level_order()), "cdef");
+
+        for node in [&nodes["L"], &nodes["RLL"], &nodes["RLR"]] {
+            let value = node.value().to_string();
+            assert_eq!(values(node.in_order()), value);
+            assert_eq!(values(node.pre_order()), value);
+            assert_eq!(values(node.post_order()), value);
+            assert_eq!(values(node.level_order()), value);
+        }
+    }
+
+    #[test]
+    fn traversals_right_spine() {
+        let nodes: Vec<RefNode<char>> = "abcd".chars().map(build_test_node).collect();
+        for pair in nodes.windows(2) {
+            pair[0].set_right(Some(&pair[1]));
+            pair[1].set_parent(Some(&pair[0]));
+        }
+        assert_eq!(values(nodes[0].in_order()), "abcd");
+        assert_eq!(values(nodes[0].pre_order()), "abcd");
+        assert_eq!(values(nodes[0].post_order()), "dcba");
+        assert_eq!(values(nodes[0].level_order()), "abcd");
+    }
+
     #[test]
     fn height_returns() {
         let nodes = build_test_nodes();
@@ -414,4 +891,33 @@ mod tests {
         assert_eq!(nodes["RLL"].height(), 1);
         assert_eq!(nodes["RLR"].height(), 1);
     }
+
+    #[test]
+    fn deep_right_spine() {
+        const N: usize = 1_000_000;
+        let leaf = RefNode::new(N - 1);
+        let mut root = RefNode::clone(&leaf);
+        for value in (0..N - 1).rev() {
+            let node = RefNode::new(value);
+            node.set_right(Some(&root));
+            root.set_parent(Some(&node));
+            root = node;
+        }
+        assert_eq!(root.size(), N);
+        assert_eq!(root.height(), N);
+        assert_eq!(leaf.depth(), N - 1);
+        assert_eq!(root.in_order().count(), N);
+
+        // Dropping the root frees the nodes above `middle`, which keeps its 
+        // own subtree, and dropping `middle` frees the rest.
+        let mut middle = RefNode::clone(&root);
+        for _ in 0..N / 2 {
+            middle = middle.get_right().unwrap();
+        }
+        drop(root);
+        assert!(middle.get_parent().is_none());
+        assert_eq!(middle.in_order().count(), N - N / 2);
+        drop(middle);
+        assert!(leaf.get_parent().is_none());
+    }
 }
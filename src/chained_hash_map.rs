@@ -0,0 +1,447 @@
+//! A key-value map backed by a hash table with chaining.
+//!
+//! It shares its table with `ChainedHashTable`: every slot of the table stores
+//! a list of the key-value pairs whose keys hash to it, and the table doubles or
+//! halves its number of slots to keep the lists short.
+
+use std::borrow::Borrow;
+use std::hash::Hash;
+use std::mem;
+use std::ops::Index;
+use crate::chained_hash_table::Chains;
+use crate::hashers;
+
+
+/// A key-value map backed by a hash table with chaining.
+#[derive(Debug, Clone)]
+pub struct ChainedHashMap<K, V, H: hashers::DimHasher> {
+    chains: Chains<K, (K, V), H>,
+}
+
+impl<K: Hash + Eq, V, H: hashers::DimHasher> ChainedHashMap<K, V, H> {
+    /// Creates a new, empty map with `2^dim` slots. Panics if `dim` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::chained_hash_map::ChainedHashMap;
+    /// # use ods::hashers::Multiplicative;
+    /// let map: ChainedHashMap<u64, char, _> = ChainedHashMap::initialize(1, Multiplicative::new());
+    /// ```
+    pub fn initialize(dim: u32, hasher: H) -> Self {
+        assert!(dim > 0, "ChainedHashMap dimension must be greater than 0");
+        Self { chains: Chains::initialize(dim, hasher) }
+    }
+
+    /// Returns the number of key-value pairs in the map.
+    pub fn len(&self) -> usize {
+        self.chains.len()
+    }
+
+    /// Returns `true` if the map has no key-value pairs, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the slot of the table that `key` belongs to.
+    pub fn hash<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Hash + ?Sized,
+    {
+        self.chains.hash(key)
+    }
+
+    /// Returns `true` if the map has a value for `key`, `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::chained_hash_map::ChainedHashMap;
+    /// # use ods::hashers::Multiplicative;
+    /// let mut map = ChainedHashMap::initialize(1, Multiplicative::new());
+    /// map.insert(String::from("a"), 1);
+    /// assert!(map.contains_key("a"));
+    /// assert!(!map.contains_key("b"));
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Returns a shared reference to the value of `key`, or `None` if `key` is
+    /// not in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::chained_hash_map::ChainedHashMap;
+    /// # use ods::hashers::Multiplicative;
+    /// let mut map = ChainedHashMap::initialize(1, Multiplicative::new());
+    /// map.insert(0, 'a');
+    /// assert_eq!(map.get(&0), Some(&'a'));
+    /// assert_eq!(map.get(&1), None);
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.chains.find(key).map(|(_, value)| value)
+    }
+
+    /// Returns a mutable reference to the value of `key`, or `None` if `key` is
+    /// not in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::chained_hash_map::ChainedHashMap;
+    /// # use ods::hashers::Multiplicative;
+    /// let mut map = ChainedHashMap::initialize(1, Multiplicative::new());
+    /// map.insert(0, 'a');
+    /// *map.get_mut(&0).unwrap() = 'b';
+    /// assert_eq!(map.get(&0), Some(&'b'));
+    /// ```
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.chains.find_mut(key).map(|(_, value)| value)
+    }
+
+    /// Inserts `value` for `key` and returns the value that `key` had before,
+    /// or `None` if `key` was not in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::chained_hash_map::ChainedHashMap;
+    /// # use ods::hashers::Multiplicative;
+    /// let mut map = ChainedHashMap::initialize(1, Multiplicative::new());
+    /// assert_eq!(map.insert(0, 'a'), None);
+    /// assert_eq!(map.insert(0, 'b'), Some('a'));
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            },
+        }
+    }
+
+    /// Removes `key` from the map and returns its value, or `None` if `key`
+    /// was not in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::chained_hash_map::ChainedHashMap;
+    /// # use ods::hashers::Multiplicative;
+    /// let mut map = ChainedHashMap::initialize(1, Multiplicative::new());
+    /// map.insert(0, 'a');
+    /// assert_eq!(map.remove(&0), Some('a'));
+    /// assert_eq!(map.remove(&0), None);
+    /// ```
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (i, j) = self.chains.position(key)?;
+        Some(self.chains.remove_at(i, j).1)
+    }
+
+    /// Returns the entry of `key` in the map, to inspect or modify it in place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::chained_hash_map::ChainedHashMap;
+    /// # use ods::hashers::Multiplicative;
+    /// let mut map = ChainedHashMap::initialize(1, Multiplicative::new());
+    /// for word in ["a", "b", "a"] {
+    ///     *map.entry(word).or_insert(0) += 1;
+    /// }
+    /// assert_eq!(map[&"a"], 2);
+    /// assert_eq!(map[&"b"], 1);
+    /// ```
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, H> {
+        match self.chains.position(&key) {
+            Some((i, j)) => Entry::Occupied(OccupiedEntry { map: self, i, j }),
+            None => Entry::Vacant(VacantEntry { map: self, key }),
+        }
+    }
+}
+
+impl<K, Q, V, H> Index<&Q> for ChainedHashMap<K, V, H>
+where
+    K: Hash + Eq + Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+    H: hashers::DimHasher,
+{
+    type Output = V;
+
+    /// Returns a shared reference to the value of `key`. Panics if `key` is not
+    /// in the map.
+    fn index(&self, key: &Q) -> &Self::Output {
+        self.get(key).expect("key should be in the map")
+    }
+}
+
+
+/// The entry of a key in a `ChainedHashMap`, which may or may not hold a value.
+pub enum Entry<'a, K, V, H: hashers::DimHasher> {
+    Occupied(OccupiedEntry<'a, K, V, H>),
+    Vacant(VacantEntry<'a, K, V, H>),
+}
+
+impl<'a, K: Hash + Eq, V, H: hashers::DimHasher> Entry<'a, K, V, H> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Returns a mutable reference to the value of the entry, inserting
+    /// `default` first if the entry is vacant.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Returns a mutable reference to the value of the entry, inserting the
+    /// result of `f` first if the entry is vacant.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, f: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(f()),
+        }
+    }
+
+    /// Returns a mutable reference to the value of the entry, inserting
+    /// `V::default()` first if the entry is vacant.
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Calls `f` with the value of the entry if it is occupied, and returns the
+    /// entry.
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+
+/// An entry of a `ChainedHashMap` that holds a value.
+pub struct OccupiedEntry<'a, K, V, H: hashers::DimHasher> {
+    map: &'a mut ChainedHashMap<K, V, H>,
+    i: usize,
+    j: usize,
+}
+
+impl<'a, K: Hash + Eq, V, H: hashers::DimHasher> OccupiedEntry<'a, K, V, H> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        &self.map.chains.table[self.i][self.j].0
+    }
+
+    /// Returns a shared reference to the value of the entry.
+    pub fn get(&self) -> &V {
+        &self.map.chains.table[self.i][self.j].1
+    }
+
+    /// Returns a mutable reference to the value of the entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.chains.table[self.i][self.j].1
+    }
+
+    /// Returns a mutable reference to the value of the entry, bound to the
+    /// lifetime of the map.
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.chains.table[self.i][self.j].1
+    }
+
+    /// Replaces the value of the entry with `value` and returns the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the entry from the map and returns its value.
+    pub fn remove(self) -> V {
+        self.map.chains.remove_at(self.i, self.j).1
+    }
+}
+
+
+/// An entry of a `ChainedHashMap` that holds no value.
+pub struct VacantEntry<'a, K, V, H: hashers::DimHasher> {
+    map: &'a mut ChainedHashMap<K, V, H>,
+    key: K,
+}
+
+impl<'a, K: Hash + Eq, V, H: hashers::DimHasher> VacantEntry<'a, K, V, H> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Inserts `value` for the key of the entry and returns a mutable
+    /// reference to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let (i, j) = self.map.chains.push((self.key, value));
+        &mut self.map.chains.table[i][j].1
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use rand::{Rng, SeedableRng};
+    use rand_pcg::Pcg64Mcg;
+
+    fn build_test_map() -> ChainedHashMap<u64, char, hashers::Multiplicative> {
+        let mut map = ChainedHashMap::initialize(1, hashers::Multiplicative::with_seed(73));
+        for (key, value) in [(3, 'c'), (1, 'a'), (2, 'b'), (18446744073709551615, 'z')] {
+            map.insert(key, value);
+        }
+        map
+    }
+
+    #[test]
+    fn initialize() {
+        let map: ChainedHashMap<u64, (), _> =
+            ChainedHashMap::initialize(3, hashers::Multiplicative::new());
+        assert_eq!(map.chains.dim, 3);
+        assert_eq!(map.chains.table.len(), 8);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn initialize_dim_zero() {
+        let _: ChainedHashMap<u64, (), _> =
+            ChainedHashMap::initialize(0, hashers::Multiplicative::new());
+    }
+
+    #[test]
+    fn insert_returns_old_value() {
+        let mut map = build_test_map();
+        assert_eq!(map.len(), 4);
+        assert_eq!(map.insert(1, 'x'), Some('a'));
+        assert_eq!(map.insert(4, 'd'), None);
+        assert_eq!(map.len(), 5);
+        assert_eq!(map.get(&1), Some(&'x'));
+    }
+
+    #[test]
+    fn get_and_get_mut() {
+        let mut map = build_test_map();
+        assert_eq!(map.get(&18446744073709551615), Some(&'z'));
+        assert_eq!(map.get(&0), None);
+        map.get_mut(&2).unwrap().make_ascii_uppercase();
+        assert_eq!(map.get(&2), Some(&'B'));
+        assert!(map.get_mut(&0).is_none());
+    }
+
+    #[test]
+    fn remove_returns_value() {
+        let mut map = build_test_map();
+        assert_eq!(map.remove(&3), Some('c'));
+        assert_eq!(map.remove(&3), None);
+        assert_eq!(map.len(), 3);
+        assert!(!map.contains_key(&3));
+    }
+
+    #[test]
+    fn index_returns_value() {
+        let map = build_test_map();
+        assert_eq!(map[&1], 'a');
+        assert_eq!(map[&3], 'c');
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_missing_key_panics() {
+        let map = build_test_map();
+        let _ = map[&0];
+    }
+
+    #[test]
+    fn entry_api() {
+        let mut map = build_test_map();
+        assert_eq!(map.entry(1).key(), &1);
+        assert_eq!(*map.entry(1).or_insert('x'), 'a');
+        assert_eq!(*map.entry(5).or_insert('e'), 'e');
+        map.entry(5).and_modify(|v| *v = 'E').or_insert('x');
+        assert_eq!(map[&5], 'E');
+
+        match map.entry(2) {
+            Entry::Occupied(entry) => assert_eq!(entry.remove(), 'b'),
+            Entry::Vacant(_) => panic!("2 should be in the map"),
+        }
+        assert!(!map.contains_key(&2));
+        assert_eq!(map.len(), 4);
+
+        let mut counts: ChainedHashMap<char, usize, _> =
+            ChainedHashMap::initialize(1, hashers::Multiplicative::with_seed(5));
+        for c in "abracadabra".chars() {
+            *counts.entry(c).or_default() += 1;
+        }
+        assert_eq!(counts[&'a'], 5);
+        assert_eq!(counts[&'r'], 2);
+        assert_eq!(counts.len(), 5);
+    }
+
+    #[test]
+    fn resize_keeps_invariants() {
+        let mut map = ChainedHashMap::initialize(1, hashers::Multiplicative::with_seed(11));
+        for key in 0..1000u64 {
+            map.insert(key, key * 2);
+            // The invariant is restored before each push, so it may be off by one.
+            assert!(map.len() <= map.chains.table.len() + 1);
+        }
+        assert_eq!(map.chains.table.len(), 1024);
+        for key in 0..990u64 {
+            assert_eq!(map.remove(&key), Some(key * 2));
+            assert!(!map.chains.table_is_very_long());
+        }
+        assert_eq!(map.chains.table.len(), 16);
+    }
+
+    #[test]
+    fn matches_std_hash_map() {
+        let mut rng = Pcg64Mcg::seed_from_u64(31);
+        let mut map = ChainedHashMap::initialize(1, hashers::Tabulation::with_seed(4, 31));
+        let mut std_map = HashMap::new();
+
+        for _ in 0..5000 {
+            let key = rng.random_range(0..200u32).to_string();
+            let value = rng.random::<u8>();
+            if rng.random_bool(0.5) {
+                assert_eq!(map.insert(key.clone(), value), std_map.insert(key, value));
+            } else {
+                assert_eq!(map.remove(key.as_str()), std_map.remove(key.as_str()));
+            }
+            assert_eq!(map.len(), std_map.len());
+        }
+        for (key, value) in std_map.iter() {
+            assert_eq!(map.get(key.as_str()), Some(value));
+        }
+    }
+}
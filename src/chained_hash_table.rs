@@ -1,13 +1,37 @@
-use std::mem;
-use crate::hashers;
+//! A hash table that stores colliding keys in per-slot lists, or chains.
+//! 
+//! Keys can be of any type that implements `Hash` and `Eq`. Their `Hash` output 
+//! is first folded into a `u64` by `hashers::fold`, and then reduced to a slot 
+//! by the `DimHasher` of the table.
+//!
+//! The chains themselves, and the logic that resizes the table to keep them
+//! short, live in `Chains`, which `ChainedHashMap` shares to store key-value
+//! pairs.
+
+use std::borrow::Borrow;
+use std::hash::Hash;
+use std::iter::Flatten;
+use std::marker::PhantomData;
+use std::{mem, slice, vec};
+use crate::hashers::{self, Keyed};
+use crate::interfaces::USet;
 
 
 #[derive(Debug, Clone)]
-pub struct ChainedHashTable<H: hashers::DimHasher> {
-    dim: u32,
-    table: Vec<Vec<u64>>,
+pub struct ChainedHashTable<K, H: hashers::DimHasher> {
+    chains: Chains<K, K, H>,
+}
+
+/// The slots of a chained hash table, each with the list of the items `E` whose
+/// keys `K` hash to it. Doubles or halves its number of slots to keep the lists
+/// short.
+#[derive(Debug, Clone)]
+pub(crate) struct Chains<K, E, H: hashers::DimHasher> {
+    pub(crate) dim: u32,
+    pub(crate) table: Vec<Vec<E>>,
     hasher: H,
-    len: usize
+    len: usize,
+    key: PhantomData<K>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -16,57 +40,210 @@ pub enum Error {
     KeyNotFound,
 }
 
-impl<H: hashers::DimHasher> ChainedHashTable<H> {
+impl<K: Hash + Eq, H: hashers::DimHasher> ChainedHashTable<K, H> {
     pub fn initialize(dim: u32, hasher: H) -> Self {
         assert!(dim > 0, "ChainedHashTable dimension must be greater than 0");
-        let table = Self::new_table(dim);
-        Self { dim, table, hasher, len: 0 }
+        Self { chains: Chains::initialize(dim, hasher) }
     }
 
     pub fn len(&self) -> usize {
-        self.len
+        self.chains.len()
     }
 
-    pub fn hash(&self, x: u64) -> usize {
-        let y = self.hasher.hash(x, self.dim);
-        y.try_into().expect("Unable to cast x's u64 into usize")
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
-    pub fn contains(&self, x: u64) -> bool {
-        let i = self.hash(x);
-        let row = &self.table[i];
-        row.iter().any(|y| *y == x)
+    /// Returns the slot of the table that `x` belongs to.
+    /// 
+    /// `x` may be any borrowed form of the key type, as long as its `Hash` 
+    /// implementation matches the key's one, like `&str` for `String` keys.
+    pub fn hash<Q>(&self, x: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Hash + ?Sized,
+    {
+        self.chains.hash(x)
+    }
+
+    pub fn contains<Q>(&self, x: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.chains.find(x).is_some()
     }
 
-    pub fn add(&mut self, x: u64) -> Result<(), Error> {
-        if self.contains(x) {
+    pub fn add(&mut self, x: K) -> Result<(), Error> {
+        if self.contains(&x) {
             Err(Error::KeyAlreadyExists)
         }
         else {
-            if !self.size_invarian_holds() {
-                self.resize(self.dim + 1);
-            }
-            let i = self.hash(x);
-            self.table[i].push(x);
-            self.len += 1;
+            self.chains.push(x);
             Ok(())
         }
     }
 
-    pub fn remove(&mut self, x: u64) -> Result<(), Error> {
+    pub fn remove<Q>(&mut self, x: &Q) -> Result<(), Error>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (i, j) = self.chains.position(x).ok_or(Error::KeyNotFound)?;
+        self.chains.remove_at(i, j);
+        Ok(())
+    }
+
+    /// Returns an iterator over the keys of the table, in no particular order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::chained_hash_table::ChainedHashTable;
+    /// # use ods::hashers::Multiplicative;
+    /// let cht: ChainedHashTable<u64, Multiplicative> = (0..4).collect();
+    /// let mut keys = cht.iter().copied().collect::<Vec<u64>>();
+    /// keys.sort();
+    /// assert_eq!(keys, [0, 1, 2, 3]);
+    /// ```
+    pub fn iter(&self) -> ChainedHashTableIterator<'_, K> {
+        ChainedHashTableIterator { rows: self.chains.table.iter().flatten() }
+    }
+
+    /// Removes every key from the table and returns an iterator over them. The
+    /// table is left empty, with its initial dimension of 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::chained_hash_table::ChainedHashTable;
+    /// # use ods::hashers::Multiplicative;
+    /// let mut cht: ChainedHashTable<u64, Multiplicative> = (0..4).collect();
+    /// assert_eq!(cht.drain().sum::<u64>(), 6);
+    /// assert!(cht.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> ChainedHashTableIntoIterator<K> {
+        let table = self.chains.take_table();
+        ChainedHashTableIntoIterator { rows: table.into_iter().flatten() }
+    }
+
+    /// Keeps only the keys for which `f` returns `true`, and shrinks the table
+    /// afterwards if it became too long for the keys left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::chained_hash_table::ChainedHashTable;
+    /// # use ods::hashers::Multiplicative;
+    /// let mut cht: ChainedHashTable<u64, Multiplicative> = (0..10).collect();
+    /// cht.retain(|x| x % 3 == 0);
+    /// assert_eq!(cht.len(), 4);
+    /// assert!(cht.contains(&9));
+    /// assert!(!cht.contains(&8));
+    /// ```
+    pub fn retain<F: FnMut(&K) -> bool>(&mut self, f: F) {
+        self.chains.retain(f);
+    }
+}
+
+impl<K: Hash + Eq, E: Keyed<K>, H: hashers::DimHasher> Chains<K, E, H> {
+    /// Creates empty chains in `2^dim` slots.
+    pub(crate) fn initialize(dim: u32, hasher: H) -> Self {
+        Self { dim, table: Self::new_table(dim), hasher, len: 0, key: PhantomData }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the slot of the table that `x` belongs to.
+    pub(crate) fn hash<Q>(&self, x: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Hash + ?Sized,
+    {
+        let y = self.hasher.hash(hashers::fold(x), self.dim);
+        y.try_into().expect("Unable to cast x's u64 into usize")
+    }
+
+    /// Returns the slot and the index in its chain of the item with key `x`,
+    /// or `None` if there is no such item.
+    pub(crate) fn position<Q>(&self, x: &Q) -> Option<(usize, usize)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
         let i = self.hash(x);
+        let j = self.table[i].iter().position(|y| y.key().borrow() == x)?;
+        Some((i, j))
+    }
+
+    /// Returns a shared reference to the item with key `x`, or `None` if
+    /// there is no such item.
+    pub(crate) fn find<Q>(&self, x: &Q) -> Option<&E>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (i, j) = self.position(x)?;
+        Some(&self.table[i][j])
+    }
 
-        let j = self.table[i].iter()
-            .position(|y| *y == x)
-            .ok_or(Error::KeyNotFound)?;
+    /// Returns a mutable reference to the item with key `x`, or `None` if
+    /// there is no such item.
+    pub(crate) fn find_mut<Q>(&mut self, x: &Q) -> Option<&mut E>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (i, j) = self.position(x)?;
+        Some(&mut self.table[i][j])
+    }
+
+    /// Adds `x`, whose key must not be in the table yet, growing the table
+    /// first if needed. Returns the slot and the index in its chain of `x`.
+    pub(crate) fn push(&mut self, x: E) -> (usize, usize) {
+        if !self.size_invariant_holds() {
+            self.resize(self.dim + 1);
+        }
+        let i = self.hash(x.key());
+        self.table[i].push(x);
+        self.len += 1;
+        (i, self.table[i].len() - 1)
+    }
 
-        self.table[i].remove(j);
+    /// Removes and returns the item at index `j` of the chain in slot `i`,
+    /// shrinking the table afterwards if needed.
+    pub(crate) fn remove_at(&mut self, i: usize, j: usize) -> E {
+        let x = self.table[i].swap_remove(j);
         self.len -= 1;
 
         if self.table_is_very_long() {
             self.resize(self.dim - 1);
         }
-        Ok(())
+        x
+    }
+
+    /// Keeps only the items for which `f` returns `true`.
+    pub(crate) fn retain<F: FnMut(&E) -> bool>(&mut self, mut f: F) {
+        for row in self.table.iter_mut() {
+            row.retain(&mut f);
+        }
+        self.len = self.table.iter().map(Vec::len).sum();
+
+        // Each halving rehashes a table half as long as the previous one, so 
+        // all of them together take less time than the first.
+        while self.table_is_very_long() {
+            self.resize(self.dim - 1);
+        }
+    }
+
+    /// Empties the table, leaving it with its initial dimension of 1, and
+    /// returns its previous slots.
+    pub(crate) fn take_table(&mut self) -> Vec<Vec<E>> {
+        self.dim = 1;
+        self.len = 0;
+        mem::replace(&mut self.table, Self::new_table(1))
     }
 
     fn resize(&mut self, to_dim: u32) {
@@ -76,32 +253,114 @@ impl<H: hashers::DimHasher> ChainedHashTable<H> {
 
         for row in table {
             for x in row {
-                let i = self.hash(x);
+                let i = self.hash(x.key());
                 self.table[i].push(x);
             }
         }
     }
 
-    fn new_table(dim: u32) -> Vec<Vec<u64>> {
-        vec![vec![]; 2usize.pow(dim)]
+    fn new_table(dim: u32) -> Vec<Vec<E>> {
+        (0..2usize.pow(dim)).map(|_| Vec::new()).collect()
     }
 
-    fn size_invarian_holds(&self) -> bool {
+    fn size_invariant_holds(&self) -> bool {
         self.len() <= self.table.len()
     }
 
-    fn table_is_very_long(&self) -> bool {
+    pub(crate) fn table_is_very_long(&self) -> bool {
         self.dim > 1 && self.len() * 3 < self.table.len()
     }
 }
 
-impl<H: hashers::DimHasher> PartialEq for ChainedHashTable<H> {
+impl<K, H> USet<K> for ChainedHashTable<K, H>
+where
+    K: Hash + Eq + Clone,
+    H: hashers::DimHasher,
+{
+    fn size(&self) -> usize {
+        self.len()
+    }
+
+    fn add(&mut self, x: K) -> bool {
+        self.add(x).is_ok()
+    }
+
+    fn remove(&mut self, x: &K) -> bool {
+        self.remove(x).is_ok()
+    }
+
+    fn find(&self, x: &K) -> Option<K> {
+        self.chains.find(x).cloned()
+    }
+}
+
+impl<K: Hash + Eq, H: hashers::DimHasher> PartialEq for ChainedHashTable<K, H> {
     fn eq(&self, other: &Self) -> bool {
-        let is_in_other = |row: &Vec<u64>| row
-            .iter()
-            .all(|x| other.contains(*x));
+        self.len() == other.len() && self.iter().all(|x| other.contains(x))
+    }
+}
 
-        self.len() == other.len() && self.table.iter().all(is_in_other)
+impl<K, H> FromIterator<K> for ChainedHashTable<K, H>
+where
+    K: Hash + Eq,
+    H: hashers::DimHasher + Default,
+{
+    /// Builds a table with the default hasher, skipping repeated keys.
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut cht = Self::initialize(1, H::default());
+        cht.extend(iter);
+        cht
+    }
+}
+
+impl<K: Hash + Eq, H: hashers::DimHasher> Extend<K> for ChainedHashTable<K, H> {
+    /// Adds every key of `iter` to the table, skipping the ones already in it.
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        for x in iter {
+            let _ = self.add(x);
+        }
+    }
+}
+
+impl<'a, K, H: hashers::DimHasher> IntoIterator for &'a ChainedHashTable<K, H> {
+    type Item = &'a K;
+    type IntoIter = ChainedHashTableIterator<'a, K>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ChainedHashTableIterator { rows: self.chains.table.iter().flatten() }
+    }
+}
+
+impl<K, H: hashers::DimHasher> IntoIterator for ChainedHashTable<K, H> {
+    type Item = K;
+    type IntoIter = ChainedHashTableIntoIterator<K>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ChainedHashTableIntoIterator { rows: self.chains.table.into_iter().flatten() }
+    }
+}
+
+pub struct ChainedHashTableIterator<'a, K> {
+    rows: Flatten<slice::Iter<'a, Vec<K>>>,
+}
+
+impl<'a, K> Iterator for ChainedHashTableIterator<'a, K> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rows.next()
+    }
+}
+
+pub struct ChainedHashTableIntoIterator<K> {
+    rows: Flatten<vec::IntoIter<Vec<K>>>,
+}
+
+impl<K> Iterator for ChainedHashTableIntoIterator<K> {
+    type Item = K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rows.next()
     }
 }
 
@@ -113,15 +372,15 @@ mod tests {
     #[test]
     fn initialize() {
         let h1= hashers::Multiplicative::new();
-        let chs1 = ChainedHashTable::initialize(1, h1);
-        assert_eq!(chs1.dim, 1);
-        assert_eq!(chs1.table.len(), 2);
+        let chs1 = ChainedHashTable::<u64, _>::initialize(1, h1);
+        assert_eq!(chs1.chains.dim, 1);
+        assert_eq!(chs1.chains.table.len(), 2);
         assert_eq!(chs1.len(), 0);
 
         let h2 = hashers::Multiplicative::new();
-        let chs2 = ChainedHashTable::initialize(10, h2);
-        assert_eq!(chs2.dim, 10);
-        assert_eq!(chs2.table.len(), 1024);
+        let chs2 = ChainedHashTable::<u64, _>::initialize(10, h2);
+        assert_eq!(chs2.chains.dim, 10);
+        assert_eq!(chs2.chains.table.len(), 1024);
         assert_eq!(chs2.len(), 0);
     }
 
@@ -129,39 +388,48 @@ mod tests {
     #[should_panic]
     fn initialize_dim_zero() {
         let h = hashers::Multiplicative::new();
-        let _ = ChainedHashTable::initialize(0, h);
+        let _ = ChainedHashTable::<u64, _>::initialize(0, h);
     }
 
     #[test]
     fn partial_eq_same_len() {
-        let cht1 = ChainedHashTable {
-            dim: 1, 
-            table: vec![
-                vec![0, 1013],
-                vec![3, 43, 18446744073709551615],
-            ],
-            hasher: hashers::Multiplicative::with_seed(42),
-            len: 5,
+        let cht1 = ChainedHashTable::<u64, _> {
+            chains: Chains {
+                dim: 1, 
+                table: vec![
+                    vec![0, 1013],
+                    vec![3, 43, 18446744073709551615],
+                ],
+                hasher: hashers::Multiplicative::with_seed(42),
+                len: 5,
+                key: PhantomData,
+            },
         };
-        let cht2 = ChainedHashTable {
-            dim: 2, 
-            table: vec![
-                vec![3, 0],
-                vec![18446744073709551615],
-                vec![43],
-                vec![1013],
-            ],
-            hasher: hashers::Multiplicative::with_seed(7),
-            len: 5,
+        let cht2 = ChainedHashTable::<u64, _> {
+            chains: Chains {
+                dim: 2, 
+                table: vec![
+                    vec![3, 0],
+                    vec![18446744073709551615],
+                    vec![43],
+                    vec![1013],
+                ],
+                hasher: hashers::Multiplicative::with_seed(7),
+                len: 5,
+                key: PhantomData,
+            },
         };
-        let cht3 = ChainedHashTable {
-            dim: 1, 
-            table: vec![
-                vec![0, 18446744073709551615],
-                vec![3, 10, 2026],
-            ],
-            hasher: hashers::Multiplicative::with_seed(13),
-            len: 5,
+        let cht3 = ChainedHashTable::<u64, _> {
+            chains: Chains {
+                dim: 1, 
+                table: vec![
+                    vec![0, 18446744073709551615],
+                    vec![3, 10, 2026],
+                ],
+                hasher: hashers::Multiplicative::with_seed(13),
+                len: 5,
+                key: PhantomData,
+            },
         };
         assert_eq!(cht1, cht2);
         assert_ne!(cht1, cht3);
@@ -170,36 +438,45 @@ mod tests {
 
     #[test]
     fn partial_eq_different_len() {
-        let cht1 = ChainedHashTable {
-            dim: 1, 
-            table: vec![
-                vec![0],
-                vec![2450321026, 18446744073709551615],
-            ],
-            hasher: hashers::Multiplicative::with_seed(555),
-            len: 3,
+        let cht1 = ChainedHashTable::<u64, _> {
+            chains: Chains {
+                dim: 1, 
+                table: vec![
+                    vec![0],
+                    vec![2450321026, 18446744073709551615],
+                ],
+                hasher: hashers::Multiplicative::with_seed(555),
+                len: 3,
+                key: PhantomData,
+            },
         };
-        let cht2 = ChainedHashTable {
-            dim: 2, 
-            table: vec![
-                vec![0, 1985059619],
-                vec![18446744073709551615],
-                vec![2450321026],
-                vec![],
-            ],
-            hasher: hashers::Multiplicative::with_seed(777),
-            len: 4,
+        let cht2 = ChainedHashTable::<u64, _> {
+            chains: Chains {
+                dim: 2, 
+                table: vec![
+                    vec![0, 1985059619],
+                    vec![18446744073709551615],
+                    vec![2450321026],
+                    vec![],
+                ],
+                hasher: hashers::Multiplicative::with_seed(777),
+                len: 4,
+                key: PhantomData,
+            },
         };
         assert_ne!(cht1, cht2);
     }
     
     #[test]
     fn add() {
-        let mut cht1 = ChainedHashTable {
-            dim: 2,
-            table: vec![vec![]; 4],
-            hasher: hashers::Multiplicative::with_seed(3141592),
-            len: 0,
+        let mut cht1 = ChainedHashTable::<u64, _> {
+            chains: Chains {
+                dim: 2,
+                table: vec![vec![]; 4],
+                hasher: hashers::Multiplicative::with_seed(3141592),
+                len: 0,
+                key: PhantomData,
+            },
         };
         let out1 = cht1.add(42);
         let out2 = cht1.add(101);
@@ -213,104 +490,229 @@ mod tests {
         assert_eq!(out4, Ok(()));
         assert_eq!(out5, Err(Error::KeyAlreadyExists));
 
-        let cht2 = ChainedHashTable {
-            dim: 1,
-            table: vec![
-                vec![0, 101],
-                vec![42, 18446744073709551615],
-            ],
-            hasher: hashers::Multiplicative::with_seed(10101),
-            len: 4,
+        let cht2 = ChainedHashTable::<u64, _> {
+            chains: Chains {
+                dim: 1,
+                table: vec![
+                    vec![0, 101],
+                    vec![42, 18446744073709551615],
+                ],
+                hasher: hashers::Multiplicative::with_seed(10101),
+                len: 4,
+                key: PhantomData,
+            },
         };
         assert_eq!(cht1, cht2);
     }
 
     #[test]
     fn remove() {
-        let mut cht1 = ChainedHashTable {
-            dim: 2,
-            table: vec![
-                vec![42, 101, 0],
-                vec![],
-                vec![],
-                vec![18446744073709551615],
-            ],
-            hasher: hashers::Multiplicative::with_seed(8086),
-            len: 4,
+        let mut cht1 = ChainedHashTable::<u64, _> {
+            chains: Chains {
+                dim: 2,
+                table: vec![
+                    vec![42, 101, 0],
+                    vec![],
+                    vec![],
+                    vec![18446744073709551615],
+                ],
+                hasher: hashers::Multiplicative::with_seed(8086),
+                len: 4,
+                key: PhantomData,
+            },
         };
-        let out1 = cht1.remove(42);
+        let out1 = cht1.remove(&42);
         assert_eq!(out1, Ok(()));
-        let out2 = cht1.remove(69);
+        let out2 = cht1.remove(&69);
         assert_eq!(out2, Err(Error::KeyNotFound));
-        let out3 = cht1.remove(101);
+        let out3 = cht1.remove(&101);
         assert_eq!(out3, Ok(()));
 
-        let cht2 = ChainedHashTable {
-            dim: 1,
-            table: vec![
-                vec![0, 18446744073709551615],
-                vec![],
-            ],
-            hasher: hashers::Multiplicative::with_seed(234),
-            len: 2,
+        let cht2 = ChainedHashTable::<u64, _> {
+            chains: Chains {
+                dim: 1,
+                table: vec![
+                    vec![0, 18446744073709551615],
+                    vec![],
+                ],
+                hasher: hashers::Multiplicative::with_seed(234),
+                len: 2,
+                key: PhantomData,
+            },
         };
         assert_eq!(cht1, cht2);
     }
 
     #[test]
     fn contains() {
-        let cht1 = ChainedHashTable { 
-            dim: 2,
-            table: vec![
-                vec![0],
-                vec![18446744073709551615],
-                vec![24503201026],
-                vec![19850596],
-            ],
-            hasher: hashers::Multiplicative::with_seed(80085),
-            len: 4,
+        let cht1 = ChainedHashTable::<u64, _> {
+            chains: Chains {
+                dim: 2,
+                table: vec![
+                    vec![0],
+                    vec![18446744073709551615],
+                    vec![24503201026],
+                    vec![19850596],
+                ],
+                hasher: hashers::Multiplicative::with_seed(80085),
+                len: 4,
+                key: PhantomData,
+            },
         };
-        assert!(cht1.contains(0));
-        assert!(cht1.contains(18446744073709551615));
-        assert!(cht1.contains(24503201026));
-        assert!(!cht1.contains(42))
+        assert!(cht1.contains(&0));
+        assert!(cht1.contains(&18446744073709551615));
+        assert!(cht1.contains(&24503201026));
+        assert!(!cht1.contains(&42))
     }
 
     #[test]
     fn resize() {
-        let cht1 = ChainedHashTable {
-            dim: 1, 
-            table: vec![
-                vec![0, 3151, 3053, 1496],
-                vec![18446744073709551615, 4253, 4161, 3199, 3057],
-            ],
-            hasher: hashers::Multiplicative::with_seed(1986),
-            len: 9,
+        let cht1 = ChainedHashTable::<u64, _> {
+            chains: Chains {
+                dim: 1, 
+                table: vec![
+                    vec![0, 3151, 3053, 1496],
+                    vec![18446744073709551615, 4253, 4161, 3199, 3057],
+                ],
+                hasher: hashers::Multiplicative::with_seed(1986),
+                len: 9,
+                key: PhantomData,
+            },
         };
         let mut cht2 = cht1.clone();
 
-        cht2.resize(2);
+        cht2.chains.resize(2);
         assert_eq!(cht1, cht2);
-        assert_eq!(cht2.table.len(), 4);
+        assert_eq!(cht2.chains.table.len(), 4);
 
-        cht2.resize(3);
+        cht2.chains.resize(3);
         assert_eq!(cht1, cht2);
-        assert_eq!(cht2.table.len(), 8);
+        assert_eq!(cht2.chains.table.len(), 8);
 
-        cht2.resize(4);
+        cht2.chains.resize(4);
         assert_eq!(cht1, cht2);
-        assert_eq!(cht2.table.len(), 16);
+        assert_eq!(cht2.chains.table.len(), 16);
 
-        cht2.resize(3);
+        cht2.chains.resize(3);
         assert_eq!(cht1, cht2);
-        assert_eq!(cht2.table.len(), 8);
+        assert_eq!(cht2.chains.table.len(), 8);
 
-        cht2.resize(2);
+        cht2.chains.resize(2);
         assert_eq!(cht1, cht2);
-        assert_eq!(cht2.table.len(), 4);
+        assert_eq!(cht2.chains.table.len(), 4);
     
-        cht2.resize(1);
+        cht2.chains.resize(1);
         assert_eq!(cht1, cht2);
-        assert_eq!(cht2.table.len(), 2);
+        assert_eq!(cht2.chains.table.len(), 2);
+    }
+
+    #[test]
+    fn iter() {
+        let cht1 = ChainedHashTable::<u64, _> {
+            chains: Chains {
+                dim: 2,
+                table: vec![
+                    vec![0, 1013],
+                    vec![],
+                    vec![3, 43],
+                    vec![18446744073709551615],
+                ],
+                hasher: hashers::Multiplicative::with_seed(42),
+                len: 5,
+                key: PhantomData,
+            },
+        };
+        let mut keys = cht1.iter().copied().collect::<Vec<u64>>();
+        keys.sort();
+        assert_eq!(keys, [0, 3, 43, 1013, 18446744073709551615]);
+        assert_eq!((&cht1).into_iter().count(), 5);
+
+        let mut owned = cht1.into_iter().collect::<Vec<u64>>();
+        owned.sort();
+        assert_eq!(owned, keys);
+
+        let empty = ChainedHashTable::<u64, _>::initialize(3, hashers::Multiplicative::new());
+        assert_eq!(empty.iter().next(), None);
+    }
+
+    #[test]
+    fn drain() {
+        let mut cht: ChainedHashTable<u64, hashers::Multiplicative> = (0..100).collect();
+        assert_eq!(cht.len(), 100);
+        let mut drained = cht.drain().collect::<Vec<u64>>();
+        drained.sort();
+        assert_eq!(drained, (0..100).collect::<Vec<u64>>());
+        assert!(cht.is_empty());
+        assert_eq!(cht.chains.dim, 1);
+        assert_eq!(cht.chains.table.len(), 2);
+        assert_eq!(cht.add(7), Ok(()));
+        assert!(cht.contains(&7));
+    }
+
+    #[test]
+    fn retain() {
+        let mut cht: ChainedHashTable<u64, hashers::Multiplicative> = (0..1000).collect();
+        assert_eq!(cht.chains.table.len(), 1024);
+        cht.retain(|x| x % 100 == 0);
+        assert_eq!(cht.len(), 10);
+        assert!(!cht.chains.table_is_very_long());
+        assert_eq!(cht.chains.table.len(), 16);
+        for x in 0..1000 {
+            assert_eq!(cht.contains(&x), x % 100 == 0);
+        }
+
+        cht.retain(|_| false);
+        assert!(cht.is_empty());
+        assert_eq!(cht.chains.table.len(), 2);
+    }
+
+    #[test]
+    fn from_iter_and_extend() {
+        let mut cht1: ChainedHashTable<u64, hashers::Multiplicative> =
+            [5, 1, 5, 3, 1].into_iter().collect();
+        assert_eq!(cht1.len(), 3);
+        cht1.extend([3, 4, 2]);
+        assert_eq!(cht1.len(), 5);
+
+        let cht2: ChainedHashTable<u64, hashers::Multiplicative> = (1..6).collect();
+        assert_eq!(cht1, cht2);
+    }
+
+    #[test]
+    fn string_keys() {
+        let hasher = hashers::Multiplicative::with_seed(1234);
+        let mut cht = ChainedHashTable::initialize(1, hasher);
+        for word in ["open", "data", "structures", "in", "rust"] {
+            assert_eq!(cht.add(String::from(word)), Ok(()));
+        }
+        assert_eq!(cht.add(String::from("rust")), Err(Error::KeyAlreadyExists));
+        assert_eq!(cht.len(), 5);
+        assert!(cht.contains("structures"));
+        assert!(!cht.contains("python"));
+
+        assert_eq!(cht.remove("data"), Ok(()));
+        assert_eq!(cht.remove("data"), Err(Error::KeyNotFound));
+        assert!(!cht.contains(&String::from("data")));
+    }
+
+    #[test]
+    fn struct_keys() {
+        #[derive(Hash, PartialEq, Eq)]
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        let hasher = hashers::Tabulation::with_seed(8, 99);
+        let mut cht = ChainedHashTable::initialize(1, hasher);
+        for x in -10..10 {
+            for y in -10..10 {
+                assert_eq!(cht.add(Point { x, y }), Ok(()));
+            }
+        }
+        assert_eq!(cht.len(), 400);
+        assert!(cht.contains(&Point { x: -10, y: 9 }));
+        assert!(!cht.contains(&Point { x: 10, y: 9 }));
+        assert_eq!(cht.add(Point { x: 0, y: 0 }), Err(Error::KeyAlreadyExists));
     }
 }
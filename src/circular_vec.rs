@@ -20,6 +20,10 @@ impl<T: Clone> CircularVec<T> {
         self.storage.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+    }
+
     pub fn shift_head(&mut self, n: isize) {
         let self_len: isize = match self.len().try_into() {
             Ok(val) => val,
@@ -84,7 +88,7 @@ mod tests {
     #[should_panic]
     fn index_empty() {
         let cv: CircularVec<i32> = CircularVec { storage: Vec::new(), head: 0 };
-        cv[0];
+        let _ = cv[0];
     }
 
     #[test]
@@ -0,0 +1,530 @@
+//! A hash table with worst-case constant time lookups, using cuckoo hashing.
+//!
+//! Keys live in one of two tables, at the slot given by the hasher of that
+//! table, so a lookup inspects at most two slots. Adding a key that finds both
+//! of its slots taken evicts the key in the first one, which moves to its slot
+//! in the other table, possibly evicting another key, and so on. An eviction
+//! chain that grows too long is most likely going around a cycle, so the table
+//! gives up and rehashes every key with freshly seeded hashers.
+//!
+//! Each table first folds the key into a `u64` with its own `SeededFold`, and
+//! then reduces that to a slot with its `DimHasher`. Folding the key once for
+//! both tables would not do: keys that share a fold would share their slots in
+//! both tables under every seed, and no rehash could ever separate them.
+
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+use std::mem;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64Mcg;
+use crate::hashers::{Reseed, SeededFold};
+use crate::interfaces::USet;
+
+
+/// Rehash attempts at the same dimension before the tables are grown anyway.
+const MAX_REHASHES_PER_DIM: usize = 8;
+
+/// Dimensions a rehash may grow the tables past the one it was asked for,
+/// before giving up on placing the keys.
+const MAX_EXTRA_DIMS: u32 = 4;
+
+#[derive(Debug)]
+pub struct CuckooHashTable<K, H1: Reseed, H2: Reseed> {
+    dim: u32,
+    t1: Vec<Option<K>>,
+    t2: Vec<Option<K>>,
+    h1: H1,
+    h2: H2,
+    f1: SeededFold,
+    f2: SeededFold,
+    len: usize,
+    rng: Pcg64Mcg,
+    rehashes: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    KeyAlreadyExists,
+    KeyNotFound,
+    /// The keys could not be placed under any of the seeds tried, which only
+    /// happens when several keys hash alike under every seed, like keys whose
+    /// `Hash` implementation feeds nothing. The table is left as it was.
+    KeyCannotBePlaced,
+}
+
+impl<K: Hash + Eq, H1: Reseed, H2: Reseed> CuckooHashTable<K, H1, H2> {
+    /// Creates an empty table with two subtables of `2^dim` slots. The seeds of
+    /// future rehashes are drawn from the OS.
+    pub fn initialize(dim: u32, h1: H1, h2: H2) -> Self {
+        Self::with_rng(dim, h1, h2, Pcg64Mcg::from_os_rng())
+    }
+
+    /// Creates an empty table with two subtables of `2^dim` slots. The seeds of
+    /// future rehashes are drawn from a generator seeded with `state`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::cuckoo_hash_table::CuckooHashTable;
+    /// # use ods::hashers::{Multiplicative, Tabulation};
+    /// let h1 = Multiplicative::with_seed(1);
+    /// let h2 = Tabulation::with_seed(8, 2);
+    /// let mut cht = CuckooHashTable::with_seed(1, h1, h2, 3);
+    /// for x in 0..100u64 {
+    ///     cht.add(x).unwrap();
+    /// }
+    /// assert!(cht.contains(&42));
+    /// ```
+    pub fn with_seed(dim: u32, h1: H1, h2: H2, state: u64) -> Self {
+        Self::with_rng(dim, h1, h2, Pcg64Mcg::seed_from_u64(state))
+    }
+
+    fn with_rng(dim: u32, h1: H1, h2: H2, mut rng: Pcg64Mcg) -> Self {
+        assert!(dim > 0, "CuckooHashTable dimension must be greater than 0");
+        Self {
+            dim,
+            t1: new_table(dim),
+            t2: new_table(dim),
+            h1,
+            h2,
+            f1: SeededFold::with_seed(rng.random()),
+            f2: SeededFold::with_seed(rng.random()),
+            len: 0,
+            rng,
+            rehashes: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns how many times the keys have been rehashed with new hashers,
+    /// either to resize the tables or to break an eviction cycle.
+    pub fn rehashes(&self) -> usize {
+        self.rehashes
+    }
+
+    pub fn contains<Q>(&self, x: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(x).is_some()
+    }
+
+    pub fn add(&mut self, x: K) -> Result<(), Error> {
+        if self.contains(&x) {
+            return Err(Error::KeyAlreadyExists);
+        }
+        if !self.has_room() {
+            self.rehash(self.dim + 1, Some(x))?;
+        }
+        else if let Err(x) = self.place(x) {
+            self.rehash(self.dim, Some(x))?;
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn remove<Q>(&mut self, x: &Q) -> Result<(), Error>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let slot = self.find_slot(x).ok_or(Error::KeyNotFound)?;
+        *slot = None;
+        self.len -= 1;
+
+        if self.table_is_very_long() {
+            // The keys already fit in the current tables, so a failed shrink
+            // just keeps them there.
+            let _ = self.rehash(self.dim - 1, None);
+        }
+        Ok(())
+    }
+
+    fn find<Q>(&self, x: &Q) -> Option<&K>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let in_t1 = self.t1[self.slot1(x)].as_ref();
+        let in_t2 = self.t2[self.slot2(x)].as_ref();
+        in_t1.into_iter().chain(in_t2).find(|y| (*y).borrow() == x)
+    }
+
+    fn find_slot<Q>(&mut self, x: &Q) -> Option<&mut Option<K>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let i = self.slot1(x);
+        let j = self.slot2(x);
+        let holds_x = |slot: &Option<K>| slot.as_ref().is_some_and(|y| y.borrow() == x);
+        if holds_x(&self.t1[i]) {
+            Some(&mut self.t1[i])
+        }
+        else if holds_x(&self.t2[j]) {
+            Some(&mut self.t2[j])
+        }
+        else {
+            None
+        }
+    }
+
+    /// Places `x` following its eviction chain. If the chain gets too long,
+    /// leaves the tables as they were and gives `x` back.
+    fn place(&mut self, x: K) -> Result<(), K> {
+        let max_chain = max_chain(self.dim);
+        let (f1, f2, h1, h2, dim) = (&self.f1, &self.f2, &self.h1, &self.h2, self.dim);
+        place(&mut self.t1, &mut self.t2, x, max_chain, |x, table| match table {
+            0 => Self::slot(f1, h1, x, dim),
+            _ => Self::slot(f2, h2, x, dim),
+        })
+    }
+
+    /// Rebuilds the tables with `2^to_dim` slots and freshly seeded hashers,
+    /// and places every key in them, along with `pending`. Tries new seeds
+    /// whenever a key can't be placed, and grows the tables if too many seeds
+    /// in a row fail.
+    ///
+    /// The layout is first worked out on the indexes of the keys, so that the
+    /// keys are only moved once it is known to succeed. If it still fails once
+    /// the tables have grown `MAX_EXTRA_DIMS` times, the table is left as it
+    /// was and `pending` is dropped.
+    fn rehash(&mut self, to_dim: u32, pending: Option<K>) -> Result<(), Error> {
+        let keys: Vec<&K> = self.t1.iter().chain(&self.t2).flatten().chain(&pending).collect();
+        for dim in to_dim..=to_dim + MAX_EXTRA_DIMS {
+            for _ in 0..MAX_REHASHES_PER_DIM {
+                self.rehashes += 1;
+                let h1 = self.h1.reseeded(self.rng.random());
+                let h2 = self.h2.reseeded(self.rng.random());
+                let f1 = SeededFold::with_seed(self.rng.random());
+                let f2 = SeededFold::with_seed(self.rng.random());
+                let slots: Vec<[usize; 2]> = keys
+                    .iter()
+                    .map(|x| [Self::slot(&f1, &h1, *x, dim), Self::slot(&f2, &h2, *x, dim)])
+                    .collect();
+
+                let mut t1 = new_table(dim);
+                let mut t2 = new_table(dim);
+                let placed = (0..keys.len()).all(|k| {
+                    place(&mut t1, &mut t2, k, max_chain(dim), |&k, table| slots[k][table]).is_ok()
+                });
+                if !placed {
+                    continue;
+                }
+                // The keys are taken in the same order as they were indexed.
+                let mut keys: Vec<Option<K>> = self
+                    .take_keys()
+                    .into_iter()
+                    .chain(pending)
+                    .map(Some)
+                    .collect();
+                let mut take = |k: Option<usize>| {
+                    k.map(|k| keys[k].take().expect("every key is placed once"))
+                };
+                self.t1 = t1.into_iter().map(&mut take).collect();
+                self.t2 = t2.into_iter().map(&mut take).collect();
+                (self.h1, self.h2, self.f1, self.f2, self.dim) = (h1, h2, f1, f2, dim);
+                return Ok(());
+            }
+        }
+        Err(Error::KeyCannotBePlaced)
+    }
+
+    fn take_keys(&mut self) -> Vec<K> {
+        let t1 = mem::take(&mut self.t1);
+        let t2 = mem::take(&mut self.t2);
+        t1.into_iter().chain(t2).flatten().collect()
+    }
+
+    /// Returns the slot of `x` in the first table.
+    fn slot1<Q: Hash + ?Sized>(&self, x: &Q) -> usize {
+        Self::slot(&self.f1, &self.h1, x, self.dim)
+    }
+
+    /// Returns the slot of `x` in the second table.
+    fn slot2<Q: Hash + ?Sized>(&self, x: &Q) -> usize {
+        Self::slot(&self.f2, &self.h2, x, self.dim)
+    }
+
+    fn slot<H, Q>(fold: &SeededFold, hasher: &H, x: &Q, dim: u32) -> usize
+    where
+        H: Reseed,
+        Q: Hash + ?Sized,
+    {
+        let y = hasher.hash(fold.hash_one(x), dim);
+        y.try_into().expect("Unable to cast x's u64 into usize")
+    }
+
+    /// Returns `true` if one more key keeps the load factor of both tables
+    /// together at most 1/2.
+    fn has_room(&self) -> bool {
+        self.len() < self.t1.len()
+    }
+
+    fn table_is_very_long(&self) -> bool {
+        self.dim > 1 && self.len() * 4 < self.t1.len()
+    }
+}
+
+/// The longest eviction chain tried before assuming a cycle in tables of
+/// `2^dim` slots, which grows with the logarithm of the table size.
+fn max_chain(dim: u32) -> usize {
+    3 * dim as usize + 1
+}
+
+fn new_table<E>(dim: u32) -> Vec<Option<E>> {
+    (0..2usize.pow(dim)).map(|_| None).collect()
+}
+
+/// Places `x` in `t1` or `t2` following its eviction chain, where `slot` gives
+/// the slot of a key in the table 0 or 1. If the chain gets longer than
+/// `max_chain`, undoes every eviction in reverse order and gives `x` back.
+fn place<E>(
+    t1: &mut [Option<E>],
+    t2: &mut [Option<E>],
+    x: E,
+    max_chain: usize,
+    slot: impl Fn(&E, usize) -> usize,
+) -> Result<(), E> {
+    let mut x = x;
+    let mut chain = Vec::new();
+    for step in 0..2 * max_chain {
+        let table = if step % 2 == 0 { &mut *t1 } else { &mut *t2 };
+        let i = slot(&x, step % 2);
+        chain.push(i);
+        match table[i].replace(x) {
+            None => return Ok(()),
+            Some(y) => x = y,
+        }
+    }
+    for (step, i) in chain.into_iter().enumerate().rev() {
+        let table = if step % 2 == 0 { &mut *t1 } else { &mut *t2 };
+        x = table[i].replace(x).expect("every slot of the chain holds a key");
+    }
+    Err(x)
+}
+
+impl<K, H1, H2> USet<K> for CuckooHashTable<K, H1, H2>
+where
+    K: Hash + Eq + Clone,
+    H1: Reseed,
+    H2: Reseed,
+{
+    fn size(&self) -> usize {
+        self.len()
+    }
+
+    fn add(&mut self, x: K) -> bool {
+        self.add(x).is_ok()
+    }
+
+    fn remove(&mut self, x: &K) -> bool {
+        self.remove(x).is_ok()
+    }
+
+    fn find(&self, x: &K) -> Option<K> {
+        self.find(x).cloned()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use crate::hashers::{self, Multiplicative, Tabulation};
+
+    fn build_table(state: u64) -> CuckooHashTable<u64, Multiplicative, Tabulation> {
+        let h1 = Multiplicative::with_seed(state);
+        let h2 = Tabulation::with_seed(8, state);
+        CuckooHashTable::with_seed(1, h1, h2, state)
+    }
+
+    #[test]
+    fn initialize() {
+        let cht = CuckooHashTable::<u64, _, _>::initialize(
+            3, Multiplicative::new(), Multiplicative::new()
+        );
+        assert_eq!(cht.t1.len(), 8);
+        assert_eq!(cht.t2.len(), 8);
+        assert!(cht.is_empty());
+        assert_eq!(cht.rehashes(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn initialize_dim_zero() {
+        let _ = CuckooHashTable::<u64, _, _>::initialize(
+            0, Multiplicative::new(), Multiplicative::new()
+        );
+    }
+
+    #[test]
+    fn add_and_contains() {
+        let mut cht = build_table(1);
+        for x in 0..1000 {
+            assert_eq!(cht.add(x * 7), Ok(()));
+        }
+        assert_eq!(cht.add(0), Err(Error::KeyAlreadyExists));
+        assert_eq!(cht.len(), 1000);
+        for x in 0..7000 {
+            assert_eq!(cht.contains(&x), x % 7 == 0);
+        }
+        assert!(cht.len() <= cht.t1.len());
+    }
+
+    #[test]
+    fn keys_sit_in_one_of_their_slots() {
+        let mut cht = build_table(2);
+        for x in 0..500 {
+            cht.add(x).unwrap();
+        }
+        for x in 0..500 {
+            assert!(cht.t1[cht.slot1(&x)] == Some(x) || cht.t2[cht.slot2(&x)] == Some(x));
+        }
+        let stored = cht.t1.iter().chain(cht.t2.iter()).flatten().count();
+        assert_eq!(stored, 500);
+    }
+
+    #[test]
+    fn remove() {
+        let mut cht = build_table(3);
+        for x in 0..100 {
+            cht.add(x).unwrap();
+        }
+        assert_eq!(cht.remove(&42), Ok(()));
+        assert_eq!(cht.remove(&42), Err(Error::KeyNotFound));
+        assert!(!cht.contains(&42));
+        assert_eq!(cht.len(), 99);
+    }
+
+    #[test]
+    fn resize() {
+        let mut cht = build_table(4);
+        for x in 0..1000 {
+            cht.add(x).unwrap();
+        }
+        assert_eq!(cht.t1.len(), 1024);
+        for x in 0..990 {
+            cht.remove(&x).unwrap();
+        }
+        assert!(!cht.table_is_very_long());
+        assert_eq!(cht.t1.len(), 32);
+        for x in 990..1000 {
+            assert!(cht.contains(&x));
+        }
+    }
+
+    #[test]
+    fn breaks_cycles_by_rehashing() {
+        // Three keys that share their slots in both tables can't be placed,
+        // so the table has to reseed its hashers.
+        let h1 = Multiplicative::with_seed(5);
+        let h2 = Multiplicative::with_seed(6);
+        let mut cht = CuckooHashTable::with_seed(2, h1, h2, 5);
+        let same_slots = (0..)
+            .filter(|x| cht.slot1(x) == 0 && cht.slot2(x) == 0)
+            .take(3)
+            .collect::<Vec<u64>>();
+        for x in same_slots.iter() {
+            cht.add(*x).unwrap();
+        }
+        assert_eq!(cht.t1.len(), 4);
+        assert!(cht.rehashes() > 0);
+        assert!(same_slots.iter().all(|x| cht.contains(x)));
+    }
+
+    #[test]
+    fn separates_keys_that_fold_together() {
+        // These keys all fold to 5 with `hashers::fold`, so they would share
+        // their slots in both tables if both hashed that same fold.
+        let m: u64 = 0x9e37_79b9_7f4a_7c15; // The multiplier of `KeyFolder`.
+        let keys = [0u64, 1, 2, 3].map(|a| (a, m.wrapping_mul(a) ^ 5));
+        assert!(keys.iter().all(|key| hashers::fold(key) == 5));
+
+        let h1 = Multiplicative::with_seed(9);
+        let h2 = Multiplicative::with_seed(10);
+        let mut cht = CuckooHashTable::with_seed(1, h1, h2, 11);
+        for key in keys {
+            assert_eq!(cht.add(key), Ok(()));
+        }
+        assert!(keys.iter().all(|key| cht.contains(key)));
+        assert!(cht.t1.len() <= 8);
+    }
+
+    /// A key whose `Hash` implementation feeds nothing, so that every key has
+    /// the same slots under every seed.
+    #[derive(PartialEq, Eq)]
+    struct Unhashable(u64);
+
+    impl Hash for Unhashable {
+        fn hash<S: std::hash::Hasher>(&self, _: &mut S) {}
+    }
+
+    #[test]
+    fn gives_up_on_keys_that_always_collide() {
+        let h1 = Multiplicative::with_seed(12);
+        let h2 = Multiplicative::with_seed(13);
+        let mut cht = CuckooHashTable::with_seed(1, h1, h2, 14);
+        assert_eq!(cht.add(Unhashable(0)), Ok(()));
+        assert_eq!(cht.add(Unhashable(1)), Ok(()));
+        let dim = cht.dim;
+        assert_eq!(cht.add(Unhashable(2)), Err(Error::KeyCannotBePlaced));
+        assert_eq!(cht.len(), 2);
+        assert_eq!(cht.dim, dim);
+        assert!(cht.contains(&Unhashable(0)));
+        assert!(cht.contains(&Unhashable(1)));
+        assert!(!cht.contains(&Unhashable(2)));
+        assert_eq!(cht.remove(&Unhashable(0)), Ok(()));
+        assert_eq!(cht.add(Unhashable(2)), Ok(()));
+    }
+
+    #[test]
+    fn failed_place_leaves_tables_unchanged() {
+        let mut t1 = vec![Some(0), Some(1)];
+        let mut t2 = vec![Some(2), Some(3)];
+        let slot = |x: &u32, table: usize| (*x as usize + table) % 2;
+        assert_eq!(place(&mut t1, &mut t2, 4, 3, slot), Err(4));
+        assert_eq!((t1, t2), (vec![Some(0), Some(1)], vec![Some(2), Some(3)]));
+    }
+
+    #[test]
+    fn string_keys() {
+        let h1 = Tabulation::with_seed(8, 10);
+        let h2 = Tabulation::with_seed(8, 20);
+        let mut cht = CuckooHashTable::with_seed(1, h1, h2, 30);
+        for word in ["open", "data", "structures", "in", "rust"] {
+            assert_eq!(cht.add(String::from(word)), Ok(()));
+        }
+        assert!(cht.contains("rust"));
+        assert_eq!(cht.remove("in"), Ok(()));
+        assert!(!cht.contains("in"));
+    }
+
+    #[test]
+    fn matches_hash_set() {
+        let mut rng = Pcg64Mcg::seed_from_u64(77);
+        let mut cht = build_table(77);
+        let mut set = HashSet::new();
+        for _ in 0..20000 {
+            let x = rng.random_range(0..2000);
+            if rng.random_bool(0.6) {
+                assert_eq!(cht.add(x).is_ok(), set.insert(x));
+            } else {
+                assert_eq!(cht.remove(&x).is_ok(), set.remove(&x));
+            }
+            assert_eq!(cht.len(), set.len());
+        }
+        for x in 0..2000 {
+            assert_eq!(cht.contains(&x), set.contains(&x));
+        }
+    }
+}
@@ -12,6 +12,7 @@
 
 use std::cell::{RefCell, Ref, RefMut};
 use std::rc::Rc;
+use crate::interfaces::{Deque, Queue};
 
 type Link<T> = Rc<RefCell<Node<T>>>;
 
@@ -33,8 +34,8 @@ impl<T> Node<T> {
         Rc::new(RefCell::new(
             Self {
                 value,
-                next: next.map(|link| Rc::clone(link)),
-                prev: prev.map(|link| Rc::clone(link)),
+                next: next.map(Rc::clone),
+                prev: prev.map(Rc::clone),
             }
         ))
     }
@@ -264,6 +265,49 @@ impl<T> DLList<T> {
     }
 }
 
+impl<T> Default for DLList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Queue<T> for DLList<T> {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    /// Pushes `x` as the new tail, so the list behaves as a FIFO queue.
+    fn add(&mut self, x: T) {
+        self.push_tail(x)
+    }
+
+    fn remove(&mut self) -> Option<T> {
+        self.pop_head()
+    }
+}
+
+impl<T> Deque<T> for DLList<T> {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn add_first(&mut self, x: T) {
+        self.push_head(x)
+    }
+
+    fn add_last(&mut self, x: T) {
+        self.push_tail(x)
+    }
+
+    fn remove_first(&mut self) -> Option<T> {
+        self.pop_head()
+    }
+
+    fn remove_last(&mut self) -> Option<T> {
+        self.pop_tail()
+    }
+}
+
 pub struct IntoIter<T>(DLList<T>);
 
 impl<T> Iterator for IntoIter<T> {
@@ -0,0 +1,436 @@
+//! A deque built from two `ArrayStack`s placed back to back.
+//!
+//! The `front` stack stores its elements in reverse order, so both ends of the
+//! deque sit at the top of one of the stacks, where insertions and removals are
+//! cheap. Whenever one stack holds more than three times as many elements as
+//! the other, the elements are redistributed evenly between the two. Indexed
+//! operations therefore run in *O(1 + min(i, n - i))* amortized time without
+//! any modular arithmetic.
+
+use std::iter::{Chain, Rev};
+use std::{mem, slice, vec};
+use crate::array_stack::ArrayStack;
+use crate::interfaces::{Deque, List};
+
+
+/// A deque built from two `ArrayStack`s placed back to back.
+pub struct DualArrayDeque<T> {
+    front: ArrayStack<T>,
+    back: ArrayStack<T>,
+}
+
+
+impl<T> DualArrayDeque<T> {
+    /// Returns a new, empty `DualArrayDeque`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::dual_array_deque::DualArrayDeque;
+    /// let deque: DualArrayDeque<i32> = DualArrayDeque::initialize();
+    /// ```
+    pub fn initialize() -> Self {
+        Self { front: ArrayStack::initialize(), back: ArrayStack::initialize() }
+    }
+
+    /// Returns the number of elements stored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::dual_array_deque::DualArrayDeque;
+    /// let deque: DualArrayDeque<i32> = DualArrayDeque::initialize();
+    /// assert_eq!(deque.size(), 0);
+    /// ```
+    pub fn size(&self) -> usize {
+        self.front.size() + self.back.size()
+    }
+
+    /// Returns a shared reference to the element in the position `i`, or `None`
+    /// if `i` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::dual_array_deque::DualArrayDeque;
+    /// let mut deque = DualArrayDeque::initialize();
+    /// deque.add(0, 'a');
+    /// assert_eq!(deque.get(0), Some(&'a'));
+    /// assert_eq!(deque.get(1), None);
+    /// ```
+    pub fn get(&self, i: usize) -> Option<&T> {
+        let front_size = self.front.size();
+        if i < front_size {
+            self.front.get(front_size - i - 1)
+        } else {
+            self.back.get(i - front_size)
+        }
+    }
+
+    /// Replaces the element in the position `i` with `x` and returns the
+    /// original element. Returns `None` if `i` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::dual_array_deque::DualArrayDeque;
+    /// let mut deque = DualArrayDeque::initialize();
+    /// deque.add(0, 'a');
+    /// assert_eq!(deque.set(0, 'b'), Some('a'));
+    /// assert_eq!(deque.get(0), Some(&'b'));
+    /// ```
+    pub fn set(&mut self, i: usize, x: T) -> Option<T> {
+        let front_size = self.front.size();
+        if i < front_size {
+            self.front.set(front_size - i - 1, x)
+        } else {
+            self.back.set(i - front_size, x)
+        }
+    }
+
+    /// Inserts `x` in the position `i`, shifting all the elements between `i`
+    /// and the closest end of the deque. Panics if `i` is out of bounds.
+    ///
+    /// Notice that `i = self.size()` is a valid location and is equivalent to
+    /// inserting at the back of the deque.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::dual_array_deque::DualArrayDeque;
+    /// let mut deque = DualArrayDeque::initialize();
+    /// deque.add(0, 'b');
+    /// deque.add(0, 'a');
+    /// deque.add(2, 'c');
+    /// assert_eq!(deque.iter().collect::<Vec<&char>>(), [&'a', &'b', &'c']);
+    /// ```
+    pub fn add(&mut self, i: usize, x: T) {
+        if self.is_out_of_inserting_bounds(i) {
+            panic!("Index out of bounds: {i}");
+        }
+        let front_size = self.front.size();
+        if i < front_size {
+            self.front.add(front_size - i, x);
+        } else {
+            self.back.add(i - front_size, x);
+        }
+        self.balance();
+    }
+
+    /// Removes the element in the position `i`, shifting all the elements
+    /// between `i` and the closest end of the deque. Returns the element if
+    /// `i` is not out of bounds, `None` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::dual_array_deque::DualArrayDeque;
+    /// let mut deque = DualArrayDeque::initialize();
+    /// deque.add(0, 'a');
+    /// assert_eq!(deque.remove(0), Some('a'));
+    /// assert_eq!(deque.remove(0), None);
+    /// ```
+    pub fn remove(&mut self, i: usize) -> Option<T> {
+        let front_size = self.front.size();
+        let x = if i < front_size {
+            self.front.remove(front_size - i - 1)
+        } else {
+            self.back.remove(i - front_size)
+        }?;
+        self.balance();
+        Some(x)
+    }
+
+    /// Returns an iterator of shared references to the deque's elements, from
+    /// front to back.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.front.iter().rev().chain(self.back.iter()))
+    }
+
+    /// Returns an iterator of mutable references to the deque's elements, from
+    /// front to back.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut(self.front.iter_mut().rev().chain(self.back.iter_mut()))
+    }
+
+    fn is_out_of_inserting_bounds(&self, i: usize) -> bool {
+        i > self.size()
+    }
+
+    fn is_unbalanced(&self) -> bool {
+        3 * self.front.size() < self.back.size()
+            || 3 * self.back.size() < self.front.size()
+    }
+
+    fn balance(&mut self) {
+        if !self.is_unbalanced() {
+            return;
+        }
+        let front_size = self.size() / 2;
+        let front = mem::replace(&mut self.front, ArrayStack::initialize());
+        let back = mem::replace(&mut self.back, ArrayStack::initialize());
+        let mut elements = front.into_iter().rev().chain(back);
+
+        let new_front: Vec<T> = elements.by_ref().take(front_size).collect();
+        for x in new_front.into_iter().rev() {
+            self.front.add(self.front.size(), x);
+        }
+        for x in elements {
+            self.back.add(self.back.size(), x);
+        }
+    }
+}
+
+
+impl<T> List<T> for DualArrayDeque<T> {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        self.get(i)
+    }
+
+    fn set(&mut self, i: usize, x: T) -> Option<T> {
+        self.set(i, x)
+    }
+
+    fn add(&mut self, i: usize, x: T) {
+        self.add(i, x)
+    }
+
+    fn remove(&mut self, i: usize) -> Option<T> {
+        self.remove(i)
+    }
+}
+
+
+impl<T> Deque<T> for DualArrayDeque<T> {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn add_first(&mut self, x: T) {
+        self.add(0, x)
+    }
+
+    fn add_last(&mut self, x: T) {
+        self.add(self.size(), x)
+    }
+
+    fn remove_first(&mut self) -> Option<T> {
+        self.remove(0)
+    }
+
+    fn remove_last(&mut self) -> Option<T> {
+        self.remove(self.size().checked_sub(1)?)
+    }
+}
+
+
+impl<T> IntoIterator for DualArrayDeque<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self.front.into_iter().rev().chain(self.back))
+    }
+}
+
+
+pub struct Iter<'a, T>(Chain<Rev<slice::Iter<'a, T>>, slice::Iter<'a, T>>);
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+}
+
+
+pub struct IterMut<'a, T>(Chain<Rev<slice::IterMut<'a, T>>, slice::IterMut<'a, T>>);
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+}
+
+
+pub struct IntoIter<T>(Chain<Rev<vec::IntoIter<T>>, vec::IntoIter<T>>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array_deque::ArrayDeque;
+    use rand::{Rng, SeedableRng};
+    use rand_pcg::Pcg64Mcg;
+
+    fn build_test_deque() -> DualArrayDeque<char> {
+        let mut front = ArrayStack::initialize();
+        front.add(0, 'b');
+        front.add(1, 'a');
+        let mut back = ArrayStack::initialize();
+        back.add(0, 'c');
+        back.add(1, 'd');
+        back.add(2, 'e');
+        DualArrayDeque { front, back }
+    }
+
+    #[test]
+    fn initialize_has_size_zero() {
+        let deque = DualArrayDeque::<i32>::initialize();
+        assert_eq!(deque.size(), 0);
+    }
+
+    #[test]
+    fn initialize_returns_empty_deque() {
+        let deque = DualArrayDeque::<i32>::initialize();
+        assert_eq!(deque.iter().count(), 0);
+    }
+
+    #[test]
+    fn get_returns_some_reference() {
+        let deque = build_test_deque();
+        assert_eq!(deque.get(0), Some(&'a'));
+        assert_eq!(deque.get(1), Some(&'b'));
+        assert_eq!(deque.get(2), Some(&'c'));
+        assert_eq!(deque.get(4), Some(&'e'));
+    }
+
+    #[test]
+    fn get_out_of_bounds_returns_none() {
+        let deque = build_test_deque();
+        assert!(deque.get(5).is_none());
+        assert!(deque.get(10).is_none());
+    }
+
+    #[test]
+    fn set_returns_prior_element() {
+        let mut deque = build_test_deque();
+        assert_eq!(deque.set(0, 'x'), Some('a'));
+        assert_eq!(deque.set(3, 'y'), Some('d'));
+        assert_eq!(deque.iter().collect::<String>(), "xbcye");
+    }
+
+    #[test]
+    fn set_out_of_bounds_returns_none() {
+        let mut deque = build_test_deque();
+        assert!(deque.set(5, 'x').is_none());
+        assert_eq!(deque.iter().collect::<String>(), "abcde");
+    }
+
+    #[test]
+    fn add_updates_storage() {
+        let mut deque = DualArrayDeque::initialize();
+        deque.add(0, 'b');
+        deque.add(0, 'a');
+        deque.add(2, 'd');
+        deque.add(2, 'c');
+        deque.add(4, 'e');
+        assert_eq!(deque.iter().collect::<String>(), "abcde");
+        assert_eq!(deque.size(), 5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn add_out_of_bounds_panics() {
+        let mut deque = build_test_deque();
+        deque.add(6, 'x');
+    }
+
+    #[test]
+    fn remove_returns_element() {
+        let mut deque = build_test_deque();
+        assert_eq!(deque.remove(0), Some('a'));
+        assert_eq!(deque.remove(3), Some('e'));
+        assert_eq!(deque.remove(1), Some('c'));
+        assert_eq!(deque.iter().collect::<String>(), "bd");
+        assert_eq!(deque.size(), 2);
+    }
+
+    #[test]
+    fn remove_out_of_bounds_returns_none() {
+        let mut deque = build_test_deque();
+        assert!(deque.remove(5).is_none());
+        assert_eq!(deque.size(), 5);
+    }
+
+    #[test]
+    fn balance_keeps_stacks_within_factor_three() {
+        let mut deque = DualArrayDeque::initialize();
+        for x in 0..100 {
+            deque.add(deque.size(), x);
+            assert!(deque.size() < 2 || !deque.is_unbalanced());
+        }
+        for _ in 0..98 {
+            deque.remove(0);
+            assert!(!deque.is_unbalanced());
+        }
+        assert_eq!(deque.iter().collect::<Vec<&i32>>(), [&98, &99]);
+    }
+
+    #[test]
+    fn iter_mut_mutates_storage() {
+        let mut deque = build_test_deque();
+        deque.iter_mut().for_each(|x| *x = x.to_ascii_uppercase());
+        assert_eq!(deque.iter().collect::<String>(), "ABCDE");
+    }
+
+    #[test]
+    fn into_iter_returns_deque() {
+        let deque = build_test_deque();
+        assert_eq!(deque.into_iter().rev().collect::<String>(), "edcba");
+    }
+
+    #[test]
+    fn matches_array_deque() {
+        let mut rng = Pcg64Mcg::seed_from_u64(2024);
+        let mut dual = DualArrayDeque::initialize();
+        let mut deque = ArrayDeque::initialize();
+
+        for x in 0..2000 {
+            let size = dual.size();
+            if size > 0 && rng.random_bool(0.4) {
+                let i = rng.random_range(0..size);
+                assert_eq!(dual.remove(i), deque.remove(i));
+            } else {
+                let i = rng.random_range(0..=size);
+                dual.add(i, x);
+                deque.add(i, x);
+            }
+            assert_eq!(dual.size(), deque.size());
+        }
+        assert!(dual.iter().eq(deque.iter()));
+    }
+}
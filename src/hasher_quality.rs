@@ -0,0 +1,278 @@
+//! Statistical checks of the output of `DimHasher` implementations.
+//!
+//! `evaluate` runs a seeded battery of tests on a hasher and gathers the
+//! results in a `QualityReport`:
+//!
+//! - Bucket uniformity: random keys are hashed into `2^dim` buckets, and the
+//!   bucket counts are compared with the uniform distribution with Pearson's
+//!   chi-squared statistic.
+//! - Avalanche: flipping any single bit of a key should flip every output bit
+//!   with probability 1/2.
+//! - Bit independence: the flips of any two output bits, caused by the same
+//!   input bit, should be uncorrelated.
+//! - Collisions on adversarial inputs: sequential keys and keys that only
+//!   differ in their high bits, which break hashers that ignore some bits.
+//!
+//! # Examples
+//!
+//! ```
+//! # use ods::hasher_quality::{evaluate, Settings};
+//! # use ods::hashers::Tabulation;
+//! let report = evaluate(&Tabulation::with_seed(8, 1), &Settings::default());
+//! assert!(report.is_uniform());
+//! assert!(report.avalanche_bias < 0.25);
+//! ```
+
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64Mcg;
+use crate::hashers::DimHasher;
+
+
+/// The parameters of an evaluation.
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// The dimension the hasher is evaluated at, so there are `2^dim` buckets.
+    pub dim: u32,
+    /// The number of random keys hashed by the uniformity check. The
+    /// collision checks hash `2^dim` keys instead, one per bucket.
+    pub keys: usize,
+    /// The number of random keys whose bits are flipped by the avalanche and
+    /// bit independence checks.
+    pub avalanche_samples: usize,
+    /// The distance between consecutive keys of the strided collision check.
+    pub stride: u64,
+    /// The seed of the random keys.
+    pub seed: u64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self { dim: 10, keys: 1 << 14, avalanche_samples: 1000, stride: 1 << 32, seed: 0 }
+    }
+}
+
+/// The results of an evaluation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QualityReport {
+    /// Pearson's chi-squared statistic of the bucket counts of random keys.
+    pub chi_squared: f64,
+    /// The degrees of freedom of `chi_squared`, one less than the buckets.
+    pub degrees_of_freedom: usize,
+    /// The largest distance from 1/2 of the probability that flipping an
+    /// input bit flips an output bit, over every pair of bits.
+    pub avalanche_bias: f64,
+    /// The largest absolute correlation between the flips of two output bits
+    /// caused by the same input bit.
+    pub bit_independence: f64,
+    /// The fraction of sequential keys that land in an already used bucket.
+    pub sequential_collisions: f64,
+    /// The fraction of strided keys that land in an already used bucket.
+    pub strided_collisions: f64,
+    /// The fraction of keys expected to land in an already used bucket, when
+    /// hashing as many keys with a truly random function.
+    pub expected_collisions: f64,
+}
+
+impl QualityReport {
+    /// Returns `true` if `chi_squared` is below the critical value of the
+    /// chi-squared distribution at a 0.1% significance level.
+    pub fn is_uniform(&self) -> bool {
+        self.chi_squared <= chi_squared_critical(self.degrees_of_freedom)
+    }
+}
+
+/// Runs every check on `hasher` with the given settings.
+pub fn evaluate<H: DimHasher>(hasher: &H, settings: &Settings) -> QualityReport {
+    assert!(settings.dim > 0 && settings.dim < u64::BITS, "dim must be in 1..64");
+    let mut rng = Pcg64Mcg::seed_from_u64(settings.seed);
+
+    let random_keys = (0..settings.keys).map(|_| rng.random()).collect::<Vec<u64>>();
+    let (avalanche_bias, bit_independence) = avalanche(hasher, settings, &mut rng);
+    let n = 2u64.pow(settings.dim);
+    let sequential = (0..n).collect::<Vec<u64>>();
+    let strided = (0..n).map(|i| i.wrapping_mul(settings.stride)).collect::<Vec<u64>>();
+
+    QualityReport {
+        chi_squared: chi_squared(hasher, settings.dim, &random_keys),
+        degrees_of_freedom: 2usize.pow(settings.dim) - 1,
+        avalanche_bias,
+        bit_independence,
+        sequential_collisions: collision_rate(hasher, settings.dim, &sequential),
+        strided_collisions: collision_rate(hasher, settings.dim, &strided),
+        expected_collisions: expected_collision_rate(settings.dim, sequential.len()),
+    }
+}
+
+/// Returns Pearson's chi-squared statistic of the bucket counts of `keys`.
+pub fn chi_squared<H: DimHasher>(hasher: &H, dim: u32, keys: &[u64]) -> f64 {
+    let counts = bucket_counts(hasher, dim, keys);
+    let expected = keys.len() as f64 / counts.len() as f64;
+    counts.iter()
+        .map(|&count| (count as f64 - expected).powi(2) / expected)
+        .sum()
+}
+
+/// Returns the fraction of `keys` that land in a bucket already used by a
+/// previous key.
+pub fn collision_rate<H: DimHasher>(hasher: &H, dim: u32, keys: &[u64]) -> f64 {
+    if keys.is_empty() {
+        return 0.0;
+    }
+    let used = bucket_counts(hasher, dim, keys)
+        .into_iter()
+        .filter(|&count| count > 0)
+        .count();
+    (keys.len() - used) as f64 / keys.len() as f64
+}
+
+/// Returns the fraction of `n` keys expected to land in an already used
+/// bucket, out of `2^dim`, if buckets are chosen uniformly at random.
+pub fn expected_collision_rate(dim: u32, n: usize) -> f64 {
+    if n == 0 {
+        return 0.0;
+    }
+    let m = 2f64.powi(dim as i32);
+    let used = m * (1.0 - (1.0 - 1.0 / m).powf(n as f64));
+    (n as f64 - used) / n as f64
+}
+
+/// Returns the avalanche bias and the bit independence of `hasher`, measured
+/// by flipping each bit of random keys.
+fn avalanche<H: DimHasher>(hasher: &H, settings: &Settings, rng: &mut Pcg64Mcg) -> (f64, f64) {
+    let dim = settings.dim as usize;
+    // flips[i][j][k] counts how many times flipping the input bit `i` flipped
+    // both output bits `j` and `k`, so flips[i][j][j] counts flips of `j`.
+    let mut flips = vec![vec![vec![0usize; dim]; dim]; u64::BITS as usize];
+    for _ in 0..settings.avalanche_samples {
+        let x = rng.random::<u64>();
+        let y = hasher.hash(x, settings.dim);
+        for (i, flips_i) in flips.iter_mut().enumerate() {
+            let diff = y ^ hasher.hash(x ^ (1 << i), settings.dim);
+            for j in (0..dim).filter(|j| diff >> j & 1 == 1) {
+                for k in (j..dim).filter(|k| diff >> k & 1 == 1) {
+                    flips_i[j][k] += 1;
+                }
+            }
+        }
+    }
+
+    let samples = settings.avalanche_samples as f64;
+    let mut bias = 0f64;
+    let mut correlation = 0f64;
+    for flips_i in flips.iter() {
+        let p = (0..dim).map(|j| flips_i[j][j] as f64 / samples).collect::<Vec<f64>>();
+        for (j, both) in flips_i.iter().enumerate() {
+            bias = bias.max((p[j] - 0.5).abs());
+            for k in j + 1..dim {
+                let p_jk = both[k] as f64 / samples;
+                let deviation = (p[j] * (1.0 - p[j]) * p[k] * (1.0 - p[k])).sqrt();
+                // A bit that always or never flips is fully dependent on the
+                // input bit, and already shows up as avalanche bias.
+                if deviation > 0.0 {
+                    correlation = correlation.max(((p_jk - p[j] * p[k]) / deviation).abs());
+                }
+            }
+        }
+    }
+    (bias, correlation)
+}
+
+fn bucket_counts<H: DimHasher>(hasher: &H, dim: u32, keys: &[u64]) -> Vec<usize> {
+    let mut counts = vec![0; 2usize.pow(dim)];
+    for &x in keys {
+        let i: usize = hasher.hash(x, dim)
+            .try_into()
+            .expect("Unable to cast u64 into usize");
+        counts[i] += 1;
+    }
+    counts
+}
+
+/// Approximates the critical value of the chi-squared distribution at a 0.1%
+/// significance level with the Wilson-Hilferty transformation.
+fn chi_squared_critical(degrees_of_freedom: usize) -> f64 {
+    const Z: f64 = 3.090;
+    let k = degrees_of_freedom as f64;
+    let a = 2.0 / (9.0 * k);
+    k * (1.0 - a + Z * a.sqrt()).powi(3)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashers::{Multiplicative, Tabulation};
+
+    /// A poor hasher that keeps the lowest bits of the key.
+    struct LowBits;
+
+    impl DimHasher for LowBits {
+        fn hash(&self, x: u64, dim: u32) -> u64 {
+            x & (u64::MAX >> (u64::BITS - dim))
+        }
+    }
+
+    #[test]
+    fn chi_squared_critical_values() {
+        // Reference values from chi-squared distribution tables.
+        assert!((chi_squared_critical(10) - 29.588).abs() < 0.5);
+        assert!((chi_squared_critical(100) - 149.449).abs() < 0.5);
+        assert!((chi_squared_critical(1023) - 1168.5).abs() < 2.0);
+    }
+
+    #[test]
+    fn chi_squared_of_perfect_spread() {
+        let keys = (0..1024).collect::<Vec<u64>>();
+        assert_eq!(chi_squared(&LowBits, 4, &keys), 0.0);
+        let same = vec![0; 1024];
+        assert_eq!(chi_squared(&LowBits, 4, &same), 1024.0 * 15.0);
+    }
+
+    #[test]
+    fn expected_collisions() {
+        assert_eq!(expected_collision_rate(4, 0), 0.0);
+        assert_eq!(expected_collision_rate(4, 1), 0.0);
+        assert!((expected_collision_rate(1, 2) - 0.25).abs() < 1e-12);
+        // Hashing m keys into m buckets leaves about m/e of them empty.
+        let rate = expected_collision_rate(10, 1024);
+        assert!((rate - (-1f64).exp()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn tabulation_passes() {
+        let report = evaluate(&Tabulation::with_seed(8, 42), &Settings::default());
+        assert!(report.is_uniform());
+        // Simple tabulation looks up a fixed table, so the flip probabilities
+        // of each input bit are averaged over just 128 pairs of entries.
+        assert!(report.avalanche_bias < 0.25);
+        assert!(report.bit_independence < 0.5);
+        assert!((report.sequential_collisions - report.expected_collisions).abs() < 0.05);
+        assert!((report.strided_collisions - report.expected_collisions).abs() < 0.05);
+    }
+
+    #[test]
+    fn multiplicative_lacks_avalanche() {
+        let report = evaluate(&Multiplicative::with_seed(42), &Settings::default());
+        assert!(report.is_uniform());
+        // Flipping the highest bit of the key only flips the highest bit of
+        // its hash, since z * 2^63 = 2^63 (mod 2^64) for any odd z.
+        assert_eq!(report.avalanche_bias, 0.5);
+        assert!(report.sequential_collisions <= report.expected_collisions);
+    }
+
+    #[test]
+    fn low_bits_fails() {
+        let report = evaluate(&LowBits, &Settings::default());
+        assert!(report.is_uniform());
+        assert_eq!(report.sequential_collisions, 0.0);
+        assert_eq!(report.strided_collisions, 1.0 - 1.0 / 1024.0);
+        assert_eq!(report.avalanche_bias, 0.5);
+    }
+
+    #[test]
+    fn evaluation_is_deterministic() {
+        let settings = Settings { seed: 7, ..Settings::default() };
+        let hasher = Tabulation::with_seed(8, 7);
+        assert_eq!(evaluate(&hasher, &settings), evaluate(&hasher, &settings));
+    }
+}
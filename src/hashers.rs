@@ -1,3 +1,4 @@
+use std::hash::{BuildHasher, Hash, Hasher};
 use rand::{Rng, SeedableRng};
 use rand_pcg::Pcg64Mcg;
 
@@ -6,6 +7,110 @@ pub trait DimHasher {
     fn hash(&self, x: u64, dim: u32) -> u64;
 }
 
+/// Hashes a whole slice of `T`, like a byte string or a vector of words, into
+/// `dim` bits.
+pub trait SliceHasher<T> {
+    fn hash(&self, xs: &[T], dim: u32) -> u64;
+}
+
+/// A `DimHasher` that can draw a new, independent hash function from its own
+/// family, for tables that have to rehash their keys with fresh functions.
+pub trait Reseed: DimHasher {
+    /// Returns a new hasher of the same family and parameters, seeded with
+    /// `state`.
+    fn reseeded(&self, state: u64) -> Self;
+}
+
+/// Folds the output of `key`'s `Hash` implementation into the single `u64` that
+/// a `DimHasher` consumes.
+///
+/// Keys that feed a single `u64` to the hasher, like `u64` itself, are folded
+/// into that same value, so hashing them keeps the original behaviour.
+///
+/// # Examples
+///
+/// ```
+/// # use ods::hashers;
+/// assert_eq!(hashers::fold(&42u64), 42);
+/// assert_ne!(hashers::fold("ab"), hashers::fold("ba"));
+/// ```
+pub fn fold<K: Hash + ?Sized>(key: &K) -> u64 {
+    let mut folder = KeyFolder::default();
+    key.hash(&mut folder);
+    folder.finish()
+}
+
+/// An item stored in a hash table, together with the key it is hashed by: a
+/// set stores its keys themselves, and a map stores key-value pairs.
+pub(crate) trait Keyed<K> {
+    fn key(&self) -> &K;
+}
+
+impl<K> Keyed<K> for K {
+    fn key(&self) -> &K {
+        self
+    }
+}
+
+impl<K, V> Keyed<K> for (K, V) {
+    fn key(&self) -> &K {
+        &self.0
+    }
+}
+
+
+/// A `Hasher` that folds every word it is fed into a single `u64`.
+///
+/// Each new word is combined with the state multiplied by an odd constant,
+/// which keeps the order of the words relevant. This is no hash function by
+/// itself: it only reduces the key to a word, and the randomness comes from the
+/// `DimHasher` applied afterwards.
+#[derive(Debug, Default)]
+pub struct KeyFolder {
+    state: u64,
+}
+
+impl KeyFolder {
+    const MULTIPLIER: u64 = 0x9e37_79b9_7f4a_7c15;
+}
+
+impl Hasher for KeyFolder {
+    fn finish(&self) -> u64 {
+        self.state
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.write_u64(u64::from_le_bytes(word));
+        }
+        // Without the length, leading zero bytes would leave no trace.
+        self.write_usize(bytes.len());
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.write_u64(i.into());
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write_u64(i.into());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write_u64(i.into());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.state = self.state.wrapping_mul(Self::MULTIPLIER) ^ i;
+    }
+}
+
+
 #[derive(Debug, Clone)]
 pub struct Multiplicative {
     z: u64,
@@ -27,12 +132,24 @@ impl Multiplicative {
     }
 }
 
+impl Default for Multiplicative {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DimHasher for Multiplicative {
     fn hash(&self, x: u64, dim: u32) -> u64 {
         self.z.overflowing_mul(x).0 >> (u64::BITS - dim)
     }
 }
 
+impl Reseed for Multiplicative {
+    fn reseeded(&self, state: u64) -> Self {
+        Self::with_seed(state)
+    }
+}
+
 
 pub struct Tabulation {
     r: u32,
@@ -88,6 +205,280 @@ impl DimHasher for Tabulation {
     }
 }
 
+impl Reseed for Tabulation {
+    fn reseeded(&self, state: u64) -> Self {
+        Self::with_seed(self.r, state)
+    }
+}
+
+
+/// Polynomial hashing of byte strings over the prime field of order
+/// `2^61 - 1`.
+///
+/// The hash of the bytes `x_0, ..., x_{k-1}` is the polynomial
+/// `x_0 + x_1 z + ... + x_{k-1} z^{k-1} + (p - 1) z^k` evaluated at a random
+/// point `z` of the field. The last term marks the end of the string, so that
+/// no string is a prefix of another. Two different strings of length at most
+/// `k` collide with probability at most `(k + 1) / p`. The result is reduced to
+/// `dim` bits with a `Multiplicative` hasher.
+///
+/// # Examples
+///
+/// ```
+/// # use ods::hashers::{Polynomial, SliceHasher};
+/// let h = Polynomial::with_seed(42);
+/// assert_eq!(h.hash(b"open data", 16), h.hash("open data".as_bytes(), 16));
+/// assert!(h.hash(b"open data", 16) < 1 << 16);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Polynomial {
+    z: u64,
+    reducer: Multiplicative,
+}
+
+impl Polynomial {
+    /// The Mersenne prime `2^61 - 1`, the order of the field.
+    pub const P: u64 = (1 << 61) - 1;
+
+    pub fn new() -> Self {
+        let rng = Pcg64Mcg::from_os_rng();
+        Self::from_rng(rng)
+    }
+
+    pub fn with_seed(state: u64) -> Self {
+        let rng = Pcg64Mcg::seed_from_u64(state);
+        Self::from_rng(rng)
+    }
+
+    fn from_rng(mut rng: Pcg64Mcg) -> Self {
+        let z = rng.random_range(1..Self::P);
+        let reducer = Multiplicative::with_seed(rng.random());
+        Self { z, reducer }
+    }
+
+    /// Returns the polynomial of `xs` evaluated at `z`, modulo `P`.
+    fn evaluate(&self, xs: &[u8]) -> u64 {
+        // Horner's rule, from the end marker down to x_0.
+        xs.iter()
+            .rev()
+            .fold(Self::P - 1, |acc, &x| Self::add_mod(Self::mul_mod(acc, self.z), x.into()))
+    }
+
+    fn mul_mod(a: u64, b: u64) -> u64 {
+        let x = a as u128 * b as u128;
+        // 2^61 = 1 (mod P), so the high bits can be added to the low ones.
+        let y = (x & Self::P as u128) as u64 + (x >> 61) as u64;
+        Self::add_mod(y & Self::P, y >> 61)
+    }
+
+    fn add_mod(a: u64, b: u64) -> u64 {
+        let y = a + b;
+        if y >= Self::P { y - Self::P } else { y }
+    }
+}
+
+impl Default for Polynomial {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SliceHasher<u8> for Polynomial {
+    fn hash(&self, xs: &[u8], dim: u32) -> u64 {
+        self.reducer.hash(self.evaluate(xs), dim)
+    }
+}
+
+
+/// A seeded way of folding any `Hash` key into a `u64`, for tables that need
+/// keys to collide independently under each of their hash functions.
+///
+/// Unlike `fold`, whose output is fixed for each key, the fold here is the
+/// polynomial of the 32-bit halves of the words fed by the key, evaluated at a
+/// random point `z` of the field of `Polynomial`. Two different keys that feed
+/// at most `k` words collide with probability at most `(2k + 1) / p`, whatever
+/// the keys, and independently for each seed.
+///
+/// # Examples
+///
+/// ```
+/// # use std::hash::BuildHasher;
+/// # use ods::hashers::SeededFold;
+/// let f = SeededFold::with_seed(42);
+/// assert_eq!(f.hash_one("open data"), f.hash_one("open data"));
+/// assert_ne!(f.hash_one((0u64, 1u64)), f.hash_one((1u64, 0u64)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SeededFold {
+    z: u64,
+}
+
+impl SeededFold {
+    pub fn new() -> Self {
+        let rng = Pcg64Mcg::from_os_rng();
+        Self::from_rng(rng)
+    }
+
+    pub fn with_seed(state: u64) -> Self {
+        let rng = Pcg64Mcg::seed_from_u64(state);
+        Self::from_rng(rng)
+    }
+
+    fn from_rng(mut rng: Pcg64Mcg) -> Self {
+        Self { z: rng.random_range(1..Polynomial::P) }
+    }
+}
+
+impl Default for SeededFold {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuildHasher for SeededFold {
+    type Hasher = PolynomialFolder;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        // The leading 1 gives keys of different lengths polynomials of
+        // different degrees, so leading zero words still leave a trace.
+        PolynomialFolder { z: self.z, state: 1 }
+    }
+}
+
+/// The `Hasher` of `SeededFold`, which evaluates its polynomial with Horner's
+/// rule as the words come in.
+#[derive(Debug)]
+pub struct PolynomialFolder {
+    z: u64,
+    state: u64,
+}
+
+impl PolynomialFolder {
+    fn push(&mut self, limb: u32) {
+        let state = Polynomial::mul_mod(self.state, self.z);
+        self.state = Polynomial::add_mod(state, limb.into());
+    }
+}
+
+impl Hasher for PolynomialFolder {
+    fn finish(&self) -> u64 {
+        self.state
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(4) {
+            let mut limb = [0; 4];
+            limb[..chunk.len()].copy_from_slice(chunk);
+            self.push(u32::from_le_bytes(limb));
+        }
+        self.write_usize(bytes.len());
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.write_u64(i.into());
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write_u64(i.into());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write_u64(i.into());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.push((i >> 32) as u32);
+        self.push(i as u32);
+    }
+}
+
+
+/// Multiply-add-shift hashing of vectors of words.
+///
+/// The words `x_0, ..., x_{k-1}` and the length `k` are multiplied by random
+/// 128-bit coefficients `a_0, ..., a_k` and added to a random 128-bit `b`, all
+/// modulo `2^128`, and the highest `dim` bits of the sum are the hash. This
+/// family is strongly universal for vectors of the same length. Slices can't
+/// be longer than the `max_len` given at construction.
+///
+/// # Examples
+///
+/// ```
+/// # use ods::hashers::{MultiplyAddShift, SliceHasher};
+/// let h = MultiplyAddShift::with_seed(4, 42);
+/// assert_ne!(h.hash(&[1, 2], 32), h.hash(&[2, 1], 32));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultiplyAddShift {
+    a: Vec<u128>,
+    b: u128,
+}
+
+impl MultiplyAddShift {
+    pub fn new(max_len: usize) -> Self {
+        let rng = Pcg64Mcg::from_os_rng();
+        Self::from_rng(max_len, rng)
+    }
+
+    pub fn with_seed(max_len: usize, state: u64) -> Self {
+        let rng = Pcg64Mcg::seed_from_u64(state);
+        Self::from_rng(max_len, rng)
+    }
+
+    fn from_rng(max_len: usize, mut rng: Pcg64Mcg) -> Self {
+        let a = (0..=max_len).map(|_| rng.random()).collect();
+        Self { a, b: rng.random() }
+    }
+
+    pub fn max_len(&self) -> usize {
+        self.a.len() - 1
+    }
+}
+
+impl SliceHasher<u64> for MultiplyAddShift {
+    fn hash(&self, xs: &[u64], dim: u32) -> u64 {
+        assert!(dim > 0 && dim <= u64::BITS, "dim must be in 1..=64");
+        assert!(xs.len() <= self.max_len(), "slice longer than max_len");
+
+        let words = xs.iter().copied().chain([xs.len() as u64]);
+        let sum = self.a.iter()
+            .zip(words)
+            .fold(self.b, |acc, (a, x)| acc.wrapping_add(a.wrapping_mul(x.into())));
+        (sum >> (u128::BITS - dim)) as u64
+    }
+}
+
+
+#[cfg(test)]
+mod tests_fold {
+    use super::*;
+
+    #[test]
+    fn fold_single_word_is_identity() {
+        assert_eq!(fold(&0u64), 0);
+        assert_eq!(fold(&18446744073709551615u64), 18446744073709551615);
+        assert_eq!(fold(&7u32), 7);
+        assert_eq!(fold(&'a'), 97);
+    }
+
+    #[test]
+    fn fold_is_deterministic() {
+        assert_eq!(fold("hash me"), fold(&String::from("hash me")));
+        assert_eq!(fold(&(1u8, -1i64)), fold(&(1u8, -1i64)));
+    }
+
+    #[test]
+    fn fold_depends_on_order() {
+        assert_ne!(fold(&(1u64, 2u64)), fold(&(2u64, 1u64)));
+        assert_ne!(fold("abcdefghi"), fold("abcdefgih"));
+        assert_ne!(fold(""), fold("\0"));
+    }
+}
+
 
 #[cfg(test)]
 mod tests_multiplicative {
@@ -135,6 +526,12 @@ mod tests_multiplicative {
         assert_eq!(h3.hash(15761423750684663989, 64), 18276548952320700811);
     }
 
+    #[test]
+    fn reseeded() {
+        let h = Multiplicative::with_seed(0).reseeded(42);
+        assert_eq!(h.z, Multiplicative::with_seed(42).z);
+    }
+
     #[test]
     #[should_panic]
     fn hash_low_dim() {
@@ -187,4 +584,138 @@ mod test_tabulation {
         assert_eq!(t3.hash(151, 32), t3.hash(151, 32));
         assert_eq!(t3.hash(u64::MAX, 64), t3.hash(u64::MAX, 64));
     }
+
+    #[test]
+    fn reseeded() {
+        let t1 = Tabulation::with_seed(4, 0).reseeded(101);
+        let t2 = Tabulation::with_seed(4, 101);
+        assert_eq!(t1.r, 4);
+        assert_eq!(t1.tab, t2.tab);
+    }
+}
+
+
+#[cfg(test)]
+mod tests_polynomial {
+    use super::*;
+
+    fn exact(z: u64) -> Polynomial {
+        // z = 1 and dim = 64 make the reducer the identity.
+        Polynomial { z, reducer: Multiplicative { z: 1 } }
+    }
+
+    #[test]
+    fn with_seed() {
+        let h = Polynomial::with_seed(7);
+        assert!(h.z > 0 && h.z < Polynomial::P);
+        assert_eq!(h.z, Polynomial::with_seed(7).z);
+    }
+
+    #[test]
+    fn mul_mod() {
+        let p = Polynomial::P;
+        assert_eq!(Polynomial::mul_mod(p - 1, p - 1), 1);
+        assert_eq!(Polynomial::mul_mod(1 << 60, 4), 2);
+        assert_eq!(Polynomial::mul_mod(0, p - 1), 0);
+        assert_eq!(Polynomial::mul_mod(123456789, 987654321), 121932631112635269);
+    }
+
+    #[test]
+    fn known_answers() {
+        let p = Polynomial::P;
+        // 1 + 2 * 2 + 3 * 2^2 + (p - 1) * 2^3 = 9 (mod p)
+        assert_eq!(exact(2).hash(&[1, 2, 3], 64), 9);
+        // 5 + (p - 1) * (p - 1) = 6 (mod p)
+        assert_eq!(exact(p - 1).hash(&[5], 64), 6);
+        assert_eq!(exact(3).hash(&[], 64), p - 1);
+    }
+
+    #[test]
+    fn no_prefix_collisions() {
+        let h = Polynomial::with_seed(3);
+        assert_ne!(h.hash(b"", 64), h.hash(b"\0", 64));
+        assert_ne!(h.hash(b"a", 64), h.hash(b"a\0", 64));
+        assert_ne!(h.hash(b"ab", 64), h.hash(b"ba", 64));
+    }
+}
+
+
+#[cfg(test)]
+mod tests_multiply_add_shift {
+    use super::*;
+
+    #[test]
+    fn with_seed() {
+        let h1 = MultiplyAddShift::with_seed(3, 11);
+        let h2 = MultiplyAddShift::with_seed(3, 11);
+        assert_eq!(h1.max_len(), 3);
+        assert_eq!(h1.a, h2.a);
+        assert_eq!(h1.b, h2.b);
+    }
+
+    #[test]
+    fn known_answers() {
+        let h1 = MultiplyAddShift { a: vec![1 << 64, 3 << 64], b: 5 << 64 | 7 };
+        // 10 * 2^64 + 1 * 3 * 2^64 + 5 * 2^64 + 7
+        assert_eq!(h1.hash(&[10], 64), 18);
+        assert_eq!(h1.hash(&[], 64), 5);
+
+        let h2 = MultiplyAddShift { a: vec![1 << 127, 0], b: 0 };
+        assert_eq!(h2.hash(&[1], 1), 1);
+        assert_eq!(h2.hash(&[2], 1), 0);
+    }
+
+    #[test]
+    fn length_matters() {
+        let h = MultiplyAddShift::with_seed(4, 5);
+        assert_ne!(h.hash(&[1], 64), h.hash(&[1, 0], 64));
+        assert_ne!(h.hash(&[], 64), h.hash(&[0], 64));
+    }
+
+    #[test]
+    #[should_panic]
+    fn slice_too_long() {
+        let h = MultiplyAddShift::with_seed(2, 5);
+        let _ = h.hash(&[1, 2, 3], 32);
+    }
+}
+
+
+#[cfg(test)]
+mod tests_seeded_fold {
+    use super::*;
+
+    #[test]
+    fn with_seed() {
+        assert_eq!(SeededFold::with_seed(3).z, SeededFold::with_seed(3).z);
+        assert_ne!(SeededFold::with_seed(3).z, SeededFold::with_seed(4).z);
+    }
+
+    #[test]
+    fn known_answers() {
+        let f = SeededFold { z: 2 };
+        // 1 * z^2 + 0 * z + 5, for the two halves of 5.
+        assert_eq!(f.hash_one(5u64), 9);
+        assert_eq!(f.hash_one(1u64 << 32 | 5), 11);
+    }
+
+    #[test]
+    fn leading_zeros_matter() {
+        let f = SeededFold::with_seed(5);
+        assert_ne!(f.hash_one([0u8; 1].as_slice()), f.hash_one([0u8; 2].as_slice()));
+        assert_ne!(f.hash_one(7u64), f.hash_one((0u64, 7u64)));
+    }
+
+    #[test]
+    fn separates_keys_that_fold_together() {
+        let m = KeyFolder::MULTIPLIER;
+        let keys = [(0u64, 5u64), (1, m ^ 5), (2, m.wrapping_mul(2) ^ 5)];
+        assert!(keys.iter().all(|key| fold(key) == 5));
+
+        let f = SeededFold::with_seed(6);
+        let folds: Vec<u64> = keys.iter().map(|key| f.hash_one(key)).collect();
+        assert_ne!(folds[0], folds[1]);
+        assert_ne!(folds[1], folds[2]);
+        assert_ne!(folds[0], folds[2]);
+    }
 }
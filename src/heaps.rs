@@ -0,0 +1,346 @@
+//! Heaps, the priority queues of the book's tenth chapter.
+//!
+//! `BinaryHeap` stores a complete binary tree implicitly in an array, in the
+//! layout of Eytzinger: the root is at index 0, and the children of the node at
+//! index `i` are at indexes `2i + 1` and `2i + 2`. Every node precedes its
+//! children in the order of the heap, so the next value to remove is always at
+//! the root. Adding and removing take *O(log n)* time, and a heap is built from
+//! a vector of `n` values in *O(n)* time.
+//!
+//! The array grows and shrinks like the one of `ArrayStack`, so that its
+//! capacity stays within a constant factor of the number of values.
+
+use crate::interfaces::Queue;
+
+
+/// The order in which a `BinaryHeap` gives back its values.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Order {
+    /// The smallest value is removed first.
+    Min,
+    /// The largest value is removed first.
+    Max,
+}
+
+/// A priority queue backed by an implicit binary tree.
+#[derive(Debug)]
+pub struct BinaryHeap<T> {
+    storage: Vec<T>,
+    order: Order,
+}
+
+impl<T: Ord> BinaryHeap<T> {
+    /// Returns a new, empty `BinaryHeap` that removes its values in `order`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::heaps::{BinaryHeap, Order};
+    /// let heap: BinaryHeap<i32> = BinaryHeap::initialize(Order::Min);
+    /// assert!(heap.is_empty());
+    /// ```
+    pub fn initialize(order: Order) -> Self {
+        BinaryHeap { storage: Vec::new(), order }
+    }
+
+    /// Builds a heap that removes the values of `values` in `order`, in *O(n)*
+    /// time, by sifting down every node that has children, from the last one
+    /// up to the root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::heaps::{BinaryHeap, Order};
+    /// let heap = BinaryHeap::from_vec(vec![3, 1, 4, 1, 5], Order::Max);
+    /// assert_eq!(heap.peek(), Some(&5));
+    /// ```
+    pub fn from_vec(values: Vec<T>, order: Order) -> Self {
+        let mut heap = BinaryHeap { storage: values, order };
+        let n = heap.size();
+        for i in (0..n / 2).rev() {
+            heap.trickle_down(i, n);
+        }
+        heap
+    }
+
+    /// Returns the number of values stored.
+    pub fn size(&self) -> usize {
+        self.storage.len()
+    }
+
+    /// Returns `true` if the heap stores no values, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+    }
+
+    /// Returns the order in which `self` gives back its values.
+    pub fn order(&self) -> Order {
+        self.order
+    }
+
+    /// Returns a shared reference to the value that `remove` would return next,
+    /// or `None` if the heap is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::heaps::{BinaryHeap, Order};
+    /// let mut heap = BinaryHeap::initialize(Order::Min);
+    /// assert_eq!(heap.peek(), None);
+    /// heap.add(2);
+    /// heap.add(1);
+    /// assert_eq!(heap.peek(), Some(&1));
+    /// ```
+    pub fn peek(&self) -> Option<&T> {
+        self.storage.first()
+    }
+
+    /// Adds `x` to the heap as the last leaf, and bubbles it up until its
+    /// parent precedes it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::heaps::{BinaryHeap, Order};
+    /// let mut heap = BinaryHeap::initialize(Order::Max);
+    /// heap.add(1);
+    /// heap.add(3);
+    /// assert_eq!(heap.size(), 2);
+    /// assert_eq!(heap.peek(), Some(&3));
+    /// ```
+    pub fn add(&mut self, x: T) {
+        self.storage.push(x);
+        self.bubble_up(self.size() - 1);
+    }
+
+    /// Removes and returns the value at the root, the smallest one for a
+    /// `Order::Min` heap and the largest one for a `Order::Max` heap. The last
+    /// leaf takes its place and is trickled down. Returns `None` if the heap is
+    /// empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::heaps::{BinaryHeap, Order};
+    /// let mut heap = BinaryHeap::from_vec(vec![2, 0, 1], Order::Min);
+    /// assert_eq!(heap.remove(), Some(0));
+    /// assert_eq!(heap.remove(), Some(1));
+    /// assert_eq!(heap.remove(), Some(2));
+    /// assert_eq!(heap.remove(), None);
+    /// ```
+    pub fn remove(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let y = self.storage.swap_remove(0);
+        self.trickle_down(0, self.size());
+
+        if self.is_too_large() {
+            self.resize();
+        }
+        Some(y)
+    }
+
+    /// Consumes the heap and returns its values in the order in which `remove`
+    /// would have returned them: increasing for `Order::Min`, and decreasing
+    /// for `Order::Max`. Sorts in place, in *O(n log n)* time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::heaps::{BinaryHeap, Order};
+    /// let heap = BinaryHeap::from_vec(vec![3, 1, 4, 1, 5], Order::Min);
+    /// assert_eq!(heap.into_sorted_vec(), [1, 1, 3, 4, 5]);
+    /// ```
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        // Moves the root behind the shrinking heap, which leaves the values in
+        // the reverse order of removal.
+        for end in (1..self.size()).rev() {
+            self.storage.swap(0, end);
+            self.trickle_down(0, end);
+        }
+        self.storage.reverse();
+        self.storage
+    }
+
+    /// Returns `true` if `x` must be removed before `y`.
+    fn precedes(&self, x: &T, y: &T) -> bool {
+        match self.order {
+            Order::Min => x < y,
+            Order::Max => x > y,
+        }
+    }
+
+    /// Swaps the value at `i` with its parent while it precedes it.
+    fn bubble_up(&mut self, i: usize) {
+        let mut i = i;
+        while i > 0 {
+            let p = parent(i);
+            if !self.precedes(&self.storage[i], &self.storage[p]) {
+                break;
+            }
+            self.storage.swap(i, p);
+            i = p;
+        }
+    }
+
+    /// Swaps the value at `i` with its preceding child while the child
+    /// precedes it, considering only the first `n` positions as the heap.
+    fn trickle_down(&mut self, i: usize, n: usize) {
+        let mut i = i;
+        loop {
+            let mut j = i;
+            for child in [left(i), right(i)] {
+                if child < n && self.precedes(&self.storage[child], &self.storage[j]) {
+                    j = child;
+                }
+            }
+            if j == i {
+                break;
+            }
+            self.storage.swap(i, j);
+            i = j;
+        }
+    }
+
+    fn is_too_large(&self) -> bool {
+        self.storage.capacity() >= 3 * self.size()
+    }
+
+    fn resize(&mut self) {
+        self.storage.shrink_to(2 * self.size());
+    }
+}
+
+impl<T: Ord> Queue<T> for BinaryHeap<T> {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn add(&mut self, x: T) {
+        self.add(x)
+    }
+
+    fn remove(&mut self) -> Option<T> {
+        self.remove()
+    }
+}
+
+/// Returns the index of the left child of the node at index `i`.
+fn left(i: usize) -> usize {
+    2 * i + 1
+}
+
+/// Returns the index of the right child of the node at index `i`.
+fn right(i: usize) -> usize {
+    2 * i + 2
+}
+
+/// Returns the index of the parent of the node at index `i`, which must not be
+/// the root.
+fn parent(i: usize) -> usize {
+    (i - 1) / 2
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_pcg::Pcg64Mcg;
+
+    /// Checks that every node of `heap` precedes or equals its children.
+    fn check_heap_property<T: Ord>(heap: &BinaryHeap<T>) {
+        for i in 1..heap.size() {
+            assert!(!heap.precedes(&heap.storage[i], &heap.storage[parent(i)]));
+        }
+    }
+
+    #[test]
+    fn initialize_is_empty() {
+        let mut heap: BinaryHeap<i32> = BinaryHeap::initialize(Order::Min);
+        assert_eq!(heap.size(), 0);
+        assert_eq!(heap.peek(), None);
+        assert_eq!(heap.remove(), None);
+    }
+
+    #[test]
+    fn indexes_follow_eytzinger_layout() {
+        assert_eq!((left(0), right(0)), (1, 2));
+        assert_eq!((left(2), right(2)), (5, 6));
+        assert_eq!(parent(5), 2);
+        assert_eq!(parent(6), 2);
+        assert_eq!(parent(1), 0);
+    }
+
+    #[test]
+    fn min_and_max_orders() {
+        let values = [5, 3, 8, 1, 9, 2, 7];
+        let mut min_heap = BinaryHeap::initialize(Order::Min);
+        let mut max_heap = BinaryHeap::initialize(Order::Max);
+        for x in values {
+            min_heap.add(x);
+            max_heap.add(x);
+            check_heap_property(&min_heap);
+            check_heap_property(&max_heap);
+        }
+        assert_eq!(min_heap.peek(), Some(&1));
+        assert_eq!(max_heap.peek(), Some(&9));
+
+        let drained: Vec<i32> = std::iter::from_fn(|| min_heap.remove()).collect();
+        assert_eq!(drained, [1, 2, 3, 5, 7, 8, 9]);
+        let drained: Vec<i32> = std::iter::from_fn(|| max_heap.remove()).collect();
+        assert_eq!(drained, [9, 8, 7, 5, 3, 2, 1]);
+    }
+
+    #[test]
+    fn from_vec_heapifies() {
+        let heap = BinaryHeap::from_vec((0..100).rev().collect(), Order::Min);
+        check_heap_property(&heap);
+        assert_eq!(heap.peek(), Some(&0));
+        let heap = BinaryHeap::from_vec((0..100).collect(), Order::Max);
+        check_heap_property(&heap);
+        assert_eq!(heap.peek(), Some(&99));
+        let heap: BinaryHeap<i32> = BinaryHeap::from_vec(vec![], Order::Max);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn into_sorted_vec_keeps_duplicates() {
+        let values = vec![4, 1, 4, 2, 1, 3];
+        let heap = BinaryHeap::from_vec(values.clone(), Order::Min);
+        assert_eq!(heap.into_sorted_vec(), [1, 1, 2, 3, 4, 4]);
+        let heap = BinaryHeap::from_vec(values, Order::Max);
+        assert_eq!(heap.into_sorted_vec(), [4, 4, 3, 2, 1, 1]);
+    }
+
+    #[test]
+    fn remove_shrinks_storage() {
+        let mut heap = BinaryHeap::from_vec((0..1000).collect(), Order::Min);
+        while heap.size() > 10 {
+            heap.remove();
+        }
+        assert!(heap.storage.capacity() < 3 * heap.size());
+    }
+
+    #[test]
+    fn matches_sorting() {
+        let mut rng = Pcg64Mcg::seed_from_u64(25);
+        let mut heap = BinaryHeap::initialize(Order::Min);
+        let mut expected = Vec::new();
+        for _ in 0..2000 {
+            if rng.random_bool(0.6) {
+                let x = rng.random_range(0..500);
+                heap.add(x);
+                expected.push(x);
+                expected.sort_unstable_by(|a, b| b.cmp(a));
+            } else {
+                assert_eq!(heap.remove(), expected.pop());
+            }
+            assert_eq!(heap.peek(), expected.last());
+        }
+        check_heap_property(&heap);
+        expected.reverse();
+        assert_eq!(heap.into_sorted_vec(), expected);
+    }
+}
@@ -0,0 +1,245 @@
+//! The interfaces of the book's first chapter, shared by the data structures.
+//!
+//! Every data structure keeps its own inherent methods, named after the book as
+//! closely as the structure allows. These traits gather the common subsets of
+//! them under a single name and signature, so code can be generic over the
+//! backing structure and implementations can be swapped freely.
+//!
+//! `USet` and `SSet` return found elements by value, because some sets, like
+//! `BinarySearchTree`, keep their elements behind a `RefCell` and can't lend
+//! plain references to them.
+
+
+/// A sequence of elements with indexed access, insertion and removal.
+pub trait List<T> {
+    /// Returns the number of elements in the list.
+    fn size(&self) -> usize;
+
+    /// Returns a shared reference to the element in the position `i`, or
+    /// `None` if `i` is out of bounds.
+    fn get(&self, i: usize) -> Option<&T>;
+
+    /// Replaces the element in the position `i` with `x` and returns the
+    /// original element. Returns `None` if `i` is out of bounds.
+    fn set(&mut self, i: usize, x: T) -> Option<T>;
+
+    /// Inserts `x` in the position `i`, shifting the elements after it. Panics
+    /// if `i` is greater than `self.size()`.
+    fn add(&mut self, i: usize, x: T);
+
+    /// Removes the element in the position `i` and returns it, shifting the
+    /// elements after it. Returns `None` if `i` is out of bounds.
+    fn remove(&mut self, i: usize) -> Option<T>;
+}
+
+
+/// A collection that decides which element leaves first, be it the oldest
+/// (FIFO), the newest (LIFO) or the one with highest priority.
+pub trait Queue<T> {
+    /// Returns the number of elements in the queue.
+    fn size(&self) -> usize;
+
+    /// Adds `x` to the queue.
+    fn add(&mut self, x: T);
+
+    /// Removes the next element according to the queue discipline and returns
+    /// it. Returns `None` if the queue is empty.
+    fn remove(&mut self) -> Option<T>;
+}
+
+
+/// A sequence of elements with access to both of its ends.
+pub trait Deque<T> {
+    /// Returns the number of elements in the deque.
+    fn size(&self) -> usize;
+
+    /// Inserts `x` at the front of the deque.
+    fn add_first(&mut self, x: T);
+
+    /// Inserts `x` at the back of the deque.
+    fn add_last(&mut self, x: T);
+
+    /// Removes the element at the front of the deque and returns it. Returns
+    /// `None` if the deque is empty.
+    fn remove_first(&mut self) -> Option<T>;
+
+    /// Removes the element at the back of the deque and returns it. Returns
+    /// `None` if the deque is empty.
+    fn remove_last(&mut self) -> Option<T>;
+}
+
+
+/// An unordered set of unique elements.
+pub trait USet<T> {
+    /// Returns the number of elements in the set.
+    fn size(&self) -> usize;
+
+    /// Adds `x` to the set. Returns `false` if an element equal to `x` is
+    /// already in the set, otherwise `true`.
+    fn add(&mut self, x: T) -> bool;
+
+    /// Removes the element equal to `x` from the set. Returns `false` if there
+    /// is no such element, otherwise `true`.
+    fn remove(&mut self, x: &T) -> bool;
+
+    /// Returns the element of the set equal to `x`, or `None` if there is no
+    /// such element.
+    fn find(&self, x: &T) -> Option<T>;
+}
+
+
+/// A sorted set of unique elements.
+pub trait SSet<T> {
+    /// Returns the number of elements in the set.
+    fn size(&self) -> usize;
+
+    /// Adds `x` to the set. Returns `false` if an element equal to `x` is
+    /// already in the set, otherwise `true`.
+    fn add(&mut self, x: T) -> bool;
+
+    /// Removes the element equal to `x` from the set. Returns `false` if there
+    /// is no such element, otherwise `true`.
+    fn remove(&mut self, x: &T) -> bool;
+
+    /// Returns the smallest element of the set that is greater than or equal
+    /// to `x`, or `None` if there is no such element.
+    fn find(&self, x: &T) -> Option<T>;
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arena_binary_search_tree::ArenaBinarySearchTree;
+    use crate::array_deque::ArrayDeque;
+    use crate::array_queue::ArrayQueue;
+    use crate::array_stack::ArrayStack;
+    use crate::binary_search_tree::BinarySearchTree;
+    use crate::chained_hash_table::ChainedHashTable;
+    use crate::cuckoo_hash_table::CuckooHashTable;
+    use crate::doubly_linked_list::DLList;
+    use crate::dual_array_deque::DualArrayDeque;
+    use crate::hashers::Multiplicative;
+    use crate::heaps::{BinaryHeap, Order};
+    use crate::linear_hash_table::LinearHashTable;
+    use crate::red_black_tree::RedBlackTree;
+    use crate::rootish_array_stack::RootishArrayStack;
+    use crate::scapegoat_tree::ScapegoatTree;
+    use crate::singly_linked_list::SLList;
+    use crate::skiplist::{SkiplistList, SkiplistSSet};
+    use crate::space_efficient_list::SEList;
+    use crate::treap::Treap;
+
+    fn exercise_list(list: &mut impl List<i32>) {
+        for x in 0..10 {
+            list.add(list.size() / 2, x);
+        }
+        assert_eq!(list.size(), 10);
+        assert_eq!(list.get(0), Some(&1));
+        assert_eq!(list.get(9), Some(&0));
+        assert_eq!(list.get(10), None);
+        assert_eq!(list.set(4, 50), Some(9));
+        assert_eq!(list.set(10, 100), None);
+        assert_eq!(list.remove(4), Some(50));
+        assert_eq!(list.remove(9), None);
+
+        let contents = (0..list.size())
+            .map(|i| *list.get(i).unwrap())
+            .collect::<Vec<i32>>();
+        assert_eq!(contents, [1, 3, 5, 7, 8, 6, 4, 2, 0]);
+    }
+
+    fn drain_queue(queue: &mut impl Queue<i32>) -> Vec<i32> {
+        for x in 0..5 {
+            queue.add(x);
+        }
+        assert_eq!(queue.size(), 5);
+        std::iter::from_fn(|| queue.remove()).collect()
+    }
+
+    fn exercise_deque(deque: &mut impl Deque<i32>) {
+        deque.add_first(1);
+        deque.add_last(2);
+        deque.add_first(0);
+        deque.add_last(3);
+        assert_eq!(deque.size(), 4);
+        assert_eq!(deque.remove_first(), Some(0));
+        assert_eq!(deque.remove_last(), Some(3));
+        assert_eq!(deque.remove_last(), Some(2));
+        assert_eq!(deque.remove_first(), Some(1));
+        assert_eq!(deque.remove_first(), None);
+        assert_eq!(deque.remove_last(), None);
+    }
+
+    fn exercise_uset(set: &mut impl USet<u64>) {
+        assert!(set.add(3));
+        assert!(set.add(1));
+        assert!(!set.add(3));
+        assert_eq!(set.size(), 2);
+        assert_eq!(set.find(&3), Some(3));
+        assert_eq!(set.find(&2), None);
+        assert!(set.remove(&3));
+        assert!(!set.remove(&3));
+        assert_eq!(set.size(), 1);
+    }
+
+    fn exercise_sset(set: &mut impl SSet<i32>) {
+        for x in [5, 1, 9, 3] {
+            assert!(set.add(x));
+        }
+        assert!(!set.add(5));
+        assert_eq!(set.size(), 4);
+        assert_eq!(set.find(&0), Some(1));
+        assert_eq!(set.find(&5), Some(5));
+        assert_eq!(set.find(&6), Some(9));
+        assert_eq!(set.find(&10), None);
+        assert!(set.remove(&5));
+        assert!(!set.remove(&5));
+        assert_eq!(set.find(&4), Some(9));
+    }
+
+    #[test]
+    fn lists() {
+        exercise_list(&mut ArrayStack::initialize());
+        exercise_list(&mut ArrayDeque::initialize());
+        exercise_list(&mut DualArrayDeque::initialize());
+        exercise_list(&mut RootishArrayStack::initialize());
+        exercise_list(&mut SEList::initialize(2));
+        exercise_list(&mut SkiplistList::with_seed(1));
+    }
+
+    #[test]
+    fn queues() {
+        assert_eq!(drain_queue(&mut ArrayQueue::initialize()), [0, 1, 2, 3, 4]);
+        assert_eq!(drain_queue(&mut DLList::new()), [0, 1, 2, 3, 4]);
+        assert_eq!(drain_queue(&mut SLList::initialize()), [4, 3, 2, 1, 0]);
+        assert_eq!(drain_queue(&mut BinaryHeap::initialize(Order::Min)), [0, 1, 2, 3, 4]);
+        assert_eq!(drain_queue(&mut BinaryHeap::initialize(Order::Max)), [4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn deques() {
+        exercise_deque(&mut ArrayDeque::initialize());
+        exercise_deque(&mut DualArrayDeque::initialize());
+        exercise_deque(&mut DLList::new());
+    }
+
+    #[test]
+    fn usets() {
+        exercise_uset(&mut ChainedHashTable::initialize(1, Multiplicative::with_seed(1)));
+        exercise_uset(&mut LinearHashTable::initialize(Multiplicative::with_seed(2)));
+        exercise_uset(&mut CuckooHashTable::with_seed(
+            1, Multiplicative::with_seed(3), Multiplicative::with_seed(4), 5
+        ));
+    }
+
+    #[test]
+    fn ssets() {
+        exercise_sset(&mut BinarySearchTree::new());
+        exercise_sset(&mut ArenaBinarySearchTree::new());
+        exercise_sset(&mut SkiplistSSet::with_seed(1));
+        exercise_sset(&mut Treap::with_seed(1));
+        exercise_sset(&mut ScapegoatTree::new());
+        exercise_sset(&mut RedBlackTree::new());
+    }
+}
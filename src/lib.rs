@@ -1,17 +1,35 @@
+pub mod interfaces;
+
 pub mod array_stack;
 
 pub mod circular_vec;
 pub mod array_queue;
 pub mod array_deque;
+pub mod dual_array_deque;
+pub mod rootish_array_stack;
 
 pub mod singly_linked_list;
 pub mod doubly_linked_list;
+pub mod space_efficient_list;
+
+pub mod skiplist;
 
 pub mod hashers;
+pub mod hasher_quality;
 pub mod chained_hash_table;
 pub mod linear_hash_table;
+pub mod cuckoo_hash_table;
+pub mod chained_hash_map;
+pub mod linear_hash_map;
 
 pub mod binary_tree;
 pub mod binary_search_tree;
+pub mod arena_tree;
+pub mod arena_binary_search_tree;
+pub mod treap;
+pub mod scapegoat_tree;
+pub mod red_black_tree;
+
+pub mod heaps;
 
 pub mod adjacency_matrix;
@@ -0,0 +1,446 @@
+//! A key-value map backed by a hash table with linear probing.
+//!
+//! It shares its table with `LinearHashTable`: every slot of the table stores
+//! at most one key-value pair, collisions are solved by probing the next slots,
+//! and removed pairs leave a tombstone behind so that probe sequences are not
+//! cut short. The table is rebuilt when it runs out of empty slots or when it
+//! becomes too sparse, and its tombstones are cleaned up in place once they
+//! take more than `DEFAULT_MAX_TOMBSTONES` of it.
+
+use std::borrow::Borrow;
+use std::hash::Hash;
+use std::mem;
+use std::ops::Index;
+use crate::hashers::DimHasher;
+use crate::linear_hash_table::{Probes, Probing};
+
+
+/// A key-value map backed by a hash table with linear probing.
+#[derive(Debug)]
+pub struct LinearHashMap<K, V, H: DimHasher> {
+    probes: Probes<K, (K, V), H>,
+}
+
+impl<K: Hash + Eq, V, H: DimHasher> LinearHashMap<K, V, H> {
+    /// Creates a new, empty map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::linear_hash_map::LinearHashMap;
+    /// # use ods::hashers::Multiplicative;
+    /// let map: LinearHashMap<u64, char, _> = LinearHashMap::initialize(Multiplicative::new());
+    /// ```
+    pub fn initialize(hasher: H) -> Self {
+        Self { probes: Probes::with_probing(hasher, Probing::Linear) }
+    }
+
+    /// Returns the slot where the probe sequence of `key` starts.
+    pub fn hash<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Hash + ?Sized,
+    {
+        self.probes.hash(key)
+    }
+
+    /// Returns the number of key-value pairs in the map.
+    pub fn len(&self) -> usize {
+        self.probes.len()
+    }
+
+    /// Returns `true` if the map has no key-value pairs, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the map has a value for `key`, `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::linear_hash_map::LinearHashMap;
+    /// # use ods::hashers::Multiplicative;
+    /// let mut map = LinearHashMap::initialize(Multiplicative::new());
+    /// map.insert(String::from("a"), 1);
+    /// assert!(map.contains_key("a"));
+    /// assert!(!map.contains_key("b"));
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.probes.position(key).is_some()
+    }
+
+    /// Returns a shared reference to the value of `key`, or `None` if `key` is
+    /// not in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::linear_hash_map::LinearHashMap;
+    /// # use ods::hashers::Multiplicative;
+    /// let mut map = LinearHashMap::initialize(Multiplicative::new());
+    /// map.insert(0, 'a');
+    /// assert_eq!(map.get(&0), Some(&'a'));
+    /// assert_eq!(map.get(&1), None);
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let i = self.probes.position(key)?;
+        Some(&self.probes.item(i).1)
+    }
+
+    /// Returns a mutable reference to the value of `key`, or `None` if `key` is
+    /// not in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::linear_hash_map::LinearHashMap;
+    /// # use ods::hashers::Multiplicative;
+    /// let mut map = LinearHashMap::initialize(Multiplicative::new());
+    /// map.insert(0, 'a');
+    /// *map.get_mut(&0).unwrap() = 'b';
+    /// assert_eq!(map.get(&0), Some(&'b'));
+    /// ```
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let i = self.probes.position(key)?;
+        Some(&mut self.probes.item_mut(i).1)
+    }
+
+    /// Inserts `value` for `key` and returns the value that `key` had before,
+    /// or `None` if `key` was not in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::linear_hash_map::LinearHashMap;
+    /// # use ods::hashers::Multiplicative;
+    /// let mut map = LinearHashMap::initialize(Multiplicative::new());
+    /// assert_eq!(map.insert(0, 'a'), None);
+    /// assert_eq!(map.insert(0, 'b'), Some('a'));
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            },
+        }
+    }
+
+    /// Removes `key` from the map and returns its value, or `None` if `key`
+    /// was not in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::linear_hash_map::LinearHashMap;
+    /// # use ods::hashers::Multiplicative;
+    /// let mut map = LinearHashMap::initialize(Multiplicative::new());
+    /// map.insert(0, 'a');
+    /// assert_eq!(map.remove(&0), Some('a'));
+    /// assert_eq!(map.remove(&0), None);
+    /// ```
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let i = self.probes.position(key)?;
+        Some(self.probes.remove_at(i).1)
+    }
+
+    /// Returns the entry of `key` in the map, to inspect or modify it in place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::linear_hash_map::LinearHashMap;
+    /// # use ods::hashers::Multiplicative;
+    /// let mut map = LinearHashMap::initialize(Multiplicative::new());
+    /// for word in ["a", "b", "a"] {
+    ///     *map.entry(word).or_insert(0) += 1;
+    /// }
+    /// assert_eq!(map[&"a"], 2);
+    /// assert_eq!(map[&"b"], 1);
+    /// ```
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, H> {
+        match self.probes.position(&key) {
+            Some(i) => Entry::Occupied(OccupiedEntry { map: self, i }),
+            None => Entry::Vacant(VacantEntry { map: self, key }),
+        }
+    }
+}
+
+impl<K, Q, V, H> Index<&Q> for LinearHashMap<K, V, H>
+where
+    K: Hash + Eq + Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+    H: DimHasher,
+{
+    type Output = V;
+
+    /// Returns a shared reference to the value of `key`. Panics if `key` is not
+    /// in the map.
+    fn index(&self, key: &Q) -> &Self::Output {
+        self.get(key).expect("key should be in the map")
+    }
+}
+
+
+/// The entry of a key in a `LinearHashMap`, which may or may not hold a value.
+pub enum Entry<'a, K, V, H: DimHasher> {
+    Occupied(OccupiedEntry<'a, K, V, H>),
+    Vacant(VacantEntry<'a, K, V, H>),
+}
+
+impl<'a, K: Hash + Eq, V, H: DimHasher> Entry<'a, K, V, H> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Returns a mutable reference to the value of the entry, inserting
+    /// `default` first if the entry is vacant.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Returns a mutable reference to the value of the entry, inserting the
+    /// result of `f` first if the entry is vacant.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, f: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(f()),
+        }
+    }
+
+    /// Returns a mutable reference to the value of the entry, inserting
+    /// `V::default()` first if the entry is vacant.
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Calls `f` with the value of the entry if it is occupied, and returns the
+    /// entry.
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+
+/// An entry of a `LinearHashMap` that holds a value.
+pub struct OccupiedEntry<'a, K, V, H: DimHasher> {
+    map: &'a mut LinearHashMap<K, V, H>,
+    i: usize,
+}
+
+impl<'a, K: Hash + Eq, V, H: DimHasher> OccupiedEntry<'a, K, V, H> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        &self.map.probes.item(self.i).0
+    }
+
+    /// Returns a shared reference to the value of the entry.
+    pub fn get(&self) -> &V {
+        &self.map.probes.item(self.i).1
+    }
+
+    /// Returns a mutable reference to the value of the entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.probes.item_mut(self.i).1
+    }
+
+    /// Returns a mutable reference to the value of the entry, bound to the
+    /// lifetime of the map.
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.probes.item_mut(self.i).1
+    }
+
+    /// Replaces the value of the entry with `value` and returns the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the entry from the map and returns its value.
+    pub fn remove(self) -> V {
+        self.map.probes.remove_at(self.i).1
+    }
+}
+
+
+/// An entry of a `LinearHashMap` that holds no value.
+pub struct VacantEntry<'a, K, V, H: DimHasher> {
+    map: &'a mut LinearHashMap<K, V, H>,
+    key: K,
+}
+
+impl<'a, K: Hash + Eq, V, H: DimHasher> VacantEntry<'a, K, V, H> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Inserts `value` for the key of the entry and returns a mutable
+    /// reference to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let i = self.map.probes.push((self.key, value));
+        &mut self.map.probes.item_mut(i).1
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::hashers;
+    use crate::linear_hash_table::Entry as Slot;
+    use rand::{Rng, SeedableRng};
+    use rand_pcg::Pcg64Mcg;
+
+    fn build_test_map() -> LinearHashMap<u64, char, hashers::Multiplicative> {
+        let mut map = LinearHashMap::initialize(hashers::Multiplicative::with_seed(37));
+        for (key, value) in [(3, 'c'), (1, 'a'), (2, 'b'), (18446744073709551615, 'z')] {
+            map.insert(key, value);
+        }
+        map
+    }
+
+    #[test]
+    fn initialize() {
+        let map: LinearHashMap<u64, (), _> =
+            LinearHashMap::initialize(hashers::Multiplicative::new());
+        assert_eq!(map.probes.dim, 1);
+        assert_eq!(map.probes.table.len(), 2);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn insert_returns_old_value() {
+        let mut map = build_test_map();
+        assert_eq!(map.len(), 4);
+        assert_eq!(map.insert(1, 'x'), Some('a'));
+        assert_eq!(map.insert(4, 'd'), None);
+        assert_eq!(map.len(), 5);
+        assert_eq!(map.get(&1), Some(&'x'));
+    }
+
+    #[test]
+    fn get_and_get_mut() {
+        let mut map = build_test_map();
+        assert_eq!(map.get(&18446744073709551615), Some(&'z'));
+        assert_eq!(map.get(&0), None);
+        map.get_mut(&2).unwrap().make_ascii_uppercase();
+        assert_eq!(map.get(&2), Some(&'B'));
+        assert!(map.get_mut(&0).is_none());
+    }
+
+    #[test]
+    fn remove_leaves_tombstone() {
+        let mut map = build_test_map();
+        let i = map.probes.position(&3).unwrap();
+        assert_eq!(map.remove(&3), Some('c'));
+        assert_eq!(map.probes.table[i], Slot::Del);
+        assert_eq!(map.remove(&3), None);
+        assert_eq!(map.len(), 3);
+        assert!(!map.contains_key(&3));
+    }
+
+    #[test]
+    fn index_returns_value() {
+        let map = build_test_map();
+        assert_eq!(map[&1], 'a');
+        assert_eq!(map[&3], 'c');
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_missing_key_panics() {
+        let map = build_test_map();
+        let _ = map[&0];
+    }
+
+    #[test]
+    fn entry_api() {
+        let mut map = build_test_map();
+        assert_eq!(map.entry(1).key(), &1);
+        assert_eq!(*map.entry(1).or_insert('x'), 'a');
+        assert_eq!(*map.entry(5).or_insert('e'), 'e');
+        map.entry(5).and_modify(|v| *v = 'E').or_insert('x');
+        assert_eq!(map[&5], 'E');
+
+        match map.entry(2) {
+            Entry::Occupied(entry) => assert_eq!(entry.remove(), 'b'),
+            Entry::Vacant(_) => panic!("2 should be in the map"),
+        }
+        assert!(!map.contains_key(&2));
+        assert_eq!(map.len(), 4);
+
+        let mut counts: LinearHashMap<char, usize, _> =
+            LinearHashMap::initialize(hashers::Multiplicative::with_seed(5));
+        for c in "abracadabra".chars() {
+            *counts.entry(c).or_default() += 1;
+        }
+        assert_eq!(counts[&'a'], 5);
+        assert_eq!(counts[&'r'], 2);
+        assert_eq!(counts.len(), 5);
+    }
+
+    #[test]
+    fn resize_keeps_invariants() {
+        let mut map = LinearHashMap::initialize(hashers::Multiplicative::with_seed(13));
+        for key in 0..1000u64 {
+            map.insert(key, key * 2);
+            // The invariant is restored before each push, so it may be off by one.
+            assert!(map.probes.table.len() >= 2 * map.probes.q);
+        }
+        for key in 0..990u64 {
+            assert_eq!(map.remove(&key), Some(key * 2));
+            assert!(map.probes.shrink_invariant_holds());
+        }
+        assert!(map.probes.table.len() <= 8 * map.len());
+    }
+
+    #[test]
+    fn matches_std_hash_map() {
+        let mut rng = Pcg64Mcg::seed_from_u64(63);
+        let mut map = LinearHashMap::initialize(hashers::Tabulation::with_seed(4, 63));
+        let mut std_map = HashMap::new();
+
+        for _ in 0..5000 {
+            let key = rng.random_range(0..200u32).to_string();
+            let value = rng.random::<u8>();
+            if rng.random_bool(0.5) {
+                assert_eq!(map.insert(key.clone(), value), std_map.insert(key, value));
+            } else {
+                assert_eq!(map.remove(key.as_str()), std_map.remove(key.as_str()));
+            }
+            assert_eq!(map.len(), std_map.len());
+        }
+        for (key, value) in std_map.iter() {
+            assert_eq!(map.get(key.as_str()), Some(value));
+        }
+    }
+}
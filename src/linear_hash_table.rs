@@ -1,23 +1,76 @@
+//! A hash table of `u64` keys that solves collisions with linear probing.
+//!
+//! By default, removed keys leave an `Entry::Del` tombstone behind so that the
+//! probe sequences that go through them are not cut short. Tombstones are
+//! cleaned up in place once they take more than a configurable fraction of the
+//! table. Alternatively, the table can use Robin Hood probing, which keeps keys
+//! sorted by displacement and removes them by shifting their successors back,
+//! so no tombstones are ever left.
+//!
+//! The slots themselves, and the logic that places, removes and rehashes keys,
+//! live in `Probes`, which `LinearHashMap` shares to store key-value pairs.
+
+use std::borrow::Borrow;
+use std::hash::Hash;
+use std::marker::PhantomData;
 use std::mem;
-use crate::hashers::DimHasher;
+use crate::hashers::{self, DimHasher, Keyed};
+use crate::interfaces::USet;
 
 
+/// The default fraction of tombstones in the table that triggers a cleanup.
+pub const DEFAULT_MAX_TOMBSTONES: f64 = 0.25;
+
 #[derive(Debug)]
 pub struct LinearHashTable<H: DimHasher> {
-    dim: u32,
-    table: Vec<Entry<u64>>,
-    q: usize,
+    probes: Probes<u64, u64, H>,
+}
+
+/// The slots of a hash table with linear probing, each empty, holding an item
+/// `E` hashed by its key `K`, or holding the tombstone of a removed item.
+#[derive(Debug)]
+pub(crate) struct Probes<K, E, H: DimHasher> {
+    pub(crate) dim: u32,
+    pub(crate) table: Vec<Entry<E>>,
+    pub(crate) q: usize,
     len: usize,
     hasher: H,
+    probing: Probing,
+    max_tombstones: f64,
+    key: PhantomData<K>,
 }
 
 #[derive(Clone, PartialEq, Debug)]
-enum Entry<T> {
+pub(crate) enum Entry<T> {
     Val(T),
     Nil,
     Del,
 }
 
+/// The strategy used to place keys along their probe sequences.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Probing {
+    /// Keys take the first free slot after their hash. Removals leave
+    /// tombstones.
+    #[default]
+    Linear,
+    /// Keys that are further from their hash take the slots of the keys that
+    /// are closer to theirs. Removals shift the following keys back.
+    RobinHood,
+}
+
+/// Probe-length statistics of a `LinearHashTable`.
+///
+/// The probe length of a key is the number of slots that a successful search
+/// for it inspects, that is, its distance to its hash slot plus one.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ProbeStats {
+    pub mean: f64,
+    pub max: usize,
+    pub tombstones: usize,
+    pub load_factor: f64,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Error {
     KeyAlreadyExists,
@@ -26,33 +79,49 @@ pub enum Error {
 
 impl<H: DimHasher> LinearHashTable<H> {
     pub fn initialize(hasher: H) -> Self {
-        Self { dim: 1, table: Self::new_table(1), q: 0, len: 0, hasher }
+        Self::with_probing(hasher, Probing::Linear)
     }
 
-    fn new_table(dim: u32) -> Vec<Entry<u64>> {
-        assert!(dim > 0, "dim == 0");
-        vec![Entry::Nil; 2usize.pow(dim)]
+    /// Creates an empty table that places keys according to `probing`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::linear_hash_table::{LinearHashTable, Probing};
+    /// # use ods::hashers::Multiplicative;
+    /// let mut lht = LinearHashTable::with_probing(Multiplicative::new(), Probing::RobinHood);
+    /// lht.add(42).unwrap();
+    /// assert!(lht.contains(42));
+    /// ```
+    pub fn with_probing(hasher: H, probing: Probing) -> Self {
+        Self { probes: Probes::with_probing(hasher, probing) }
     }
 
     pub fn hash(&self, x: u64) -> usize {
-        let y = self.hasher.hash(x, self.dim);
-        y.try_into().expect("Unable to fit u64 into usize")
+        self.probes.hash(&x)
     }
 
     pub fn len(&self) -> usize {
-        self.len
+        self.probes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn probing(&self) -> Probing {
+        self.probes.probing
+    }
+
+    /// Sets the fraction of the table that tombstones may take before they are
+    /// cleaned up. Panics if `fraction` is not in `(0, 1]`.
+    pub fn set_max_tombstones(&mut self, fraction: f64) {
+        assert!(fraction > 0.0 && fraction <= 1.0, "fraction must be in (0, 1]");
+        self.probes.max_tombstones = fraction;
     }
 
     pub fn contains(&self, x: u64) -> bool {
-        let mut i = self.hash(x);
-        loop {
-            match &self.table[i] {
-                Entry::Val(y) => if *y == x { return true; },
-                Entry::Nil => return false,
-                Entry::Del => (),
-            }
-            i = self.loop_index(i + 1);
-        }
+        self.probes.position(&x).is_some()
     }
 
     pub fn add(&mut self, x: u64) -> Result<(), Error> {
@@ -60,38 +129,175 @@ impl<H: DimHasher> LinearHashTable<H> {
             Err(Error::KeyAlreadyExists)
         }
         else {
-            if !self.grow_invariant_holds() {
-                self.resize();
-            }
-            if let Entry::Nil = self.insert(x) {
-                self.q += 1;
-            }
-            self.len += 1;
+            self.probes.push(x);
             Ok(())
         }
     }
 
     pub fn remove(&mut self, x: u64) -> Result<(), Error> {
+        let i = self.probes.position(&x).ok_or(Error::KeyNotFound)?;
+        self.probes.remove_at(i);
+        Ok(())
+    }
+
+    pub fn iter(&self) -> LinearHashTableIterator<'_, H> {
+        LinearHashTableIterator { ref_to: self, index: 0}
+    }
+
+    /// Returns the probe-length statistics of the keys in the table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::linear_hash_table::LinearHashTable;
+    /// # use ods::hashers::Multiplicative;
+    /// let mut lht = LinearHashTable::initialize(Multiplicative::new());
+    /// lht.add(1).unwrap();
+    /// let stats = lht.probe_stats();
+    /// assert_eq!(stats.mean, 1.0);
+    /// assert_eq!(stats.max, 1);
+    /// assert_eq!(stats.tombstones, 0);
+    /// ```
+    pub fn probe_stats(&self) -> ProbeStats {
+        let probes = &self.probes;
+        let lengths = probes.table.iter()
+            .enumerate()
+            .filter_map(|(i, entry)| match entry {
+                Entry::Val(x) => Some(probes.displacement(x, i) + 1),
+                _ => None,
+            })
+            .collect::<Vec<usize>>();
+        let mean = match lengths.len() {
+            0 => 0.0,
+            n => lengths.iter().sum::<usize>() as f64 / n as f64,
+        };
+        ProbeStats {
+            mean,
+            max: lengths.into_iter().max().unwrap_or(0),
+            tombstones: probes.q - probes.len,
+            load_factor: probes.len as f64 / probes.table.len() as f64,
+        }
+    }
+}
+
+impl<K: Hash + Eq, E: Keyed<K>, H: DimHasher> Probes<K, E, H> {
+    /// Creates an empty table of 2 slots that places items according to
+    /// `probing`.
+    pub(crate) fn with_probing(hasher: H, probing: Probing) -> Self {
+        Self {
+            dim: 1,
+            table: Self::new_table(1),
+            q: 0,
+            len: 0,
+            hasher,
+            probing,
+            max_tombstones: DEFAULT_MAX_TOMBSTONES,
+            key: PhantomData,
+        }
+    }
+
+    fn new_table(dim: u32) -> Vec<Entry<E>> {
+        assert!(dim > 0, "dim == 0");
+        (0..2usize.pow(dim)).map(|_| Entry::Nil).collect()
+    }
+
+    /// Returns the slot where the probe sequence of `x` starts.
+    pub(crate) fn hash<Q>(&self, x: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Hash + ?Sized,
+    {
+        let y = self.hasher.hash(hashers::fold(x), self.dim);
+        y.try_into().expect("Unable to fit u64 into usize")
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the slot of the item with key `x`, or `None` if there is no
+    /// such item.
+    pub(crate) fn position<Q>(&self, x: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
         let mut i = self.hash(x);
+        let mut d = 0;
         loop {
-            match self.table[i] {
-                Entry::Val(y) => if y == x {
-                    self.table[i] = Entry::Del;
-                    self.len -= 1;
-                    if !self.shrink_invariant_holds() {
-                        self.resize();
+            match &self.table[i] {
+                Entry::Val(y) => {
+                    if y.key().borrow() == x {
+                        return Some(i);
+                    }
+                    // Robin Hood keeps displacements non-decreasing along a
+                    // run, so `x` would have taken this slot.
+                    if self.probing == Probing::RobinHood && self.displacement(y, i) < d {
+                        return None;
                     }
-                    return Ok(());
                 },
-                Entry::Nil => return Err(Error::KeyNotFound),
+                Entry::Nil => return None,
                 Entry::Del => (),
             }
             i = self.loop_index(i + 1);
+            d += 1;
         }
     }
 
-    pub fn iter(&self) -> LinearHashTableIterator<'_, H> {
-        LinearHashTableIterator { ref_to: self, index: 0}
+    /// Returns a shared reference to the item in the slot `i`.
+    pub(crate) fn item(&self, i: usize) -> &E {
+        match &self.table[i] {
+            Entry::Val(x) => x,
+            _ => panic!("slot {i} should hold a value"),
+        }
+    }
+
+    /// Returns a mutable reference to the item in the slot `i`.
+    pub(crate) fn item_mut(&mut self, i: usize) -> &mut E {
+        match &mut self.table[i] {
+            Entry::Val(x) => x,
+            _ => panic!("slot {i} should hold a value"),
+        }
+    }
+
+    /// Adds `x`, whose key must not be in the table yet, rebuilding the table
+    /// first if it runs out of empty slots. Returns the slot of `x`.
+    pub(crate) fn push(&mut self, x: E) -> usize {
+        if !self.grow_invariant_holds() {
+            self.resize();
+        }
+        let (i, entry) = self.insert(x);
+        if let Entry::Nil = entry {
+            self.q += 1;
+        }
+        self.len += 1;
+        i
+    }
+
+    /// Removes and returns the item in the slot `i`, and then rebuilds the
+    /// table if it became too sparse, or cleans up its tombstones if there
+    /// are too many of them.
+    pub(crate) fn remove_at(&mut self, i: usize) -> E {
+        let x = match self.probing {
+            Probing::Linear => mem::replace(&mut self.table[i], Entry::Del),
+            Probing::RobinHood => {
+                let x = mem::replace(&mut self.table[i], Entry::Nil);
+                self.shift_back(i);
+                self.q -= 1;
+                x
+            },
+        };
+        let Entry::Val(x) = x else {
+            panic!("slot {i} should hold a value");
+        };
+        self.len -= 1;
+        if !self.shrink_invariant_holds() {
+            self.resize();
+        }
+        else if self.has_too_many_tombstones() {
+            self.remove_tombstones();
+        }
+        x
     }
 
     fn resize(&mut self) {
@@ -101,6 +307,7 @@ impl<H: DimHasher> LinearHashTable<H> {
         }
         let mut table = Self::new_table(new_dim);
         self.dim = new_dim;
+        self.q = self.len;
         mem::swap(&mut self.table, &mut table);
 
         for x in table {
@@ -114,24 +321,107 @@ impl<H: DimHasher> LinearHashTable<H> {
         self.table.len() >= 2 * (self.q + 1)
     }
 
-    fn shrink_invariant_holds(&self) -> bool {
+    pub(crate) fn shrink_invariant_holds(&self) -> bool {
         self.table.len() <= 8 * self.len()
     }
 
-    fn insert(&mut self, x: u64) -> Entry<u64> {
-        let mut i = self.hash(x);
+    fn has_too_many_tombstones(&self) -> bool {
+        (self.q - self.len) as f64 > self.max_tombstones * self.table.len() as f64
+    }
+
+    /// Turns every tombstone into an empty slot without reallocating the table.
+    ///
+    /// Keys are then reinserted one by one, starting after a slot that was
+    /// already empty. No probe sequence crosses that slot, so each key can
+    /// only move back to a slot that the cleanup freed, and the keys placed
+    /// before it stay reachable.
+    fn remove_tombstones(&mut self) {
+        let Some(start) = self.table.iter().position(|entry| matches!(entry, Entry::Nil)) else {
+            return self.resize();
+        };
+        for entry in self.table.iter_mut() {
+            if let Entry::Del = entry {
+                *entry = Entry::Nil;
+            }
+        }
+        for k in 1..self.table.len() {
+            let i = self.loop_index(start + k);
+            if let Entry::Val(x) = mem::replace(&mut self.table[i], Entry::Nil) {
+                let _ = self.insert(x);
+            }
+        }
+        self.q = self.len;
+    }
+
+    /// Moves back the keys that follow the slot `i`, which must be empty,
+    /// until one of them is already in its hash slot or an empty slot is
+    /// reached.
+    fn shift_back(&mut self, i: usize) {
+        let mut hole = i;
+        loop {
+            let next = self.loop_index(hole + 1);
+            match &self.table[next] {
+                Entry::Val(y) if self.displacement(y, next) > 0 => {
+                    self.table.swap(hole, next);
+                    hole = next;
+                },
+                _ => break,
+            }
+        }
+    }
+
+    /// Places `x` along its probe sequence, and returns the slot where `x`
+    /// ends up, together with what the last slot filled held before.
+    fn insert(&mut self, x: E) -> (usize, Entry<E>) {
+        match self.probing {
+            Probing::Linear => self.insert_linear(x),
+            Probing::RobinHood => self.insert_robin_hood(x),
+        }
+    }
+
+    fn insert_linear(&mut self, x: E) -> (usize, Entry<E>) {
+        let mut i = self.hash(x.key());
         while let Entry::Val(_) = &self.table[i] {
             i = self.loop_index(i + 1);
         }
-        let entry = self.table[i].clone();
-        self.table[i] = Entry::Val(x);
-        entry
+        (i, mem::replace(&mut self.table[i], Entry::Val(x)))
+    }
+
+    fn insert_robin_hood(&mut self, x: E) -> (usize, Entry<E>) {
+        let mut x = x;
+        let mut i = self.hash(x.key());
+        let mut d = 0;
+        let mut placed = None;
+        loop {
+            let dy = match &self.table[i] {
+                Entry::Val(y) => self.displacement(y, i),
+                _ => {
+                    let entry = mem::replace(&mut self.table[i], Entry::Val(x));
+                    return (placed.unwrap_or(i), entry);
+                },
+            };
+            if dy < d {
+                if let Entry::Val(y) = &mut self.table[i] {
+                    mem::swap(y, &mut x);
+                }
+                placed.get_or_insert(i);
+                d = dy;
+            }
+            i = self.loop_index(i + 1);
+            d += 1;
+        }
+    }
+
+    /// Returns how many slots after its hash slot the item `x` is, if stored
+    /// at the slot `i`.
+    fn displacement(&self, x: &E, i: usize) -> usize {
+        let n = self.table.len();
+        (i + n - self.hash(x.key())) % n
     }
 
     fn loop_index(&self, i: usize) -> usize {
         i % self.table.len()
     }
-
 }
 
 pub struct LinearHashTableIterator<'a, H: DimHasher> {
@@ -143,8 +433,9 @@ impl<'a, H: DimHasher> Iterator for LinearHashTableIterator<'a, H> {
     type Item = &'a u64;
 
     fn next(&mut self) -> Option<Self::Item> {
-        while self.index < self.ref_to.table.len() {
-            let entry = &self.ref_to.table[self.index];
+        let table = &self.ref_to.probes.table;
+        while self.index < table.len() {
+            let entry = &table[self.index];
             self.index += 1;
             if let Entry::Val(x) = entry { return Some(x); }
         }
@@ -152,6 +443,24 @@ impl<'a, H: DimHasher> Iterator for LinearHashTableIterator<'a, H> {
     }
 }
 
+impl<H: DimHasher> USet<u64> for LinearHashTable<H> {
+    fn size(&self) -> usize {
+        self.len()
+    }
+
+    fn add(&mut self, x: u64) -> bool {
+        self.add(x).is_ok()
+    }
+
+    fn remove(&mut self, x: &u64) -> bool {
+        self.remove(*x).is_ok()
+    }
+
+    fn find(&self, x: &u64) -> Option<u64> {
+        self.contains(*x).then_some(*x)
+    }
+}
+
 impl<H: DimHasher> PartialEq for LinearHashTable<H> {
     fn eq(&self, other: &Self) -> bool {
         self.len() == other.len() && self.iter().all(|x| other.contains(*x))
@@ -163,63 +472,96 @@ impl<H: DimHasher> PartialEq for LinearHashTable<H> {
 mod tests {
     use crate::hashers;
     use super::*;
+    use std::collections::HashSet;
+    use rand::{Rng, SeedableRng};
+    use rand_pcg::Pcg64Mcg;
 
     #[test]
     fn iter() {
         let hasher = hashers::Multiplicative::new();
 
-        let lht1 = LinearHashTable { 
-            dim: 1, table: vec![Entry::Nil, Entry::Nil], q: 0, len: 0, hasher: hasher.clone()
+        let lht1 = LinearHashTable {
+            probes: Probes {
+                dim: 1, table: vec![Entry::Nil, Entry::Nil], q: 0, len: 0, hasher: hasher.clone(),
+                probing: Probing::Linear,
+                max_tombstones: DEFAULT_MAX_TOMBSTONES,
+                key: PhantomData,
+            },
         };
         assert_eq!(lht1.iter().collect::<Vec<&u64>>().len(), 0);
 
-        let lht2 = LinearHashTable { 
-            dim: 2, 
-            table: vec![Entry::Nil, Entry::Val(3), Entry::Nil, Entry::Val(14)], 
-            q: 2, 
-            len: 2, 
-            hasher: hasher.clone()
+        let lht2 = LinearHashTable {
+            probes: Probes {
+                dim: 2, 
+                table: vec![Entry::Nil, Entry::Val(3), Entry::Nil, Entry::Val(14)], 
+                q: 2, 
+                len: 2, 
+                hasher: hasher.clone(),
+                probing: Probing::Linear,
+                max_tombstones: DEFAULT_MAX_TOMBSTONES,
+                key: PhantomData,
+            },
         };
         assert_eq!(lht2.iter().collect::<Vec<&u64>>(), vec![&3, &14]);
 
-        let lht3 = LinearHashTable { 
-            dim: 2,
-            table: vec![Entry::Nil, Entry::Val(3), Entry::Del, Entry::Val(14)], 
-            q: 3, 
-            len: 2, 
-            hasher: hasher.clone()
+        let lht3 = LinearHashTable {
+            probes: Probes {
+                dim: 2,
+                table: vec![Entry::Nil, Entry::Val(3), Entry::Del, Entry::Val(14)], 
+                q: 3, 
+                len: 2, 
+                hasher: hasher.clone(),
+                probing: Probing::Linear,
+                max_tombstones: DEFAULT_MAX_TOMBSTONES,
+                key: PhantomData,
+            },
         };
         assert_eq!(lht3.iter().collect::<Vec<&u64>>(), vec![&3, &14]);
     }
 
     #[test]
     fn partial_eq() {
-        let lhs1 = LinearHashTable { 
-            dim: 3,
-            table: vec![
-                Entry::Val(0), Entry::Nil, Entry::Val(18446744073709551615), Entry::Nil,
-                Entry::Nil, Entry::Nil, Entry::Nil, Entry::Val(1234567890),
-            ],
-            q: 3,
-            len: 3,
-            hasher: hashers::Multiplicative::with_seed(105),
+        let lhs1 = LinearHashTable {
+            probes: Probes {
+                dim: 3,
+                table: vec![
+                    Entry::Val(0), Entry::Nil, Entry::Val(18446744073709551615), Entry::Nil,
+                    Entry::Nil, Entry::Nil, Entry::Nil, Entry::Val(1234567890),
+                ],
+                q: 3,
+                len: 3,
+                hasher: hashers::Multiplicative::with_seed(105),
+                probing: Probing::Linear,
+                max_tombstones: DEFAULT_MAX_TOMBSTONES,
+                key: PhantomData,
+            },
         };
-        let lhs2 = LinearHashTable { 
-            dim: 3,
-            table: vec![
-                Entry::Val(0), Entry::Val(1234567890), Entry::Val(18446744073709551615), 
-                Entry::Nil, Entry::Nil, Entry::Nil, Entry::Nil, Entry::Nil,
-            ],
-            q: 3,
-            len: 3,
-            hasher: hashers::Multiplicative::with_seed(11),
+        let lhs2 = LinearHashTable {
+            probes: Probes {
+                dim: 3,
+                table: vec![
+                    Entry::Val(0), Entry::Val(1234567890), Entry::Val(18446744073709551615), 
+                    Entry::Nil, Entry::Nil, Entry::Nil, Entry::Nil, Entry::Nil,
+                ],
+                q: 3,
+                len: 3,
+                hasher: hashers::Multiplicative::with_seed(11),
+                probing: Probing::Linear,
+                max_tombstones: DEFAULT_MAX_TOMBSTONES,
+                key: PhantomData,
+            },
         };
-        let lhs3 = LinearHashTable { 
-            dim: 1,
-            table: vec![Entry::Nil, Entry::Nil],
-            q: 0,
-            len: 0,
-            hasher: hashers::Multiplicative::with_seed(1),
+        let lhs3 = LinearHashTable {
+            probes: Probes {
+                dim: 1,
+                table: vec![Entry::Nil, Entry::Nil],
+                q: 0,
+                len: 0,
+                hasher: hashers::Multiplicative::with_seed(1),
+                probing: Probing::Linear,
+                max_tombstones: DEFAULT_MAX_TOMBSTONES,
+                key: PhantomData,
+            },
         };
         assert_eq!(lhs1, lhs1);
         assert_eq!(lhs1, lhs2);
@@ -231,23 +573,28 @@ mod tests {
     fn initialize() {
         let h = hashers::Multiplicative::with_seed(32);
         let lhs = LinearHashTable::initialize(h);
-        assert_eq!(lhs.dim, 1);
-        assert_eq!(lhs.table.len(), 2);
-        assert_eq!(lhs.len, 0);
-        assert!(lhs.table.iter().all(|entry| *entry == Entry::Nil));
+        assert_eq!(lhs.probes.dim, 1);
+        assert_eq!(lhs.probes.table.len(), 2);
+        assert_eq!(lhs.len(), 0);
+        assert!(lhs.probes.table.iter().all(|entry| *entry == Entry::Nil));
     }
 
     #[test]
     fn contains() {
-        let lhs1 = LinearHashTable { 
-            dim: 3,
-            table: vec![
-                Entry::Val(0), Entry::Del, Entry::Nil, Entry::Nil, 
-                Entry::Nil, Entry::Nil, Entry::Val(1234567890), Entry::Nil,
-            ],
-            q: 3,
-            len: 2,
-            hasher: hashers::Multiplicative::with_seed(101325),
+        let lhs1 = LinearHashTable {
+            probes: Probes {
+                dim: 3,
+                table: vec![
+                    Entry::Val(0), Entry::Del, Entry::Nil, Entry::Nil, 
+                    Entry::Nil, Entry::Nil, Entry::Val(1234567890), Entry::Nil,
+                ],
+                q: 3,
+                len: 2,
+                hasher: hashers::Multiplicative::with_seed(101325),
+                probing: Probing::Linear,
+                max_tombstones: DEFAULT_MAX_TOMBSTONES,
+                key: PhantomData,
+            },
         };
         assert!(lhs1.contains(0));
         assert!(lhs1.contains(1234567890));
@@ -257,8 +604,12 @@ mod tests {
     #[test]
     fn add() {
         let hasher = hashers::Multiplicative::with_seed(42);
-        let mut lhs = LinearHashTable { 
-            dim: 1, table: vec![Entry::Nil, Entry::Nil], q: 0, len: 0, hasher
+        let mut lhs = LinearHashTable {
+            probes: Probes {
+                dim: 1, table: vec![Entry::Nil, Entry::Nil], q: 0, len: 0, hasher,
+                probing: Probing::Linear, max_tombstones: DEFAULT_MAX_TOMBSTONES,
+                key: PhantomData,
+            },
         };
         assert_eq!(lhs.add(0), Ok(()));
         assert_eq!(lhs.len(), 1);
@@ -276,4 +627,162 @@ mod tests {
         assert_eq!(lhs.len(), 3);
         assert!(lhs.contains(18446744073709551615));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn remove_tombstones() {
+        let mut lht = LinearHashTable::initialize(hashers::Multiplicative::with_seed(7));
+        lht.set_max_tombstones(1.0);
+        for x in 0..40 {
+            lht.add(x).unwrap();
+        }
+        for x in 0..15 {
+            lht.remove(x).unwrap();
+        }
+        let tombstones = lht.probe_stats().tombstones;
+        assert_eq!(tombstones, 15);
+
+        let table_len = lht.probes.table.len();
+        lht.probes.remove_tombstones();
+        assert_eq!(lht.probes.table.len(), table_len);
+        assert_eq!(lht.probe_stats().tombstones, 0);
+        assert_eq!(lht.probes.q, lht.len());
+        for x in 0..40 {
+            assert_eq!(lht.contains(x), x >= 15);
+        }
+    }
+
+    #[test]
+    fn tombstones_stay_below_max() {
+        let mut rng = Pcg64Mcg::seed_from_u64(8);
+        let mut lht = LinearHashTable::initialize(hashers::Multiplicative::with_seed(8));
+        lht.set_max_tombstones(0.1);
+        for x in 0..200 {
+            lht.add(x).unwrap();
+        }
+        for _ in 0..2000 {
+            let x = rng.random_range(0..400);
+            if lht.remove(x).is_ok() {
+                lht.add(x + 400).unwrap_or(());
+            }
+            else {
+                let _ = lht.add(x);
+            }
+            let stats = lht.probe_stats();
+            assert!(stats.tombstones as f64 <= 0.1 * lht.probes.table.len() as f64);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_max_tombstones_out_of_range() {
+        let mut lht = LinearHashTable::initialize(hashers::Multiplicative::new());
+        lht.set_max_tombstones(0.0);
+    }
+
+    #[test]
+    fn robin_hood_shift_back() {
+        let hasher = hashers::Multiplicative::with_seed(21);
+        let mut lht = LinearHashTable::with_probing(hasher, Probing::RobinHood);
+        for x in 0..100 {
+            lht.add(x).unwrap();
+        }
+        for x in (0..100).step_by(2) {
+            lht.remove(x).unwrap();
+        }
+        assert!(lht.probes.table.iter().all(|entry| *entry != Entry::Del));
+        assert_eq!(lht.probes.q, lht.len());
+        for x in 0..100 {
+            assert_eq!(lht.contains(x), x % 2 == 1);
+        }
+        assert!(lht.probes.is_sorted_by_displacement());
+    }
+
+    #[test]
+    fn robin_hood_moves_pairs() {
+        let hasher = hashers::Multiplicative::with_seed(22);
+        let mut probes: Probes<String, (String, usize), _> =
+            Probes::with_probing(hasher, Probing::RobinHood);
+        for x in 0..100 {
+            let i = probes.push((x.to_string(), x));
+            assert_eq!(probes.item(i), &(x.to_string(), x));
+        }
+        for x in (0..100).step_by(3) {
+            let i = probes.position(x.to_string().as_str()).unwrap();
+            assert_eq!(probes.remove_at(i), (x.to_string(), x));
+        }
+        assert_eq!(probes.len(), 66);
+        for x in 0..100 {
+            let i = probes.position(x.to_string().as_str());
+            assert_eq!(i.map(|i| probes.item(i).1), (x % 3 != 0).then_some(x));
+        }
+        assert!(probes.is_sorted_by_displacement());
+    }
+
+    #[test]
+    fn modes_match_hash_set() {
+        for probing in [Probing::Linear, Probing::RobinHood] {
+            let mut rng = Pcg64Mcg::seed_from_u64(99);
+            let hasher = hashers::Tabulation::with_seed(8, 99);
+            let mut lht = LinearHashTable::with_probing(hasher, probing);
+            let mut set = HashSet::new();
+            for _ in 0..10000 {
+                let x = rng.random_range(0..500);
+                if rng.random_bool(0.5) {
+                    assert_eq!(lht.add(x).is_ok(), set.insert(x));
+                } else {
+                    assert_eq!(lht.remove(x).is_ok(), set.remove(&x));
+                }
+                assert_eq!(lht.len(), set.len());
+            }
+            for x in 0..500 {
+                assert_eq!(lht.contains(x), set.contains(&x));
+            }
+            if probing == Probing::RobinHood {
+                assert!(lht.probes.is_sorted_by_displacement());
+            }
+        }
+    }
+
+    #[test]
+    fn probe_stats() {
+        let lht = LinearHashTable {
+            probes: Probes {
+                dim: 2,
+                table: vec![Entry::Nil, Entry::Del, Entry::Nil, Entry::Nil],
+                q: 1,
+                len: 0,
+                hasher: hashers::Multiplicative::with_seed(3),
+                probing: Probing::Linear,
+                max_tombstones: DEFAULT_MAX_TOMBSTONES,
+                key: PhantomData,
+            },
+        };
+        let stats = lht.probe_stats();
+        assert_eq!(stats, ProbeStats { mean: 0.0, max: 0, tombstones: 1, load_factor: 0.0 });
+
+        let mut lht = LinearHashTable::initialize(hashers::Multiplicative::with_seed(3));
+        for x in 0..50 {
+            lht.add(x).unwrap();
+        }
+        let stats = lht.probe_stats();
+        assert!(stats.mean >= 1.0);
+        assert!(stats.max as f64 >= stats.mean);
+        assert_eq!(stats.load_factor, 50.0 / lht.probes.table.len() as f64);
+    }
+
+    impl<K: Hash + Eq, E: Keyed<K>, H: DimHasher> Probes<K, E, H> {
+        /// Checks that, along every run of keys, no key is further from its
+        /// hash slot than the previous one plus one.
+        fn is_sorted_by_displacement(&self) -> bool {
+            (0..self.table.len()).all(|i| {
+                let next = self.loop_index(i + 1);
+                match (&self.table[i], &self.table[next]) {
+                    (Entry::Val(x), Entry::Val(y)) =>
+                        self.displacement(y, next) <= self.displacement(x, i) + 1,
+                    (_, Entry::Val(y)) => self.displacement(y, next) == 0,
+                    _ => true,
+                }
+            })
+        }
+    }
+}
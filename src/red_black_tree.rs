@@ -0,0 +1,633 @@
+//! A left-leaning red-black tree, the balanced binary search tree of the book's
+//! ninth chapter.
+//!
+//! Every node is colored red or black, and the tree keeps two invariants: every
+//! path from the root to an empty subtree has the same number of black nodes,
+//! the black height, and no red node has a red child. A red node is always the
+//! left child of its parent, so that the tree mirrors a 2-3 tree where a black
+//! node and its red left child form a single 3-node. Together, these make the
+//! height at most *2 log(n + 1)*, so searching, adding and removing take
+//! *O(log n)* time in the worst case, whatever the order of the operations.
+//!
+//! The invariants are restored on the way back up from the recursion with
+//! rotations and color flips, so, like in the `Treap`, nodes are simply owned
+//! by their parent through a `Box`.
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use crate::interfaces::SSet;
+
+
+type Link<T> = Option<Box<Node<T>>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Color {
+    Red,
+    Black,
+}
+
+impl Color {
+    fn flip(self) -> Self {
+        match self {
+            Color::Red => Color::Black,
+            Color::Black => Color::Red,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Node<T> {
+    value: T,
+    color: Color,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+/// Returns `true` if the root of the subtree at `link` is red. Empty subtrees
+/// are black.
+fn is_red<T>(link: &Link<T>) -> bool {
+    link.as_ref().is_some_and(|node| node.color == Color::Red)
+}
+
+/// Returns `true` if the left child of the root of the subtree at `link` is red.
+fn is_red_left<T>(link: &Link<T>) -> bool {
+    link.as_ref().is_some_and(|node| is_red(&node.left))
+}
+
+/// A left-leaning red-black tree data structure.
+#[derive(Debug)]
+pub struct RedBlackTree<T> {
+    root: Link<T>,
+    size: usize,
+}
+
+impl<T> RedBlackTree<T> {
+    /// Creates a new, empty red-black tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::red_black_tree::RedBlackTree;
+    /// let tree: RedBlackTree<i32> = RedBlackTree::new();
+    /// ```
+    pub fn new() -> Self {
+        Self { root: None, size: 0 }
+    }
+
+    /// Returns the number of nodes contained in `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::red_black_tree::RedBlackTree;
+    /// let tree: RedBlackTree<i32> = RedBlackTree::new();
+    /// assert_eq!(tree.size(), 0);
+    /// ```
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns `true` if the size of the tree is zero, `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::red_black_tree::RedBlackTree;
+    /// let tree: RedBlackTree<i32> = RedBlackTree::new();
+    /// assert!(tree.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Returns the number of nodes in the longest path from the root to a
+    /// leaf, or 0 if the tree is empty.
+    ///
+    /// The height is computed recursively, which is fine because it is at
+    /// most *2 log(n + 1)*.
+    pub fn height(&self) -> usize {
+        fn height<T>(link: &Link<T>) -> usize {
+            match link {
+                None => 0,
+                Some(node) => 1 + height(&node.left).max(height(&node.right)),
+            }
+        }
+        height(&self.root)
+    }
+
+    /// Makes the right child of the root at `link` the new root of the
+    /// subtree. The new root takes the color of the old one, and the old root
+    /// becomes its red left child.
+    fn rotate_left(link: &mut Link<T>) {
+        let mut node = link.take().expect("only non-empty subtrees are rotated");
+        let mut right = node.right.take().expect("rotating left needs a right child");
+        node.right = right.left.take();
+        right.color = node.color;
+        node.color = Color::Red;
+        right.left = Some(node);
+        *link = Some(right);
+    }
+
+    /// Makes the left child of the root at `link` the new root of the subtree.
+    /// The new root takes the color of the old one, and the old root becomes
+    /// its red right child.
+    fn rotate_right(link: &mut Link<T>) {
+        let mut node = link.take().expect("only non-empty subtrees are rotated");
+        let mut left = node.left.take().expect("rotating right needs a left child");
+        node.left = left.right.take();
+        left.color = node.color;
+        node.color = Color::Red;
+        left.right = Some(node);
+        *link = Some(left);
+    }
+
+    /// Flips the colors of `node` and of both its children, which splits or
+    /// merges the 4-node they form.
+    fn flip_colors(node: &mut Node<T>) {
+        node.color = node.color.flip();
+        for child in [&mut node.left, &mut node.right].into_iter().flatten() {
+            child.color = child.color.flip();
+        }
+    }
+
+    /// Restores the invariants at the root of the subtree at `link`, whose
+    /// subtrees are valid left-leaning red-black trees.
+    fn fix_up(link: &mut Link<T>) {
+        let node = link.as_ref().expect("only non-empty subtrees are fixed");
+        if is_red(&node.right) && !is_red(&node.left) {
+            Self::rotate_left(link);
+        }
+        let node = link.as_ref().expect("a rotated subtree has a root");
+        if is_red(&node.left) && is_red_left(&node.left) {
+            Self::rotate_right(link);
+        }
+        let node = link.as_mut().expect("a rotated subtree has a root");
+        if is_red(&node.left) && is_red(&node.right) {
+            Self::flip_colors(node);
+        }
+    }
+
+    /// Makes the left child of the root at `link`, or one of its children,
+    /// red, borrowing a node from the right sibling if it can.
+    fn move_red_left(link: &mut Link<T>) {
+        let node = link.as_mut().expect("only non-empty subtrees are changed");
+        Self::flip_colors(node);
+        if is_red_left(&node.right) {
+            Self::rotate_right(&mut node.right);
+            Self::rotate_left(link);
+            Self::flip_colors(link.as_mut().expect("a rotated subtree has a root"));
+        }
+    }
+
+    /// Makes the right child of the root at `link`, or one of its children,
+    /// red, borrowing a node from the left sibling if it can.
+    fn move_red_right(link: &mut Link<T>) {
+        let node = link.as_mut().expect("only non-empty subtrees are changed");
+        Self::flip_colors(node);
+        if is_red_left(&node.left) {
+            Self::rotate_right(link);
+            Self::flip_colors(link.as_mut().expect("a rotated subtree has a root"));
+        }
+    }
+
+    /// Removes the smallest node of the non-empty subtree at `link`, whose
+    /// root or root's left child is red, and returns its value.
+    fn remove_min(link: &mut Link<T>) -> T {
+        let node = link.as_ref().expect("only non-empty subtrees have a minimum");
+        if node.left.is_none() {
+            // A node without left child has no right child either, otherwise
+            // the right child would be red or the black heights would differ.
+            return link.take().expect("`link` is not empty").value;
+        }
+        if !is_red(&node.left) && !is_red_left(&node.left) {
+            Self::move_red_left(link);
+        }
+        let node = link.as_mut().expect("a rotated subtree has a root");
+        let value = Self::remove_min(&mut node.left);
+        Self::fix_up(link);
+        value
+    }
+}
+
+impl<T> Default for RedBlackTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> RedBlackTree<T> {
+    /// Returns a reference to the value in `self` equal to `value`, or `None`
+    /// if `value` is not in `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::red_black_tree::RedBlackTree;
+    /// let mut tree = RedBlackTree::new();
+    /// assert!(tree.find(&0).is_none());
+    /// tree.add(0);
+    /// assert_eq!(tree.find(&0), Some(&0));
+    ///
+    /// let mut tree = RedBlackTree::new();
+    /// tree.add(String::from("a"));
+    /// assert!(tree.find("a").is_some());
+    /// ```
+    pub fn find<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut link = &self.root;
+        while let Some(node) = link {
+            link = match value.cmp(node.value.borrow()) {
+                Ordering::Less => &node.left,
+                Ordering::Greater => &node.right,
+                Ordering::Equal => return Some(&node.value),
+            };
+        }
+        None
+    }
+
+    /// Returns `true` if `value` is in `self`, otherwise `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::red_black_tree::RedBlackTree;
+    /// let mut tree = RedBlackTree::new();
+    /// assert!(!tree.contains(&0));
+    /// tree.add(0);
+    /// assert!(tree.contains(&0));
+    /// ```
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(value).is_some()
+    }
+
+    /// Adds `value` to the tree `self`. Returns `false` if `value` is already
+    /// in `self`, otherwise `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::red_black_tree::RedBlackTree;
+    /// let mut tree = RedBlackTree::new();
+    /// assert!(tree.add(0));
+    /// assert!(!tree.add(0));
+    /// ```
+    pub fn add(&mut self, value: T) -> bool {
+        let added = Self::add_at(&mut self.root, value);
+        if let Some(root) = &mut self.root {
+            root.color = Color::Black;
+        }
+        if added {
+            self.size += 1;
+        }
+        added
+    }
+
+    /// Adds `value` to the subtree at `link` as a red leaf, and then fixes the
+    /// invariants on the way back up.
+    fn add_at(link: &mut Link<T>, value: T) -> bool {
+        let Some(node) = link else {
+            *link = Some(Box::new(Node { value, color: Color::Red, left: None, right: None }));
+            return true;
+        };
+        let added = match value.cmp(&node.value) {
+            Ordering::Less => Self::add_at(&mut node.left, value),
+            Ordering::Greater => Self::add_at(&mut node.right, value),
+            Ordering::Equal => return false,
+        };
+        Self::fix_up(link);
+        added
+    }
+
+    /// Removes `value` from the tree `self`. Returns `false` if `value` is not
+    /// in `self`, otherwise `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::red_black_tree::RedBlackTree;
+    /// let mut tree = RedBlackTree::new();
+    /// tree.add(0);
+    /// assert!(tree.remove(&0));
+    /// assert!(!tree.remove(&0));
+    /// ```
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        if self.find(value).is_none() {
+            return false;
+        }
+        let root = self.root.as_mut().expect("`value` is in the tree");
+        if !is_red(&root.left) && !is_red(&root.right) {
+            root.color = Color::Red;
+        }
+        Self::remove_at(&mut self.root, value);
+        if let Some(root) = &mut self.root {
+            root.color = Color::Black;
+        }
+        self.size -= 1;
+        true
+    }
+
+    /// Removes `value`, which must be in the subtree at `link`, keeping the
+    /// current node or one of its children red on the way down, so that the
+    /// node finally removed is never a lone black node.
+    fn remove_at<Q>(link: &mut Link<T>, value: &Q)
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let node = link.as_ref().expect("`value` is in the subtree");
+        if value < node.value.borrow() {
+            if !is_red(&node.left) && !is_red_left(&node.left) {
+                Self::move_red_left(link);
+            }
+            let node = link.as_mut().expect("a rotated subtree has a root");
+            Self::remove_at(&mut node.left, value);
+        } else {
+            if is_red(&node.left) {
+                Self::rotate_right(link);
+            }
+            let node = link.as_ref().expect("a rotated subtree has a root");
+            if value == node.value.borrow() && node.right.is_none() {
+                *link = None;
+                return;
+            }
+            if !is_red(&node.right) && !is_red_left(&node.right) {
+                Self::move_red_right(link);
+            }
+            let node = link.as_mut().expect("a rotated subtree has a root");
+            if value == node.value.borrow() {
+                node.value = Self::remove_min(&mut node.right);
+            } else {
+                Self::remove_at(&mut node.right, value);
+            }
+        }
+        Self::fix_up(link);
+    }
+
+    /// Checks that `self` is a valid left-leaning red-black tree: its values
+    /// are sorted, the root is black, every red node is a left child with no
+    /// red child, and every path from the root to an empty subtree goes
+    /// through the same number of black nodes. Returns that black height.
+    ///
+    /// # Panics
+    ///
+    /// Panics, with a message naming the broken invariant, if `self` is not
+    /// valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::red_black_tree::RedBlackTree;
+    /// let mut tree = RedBlackTree::new();
+    /// for x in 0..7 {
+    ///     tree.add(x);
+    /// }
+    /// assert_eq!(tree.check_invariants(), 3);
+    /// ```
+    pub fn check_invariants(&self) -> usize {
+        fn check<T: Ord>(link: &Link<T>, lo: Option<&T>, hi: Option<&T>) -> (usize, usize) {
+            let Some(node) = link else { return (0, 0) };
+            assert!(lo.is_none_or(|lo| *lo < node.value), "values are not sorted");
+            assert!(hi.is_none_or(|hi| node.value < *hi), "values are not sorted");
+            assert!(!is_red(&node.right), "a red node is a right child");
+            if node.color == Color::Red {
+                assert!(!is_red(&node.left), "a red node has a red child");
+            }
+            let (left_size, left_height) = check(&node.left, lo, Some(&node.value));
+            let (right_size, right_height) = check(&node.right, Some(&node.value), hi);
+            assert_eq!(left_height, right_height, "black heights differ");
+            let black = usize::from(node.color == Color::Black);
+            (1 + left_size + right_size, black + left_height)
+        }
+        assert!(!is_red(&self.root), "the root is red");
+        let (size, black_height) = check(&self.root, None, None);
+        assert_eq!(size, self.size, "the size is out of date");
+        black_height
+    }
+}
+
+impl<T: Ord + Clone> SSet<T> for RedBlackTree<T> {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn add(&mut self, x: T) -> bool {
+        self.add(x)
+    }
+
+    fn remove(&mut self, x: &T) -> bool {
+        self.remove(x)
+    }
+
+    fn find(&self, x: &T) -> Option<T> {
+        let mut link = &self.root;
+        let mut candidate = None;
+        while let Some(node) = link {
+            link = match x.cmp(&node.value) {
+                Ordering::Less => {
+                    candidate = Some(&node.value);
+                    &node.left
+                },
+                Ordering::Greater => &node.right,
+                Ordering::Equal => return Some(node.value.clone()),
+            };
+        }
+        candidate.cloned()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+    use rand::{Rng, SeedableRng};
+    use rand::seq::SliceRandom;
+    use rand_pcg::Pcg64Mcg;
+
+    /// Returns the largest height, counted in nodes, of a tree of `n` nodes:
+    /// *2 log(n + 1)* rounded down, which is the largest `h` with
+    /// `2^h <= (n + 1)^2`.
+    fn height_bound(n: usize) -> usize {
+        ((n + 1) * (n + 1)).ilog2() as usize
+    }
+
+    /// Adds then removes `values` in the given orders, checking the invariants
+    /// and the height after every operation.
+    fn exercise(values: &[i32], removals: &[i32]) {
+        let mut tree = RedBlackTree::new();
+        for (i, &x) in values.iter().enumerate() {
+            assert!(tree.add(x));
+            tree.check_invariants();
+            assert!(tree.height() <= height_bound(i + 1), "height {}", tree.height());
+        }
+        for (i, x) in removals.iter().enumerate() {
+            assert!(tree.remove(x));
+            tree.check_invariants();
+            assert!(tree.height() <= height_bound(values.len() - i));
+        }
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn height_counts_nodes() {
+        let mut tree = RedBlackTree::new();
+        tree.add(0);
+        assert_eq!(tree.height(), 1);
+        tree.add(1);
+        assert_eq!(tree.height(), 2);
+    }
+
+    #[test]
+    fn new() {
+        let tree: RedBlackTree<i32> = RedBlackTree::new();
+        assert!(tree.is_empty());
+        assert_eq!(tree.size(), 0);
+        assert_eq!(tree.height(), 0);
+        assert_eq!(tree.check_invariants(), 0);
+    }
+
+    #[test]
+    fn add_find_remove() {
+        let mut tree = RedBlackTree::new();
+        for x in [5, 2, 8, 1, 9, 3] {
+            assert!(tree.add(x));
+        }
+        assert!(!tree.add(8));
+        assert_eq!(tree.size(), 6);
+        assert_eq!(tree.find(&3), Some(&3));
+        assert_eq!(tree.find(&4), None);
+        tree.check_invariants();
+
+        assert!(tree.remove(&5));
+        assert!(!tree.remove(&5));
+        assert!(!tree.remove(&7));
+        assert_eq!(tree.size(), 5);
+        assert_eq!(tree.find(&5), None);
+        tree.check_invariants();
+    }
+
+    #[test]
+    fn lookups_take_borrowed_values() {
+        let mut tree = RedBlackTree::new();
+        for fruit in ["kiwi", "apple", "pear", "fig"] {
+            tree.add(String::from(fruit));
+        }
+        assert!(tree.contains("kiwi"));
+        assert!(!tree.contains("plum"));
+        assert_eq!(tree.find("apple").map(String::as_str), Some("apple"));
+        assert!(tree.remove("kiwi"));
+        assert!(!tree.remove("kiwi"));
+        assert!(tree.remove("apple"));
+        assert_eq!(tree.size(), 2);
+        tree.check_invariants();
+    }
+
+    #[test]
+    #[should_panic(expected = "a red node is a right child")]
+    fn check_invariants_catches_right_leaning_red() {
+        let leaf = |value, color| Some(Box::new(Node { value, color, left: None, right: None }));
+        let tree = RedBlackTree {
+            root: Some(Box::new(Node {
+                value: 1,
+                color: Color::Black,
+                left: None,
+                right: leaf(2, Color::Red),
+            })),
+            size: 2,
+        };
+        tree.check_invariants();
+    }
+
+    #[test]
+    #[should_panic(expected = "black heights differ")]
+    fn check_invariants_catches_unbalanced_black_heights() {
+        let leaf = |value, color| Some(Box::new(Node { value, color, left: None, right: None }));
+        let tree = RedBlackTree {
+            root: Some(Box::new(Node {
+                value: 1,
+                color: Color::Black,
+                left: leaf(0, Color::Black),
+                right: None,
+            })),
+            size: 2,
+        };
+        tree.check_invariants();
+    }
+
+    #[test]
+    fn sorted_orders() {
+        let ascending: Vec<i32> = (0..1000).collect();
+        let descending: Vec<i32> = (0..1000).rev().collect();
+        exercise(&ascending, &ascending);
+        exercise(&ascending, &descending);
+        exercise(&descending, &ascending);
+        exercise(&descending, &descending);
+    }
+
+    #[test]
+    fn zigzag_orders() {
+        // Alternates between the smallest and the largest value left, which
+        // builds two long spines in an unbalanced tree.
+        let zigzag: Vec<i32> = (0..500).flat_map(|x| [x, 999 - x]).collect();
+        // Adds the middle value first, then moves outwards on both sides.
+        let outwards: Vec<i32> = (0..500).flat_map(|x| [499 - x, 500 + x]).collect();
+        let inwards: Vec<i32> = outwards.iter().rev().copied().collect();
+        exercise(&zigzag, &zigzag);
+        exercise(&outwards, &zigzag);
+        exercise(&inwards, &outwards);
+    }
+
+    #[test]
+    fn shuffled_order() {
+        let mut rng = Pcg64Mcg::seed_from_u64(1);
+        let mut values: Vec<i32> = (0..1000).collect();
+        values.shuffle(&mut rng);
+        let mut removals = values.clone();
+        removals.shuffle(&mut rng);
+        exercise(&values, &removals);
+    }
+
+    #[test]
+    fn height_is_logarithmic_on_sorted_input() {
+        let n = 100_000;
+        let mut tree = RedBlackTree::new();
+        for x in 0..n {
+            tree.add(x);
+        }
+        assert!(tree.height() <= height_bound(n as usize), "height {}", tree.height());
+        tree.check_invariants();
+        for x in (0..n).step_by(2) {
+            tree.remove(&x);
+        }
+        assert!(tree.height() <= height_bound(n as usize / 2), "height {}", tree.height());
+        tree.check_invariants();
+    }
+
+    #[test]
+    fn matches_btree_set() {
+        let mut rng = Pcg64Mcg::seed_from_u64(2);
+        let mut tree = RedBlackTree::new();
+        let mut btree = BTreeSet::new();
+        for _ in 0..10000 {
+            let x = rng.random_range(0..500);
+            match rng.random_range(0..3) {
+                0 => assert_eq!(tree.add(x), btree.insert(x)),
+                1 => assert_eq!(tree.remove(&x), btree.remove(&x)),
+                _ => assert_eq!(tree.find(&x), btree.get(&x)),
+            }
+            assert_eq!(tree.size(), btree.len());
+        }
+        tree.check_invariants();
+    }
+}
@@ -0,0 +1,427 @@
+//! A stack that stores its elements in a list of blocks of growing size.
+//!
+//! The block `b` has room for `b + 1` elements, so `r` blocks hold up to
+//! `r(r + 1) / 2` elements. The storage grows and shrinks one block at a time,
+//! which keeps the number of allocated but unused slots in *O(sqrt(n))*, instead
+//! of the *O(n)* wasted by `ArrayStack`. As in `ArrayStack`, the element located
+//! at index 0 corresponds to the bottom of the stack.
+
+use crate::interfaces::List;
+
+/// A stack that stores its elements in a list of blocks of growing size.
+pub struct RootishArrayStack<T> {
+    blocks: Vec<Vec<Option<T>>>,
+    size: usize,
+}
+
+
+impl<T> RootishArrayStack<T> {
+    /// Returns a new, empty `RootishArrayStack`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::rootish_array_stack::RootishArrayStack;
+    /// let stack: RootishArrayStack<i32> = RootishArrayStack::initialize();
+    /// ```
+    pub fn initialize() -> Self {
+        Self { blocks: Vec::new(), size: 0 }
+    }
+
+    /// Returns the number of elements stored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::rootish_array_stack::RootishArrayStack;
+    /// let stack: RootishArrayStack<i32> = RootishArrayStack::initialize();
+    /// assert_eq!(stack.size(), 0);
+    /// ```
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the number of slots allocated in the blocks that hold no element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::rootish_array_stack::RootishArrayStack;
+    /// let mut stack = RootishArrayStack::initialize();
+    /// stack.add(0, 'a');
+    /// stack.add(1, 'b');
+    /// assert_eq!(stack.wasted_space(), 1);
+    /// ```
+    pub fn wasted_space(&self) -> usize {
+        self.capacity() - self.size()
+    }
+
+    /// Returns a shared reference to the element in the position `i`, or `None`
+    /// if `i` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::rootish_array_stack::RootishArrayStack;
+    /// let mut stack = RootishArrayStack::initialize();
+    /// stack.add(0, 'a');
+    /// assert_eq!(stack.get(0), Some(&'a'));
+    /// assert_eq!(stack.get(1), None);
+    /// ```
+    pub fn get(&self, i: usize) -> Option<&T> {
+        if self.is_out_of_indexing_bounds(i) {
+            return None;
+        }
+        self.slot(i).as_ref()
+    }
+
+    /// Replaces the element in the position `i` with `x` and returns the
+    /// original element. Returns `None` if `i` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::rootish_array_stack::RootishArrayStack;
+    /// let mut stack = RootishArrayStack::initialize();
+    /// stack.add(0, 'a');
+    /// assert_eq!(stack.set(0, 'b'), Some('a'));
+    /// assert_eq!(stack.get(0), Some(&'b'));
+    /// ```
+    pub fn set(&mut self, i: usize, x: T) -> Option<T> {
+        if self.is_out_of_indexing_bounds(i) {
+            return None;
+        }
+        self.slot_mut(i).replace(x)
+    }
+
+    /// Inserts `x` in the position `i`, shifting up all other values above
+    /// `i`. Panics if `i` if out of bounds.
+    ///
+    /// Notice that `i = self.size()` is a valid location and is equivalent to
+    /// inserting at the top of the stack.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::rootish_array_stack::RootishArrayStack;
+    /// let mut stack = RootishArrayStack::initialize();
+    /// stack.add(0, 'b');
+    /// stack.add(0, 'a');
+    /// assert_eq!(stack.iter().collect::<Vec<&char>>(), [&'a', &'b']);
+    /// ```
+    pub fn add(&mut self, i: usize, x: T) {
+        if self.is_out_of_inserting_bounds(i) {
+            panic!("Index out of bounds: {i}");
+        }
+        if self.is_full() {
+            self.grow();
+        }
+        self.size += 1;
+        for j in (i + 1..self.size()).rev() {
+            let y = self.slot_mut(j - 1).take();
+            *self.slot_mut(j) = y;
+        }
+        *self.slot_mut(i) = Some(x);
+    }
+
+    /// Removes the value in `i`, shifting down all other values above `i`.
+    /// Returns the value in `i` if it is not out of bounds, `None` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::rootish_array_stack::RootishArrayStack;
+    /// let mut stack = RootishArrayStack::initialize();
+    /// stack.add(0, 'a');
+    /// assert_eq!(stack.remove(0), Some('a'));
+    /// assert_eq!(stack.remove(0), None);
+    /// ```
+    pub fn remove(&mut self, i: usize) -> Option<T> {
+        if self.is_out_of_indexing_bounds(i) {
+            return None;
+        }
+        let x = self.slot_mut(i).take();
+        for j in i + 1..self.size() {
+            let y = self.slot_mut(j).take();
+            *self.slot_mut(j - 1) = y;
+        }
+        self.size -= 1;
+
+        while self.is_too_large() {
+            self.shrink();
+        }
+        x
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { stack: self, index: 0 }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut(self.blocks.iter_mut().flatten())
+    }
+
+    /// Returns the block that stores the index `i`. This is the largest `b`
+    /// such that `b(b + 1) / 2 <= i`.
+    fn i2b(i: usize) -> usize {
+        ((8 * i + 1).isqrt() - 1) / 2
+    }
+
+    fn slot(&self, i: usize) -> &Option<T> {
+        let b = Self::i2b(i);
+        &self.blocks[b][i - b * (b + 1) / 2]
+    }
+
+    fn slot_mut(&mut self, i: usize) -> &mut Option<T> {
+        let b = Self::i2b(i);
+        &mut self.blocks[b][i - b * (b + 1) / 2]
+    }
+
+    fn capacity(&self) -> usize {
+        let r = self.blocks.len();
+        r * (r + 1) / 2
+    }
+
+    fn is_full(&self) -> bool {
+        self.size() == self.capacity()
+    }
+
+    fn is_too_large(&self) -> bool {
+        let r = self.blocks.len();
+        r > 0 && r.saturating_sub(2) * (r - 1) / 2 >= self.size()
+    }
+
+    fn grow(&mut self) {
+        let block_len = self.blocks.len() + 1;
+        self.blocks.push((0..block_len).map(|_| None).collect());
+    }
+
+    fn shrink(&mut self) {
+        self.blocks.pop();
+    }
+
+    fn is_out_of_indexing_bounds(&self, i: usize) -> bool {
+        i >= self.size()
+    }
+
+    fn is_out_of_inserting_bounds(&self, i: usize) -> bool {
+        i > self.size()
+    }
+}
+
+
+impl<T> List<T> for RootishArrayStack<T> {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        self.get(i)
+    }
+
+    fn set(&mut self, i: usize, x: T) -> Option<T> {
+        self.set(i, x)
+    }
+
+    fn add(&mut self, i: usize, x: T) {
+        self.add(i, x)
+    }
+
+    fn remove(&mut self, i: usize) -> Option<T> {
+        self.remove(i)
+    }
+}
+
+
+impl<T> IntoIterator for RootishArrayStack<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self.blocks.into_iter().flatten())
+    }
+}
+
+
+pub struct Iter<'a, T> {
+    stack: &'a RootishArrayStack<T>,
+    index: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.stack.get(self.index)?;
+        self.index += 1;
+        Some(item)
+    }
+}
+
+
+pub struct IterMut<'a, T>(std::iter::Flatten<std::slice::IterMut<'a, Vec<Option<T>>>>);
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()?.as_mut()
+    }
+}
+
+
+pub struct IntoIter<T>(std::iter::Flatten<std::vec::IntoIter<Vec<Option<T>>>>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()?
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_test_stack() -> RootishArrayStack<char> {
+        RootishArrayStack {
+            blocks: vec![
+                vec![Some('a')],
+                vec![Some('b'), Some('c')],
+                vec![Some('d'), None, None],
+            ],
+            size: 4,
+        }
+    }
+
+    #[test]
+    fn initialize_has_size_zero() {
+        let stack = RootishArrayStack::<i32>::initialize();
+        assert_eq!(stack.size(), 0);
+        assert_eq!(stack.iter().count(), 0);
+    }
+
+    #[test]
+    fn i2b_returns_block() {
+        let blocks = (0..10)
+            .map(RootishArrayStack::<i32>::i2b)
+            .collect::<Vec<usize>>();
+        assert_eq!(blocks, [0, 1, 1, 2, 2, 2, 3, 3, 3, 3]);
+    }
+
+    #[test]
+    fn get_returns_shared_reference() {
+        let stack = build_test_stack();
+        assert_eq!(stack.get(0), Some(&'a'));
+        assert_eq!(stack.get(2), Some(&'c'));
+        assert_eq!(stack.get(3), Some(&'d'));
+    }
+
+    #[test]
+    fn get_out_of_bounds_returns_none() {
+        let stack = build_test_stack();
+        assert!(stack.get(4).is_none());
+        assert!(stack.get(10).is_none());
+    }
+
+    #[test]
+    fn set_returns_prior_element() {
+        let mut stack = build_test_stack();
+        assert_eq!(stack.set(1, 'x'), Some('b'));
+        assert_eq!(stack.set(3, 'z'), Some('d'));
+        assert_eq!(stack.iter().collect::<String>(), "axcz");
+    }
+
+    #[test]
+    fn set_out_of_bounds_returns_none() {
+        let mut stack = build_test_stack();
+        assert!(stack.set(4, 'x').is_none());
+        assert_eq!(stack.size(), 4);
+    }
+
+    #[test]
+    fn add_updates_storage() {
+        let mut stack = RootishArrayStack::initialize();
+        stack.add(0, 'c');
+        stack.add(0, 'a');
+        stack.add(1, 'b');
+        stack.add(3, 'e');
+        stack.add(3, 'd');
+        assert_eq!(stack.iter().collect::<String>(), "abcde");
+        assert_eq!(stack.size(), 5);
+    }
+
+    #[test]
+    fn add_grows_one_block_at_a_time() {
+        let mut stack = RootishArrayStack::initialize();
+        for (x, blocks) in [1, 2, 2, 3, 3, 3, 4].into_iter().enumerate() {
+            stack.add(x, x);
+            assert_eq!(stack.blocks.len(), blocks);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn add_out_of_bounds_panics() {
+        let mut stack = build_test_stack();
+        stack.add(5, 'x');
+    }
+
+    #[test]
+    fn remove_returns_value() {
+        let mut stack = build_test_stack();
+        assert_eq!(stack.remove(1), Some('b'));
+        assert_eq!(stack.remove(2), Some('d'));
+        assert_eq!(stack.remove(0), Some('a'));
+        assert_eq!(stack.iter().collect::<String>(), "c");
+    }
+
+    #[test]
+    fn remove_out_of_bounds_returns_none() {
+        let mut stack = build_test_stack();
+        assert!(stack.remove(4).is_none());
+        assert_eq!(stack.size(), 4);
+    }
+
+    #[test]
+    fn remove_shrinks_storage() {
+        let mut stack = build_test_stack();
+        stack.remove(0);
+        stack.remove(0);
+        assert_eq!(stack.blocks.len(), 3);
+        stack.remove(0);
+        assert_eq!(stack.blocks.len(), 2);
+        stack.remove(0);
+        assert_eq!(stack.blocks.len(), 0);
+    }
+
+    #[test]
+    fn wasted_space_is_square_root_bounded() {
+        let mut stack = RootishArrayStack::initialize();
+        let bound = |n: usize| 2.0 * (2.0 * n as f64).sqrt() + 3.0;
+
+        for x in 0..5000 {
+            stack.add(stack.size(), x);
+            assert!((stack.wasted_space() as f64) <= bound(stack.size()));
+        }
+        for _ in 0..5000 {
+            stack.remove(stack.size() - 1);
+            assert!((stack.wasted_space() as f64) <= bound(stack.size()));
+        }
+    }
+
+    #[test]
+    fn iter_mut_mutates_storage() {
+        let mut stack = build_test_stack();
+        stack.iter_mut().for_each(|x| *x = x.to_ascii_uppercase());
+        assert_eq!(stack.iter().collect::<String>(), "ABCD");
+    }
+
+    #[test]
+    fn into_iter_returns_stack() {
+        let stack = build_test_stack();
+        assert_eq!(stack.into_iter().collect::<String>(), "abcd");
+    }
+}
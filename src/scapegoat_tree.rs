@@ -0,0 +1,411 @@
+//! A scapegoat tree, the binary search tree of the book's eighth chapter.
+//!
+//! A scapegoat tree stores no balance information in its nodes. Instead, it
+//! keeps a counter `q` that is an upper bound of the number of nodes `n`, with
+//! `q / 2 <= n <= q`, and makes sure that no node is deeper than
+//! `log_{3/2}(q)`.
+//!
+//! Values are added as leaves, following the insertion path of
+//! `BinarySearchTree`. When a new leaf is too deep, one of its ancestors, the
+//! scapegoat, must have a child that holds more than 2/3 of its subtree, and
+//! that subtree is rebuilt into a perfectly balanced one. Removals just rebuild
+//! the whole tree when `q` grows larger than `2n`. Rebuilding a subtree of size
+//! `m` takes *O(m)* time, but it happens rarely enough that adding and removing
+//! take *O(log n)* amortized time.
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use crate::binary_search_tree::add_leaf;
+use crate::binary_tree::RefNode;
+use crate::interfaces::SSet;
+
+
+/// A scapegoat tree data structure.
+pub struct ScapegoatTree<T> {
+    root: Option<RefNode<T>>,
+    n: usize,
+    q: usize,
+}
+
+impl<T> ScapegoatTree<T> {
+    /// Creates a new, empty scapegoat tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::scapegoat_tree::ScapegoatTree;
+    /// let tree: ScapegoatTree<i32> = ScapegoatTree::new();
+    /// ```
+    pub fn new() -> Self {
+        Self { root: None, n: 0, q: 0 }
+    }
+
+    /// Returns the number of nodes contained in `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::scapegoat_tree::ScapegoatTree;
+    /// let tree: ScapegoatTree<i32> = ScapegoatTree::new();
+    /// assert_eq!(tree.size(), 0);
+    /// ```
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    /// Returns `true` if the size of the tree is zero, `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::scapegoat_tree::ScapegoatTree;
+    /// let tree: ScapegoatTree<i32> = ScapegoatTree::new();
+    /// assert!(tree.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Returns the number of nodes in the longest path from the root to a
+    /// leaf, or 0 if the tree is empty.
+    pub fn height(&self) -> usize {
+        self.root.as_ref().map_or(0, RefNode::height)
+    }
+
+    /// Rebuilds the subtree under `node` into a perfectly balanced one, and
+    /// hangs it where `node` was.
+    fn rebuild(&mut self, node: &RefNode<T>) {
+        let parent = node.get_parent();
+        let nodes: Vec<RefNode<T>> = node.in_order().collect();
+        let subtree = build_balanced(&nodes).expect("`node` should be in its own subtree");
+
+        match parent {
+            None => self.root = Some(RefNode::clone(&subtree)),
+            Some(ref parent) => {
+                if parent.get_right().is_some_and(|right| right.ptr_eq(node)) {
+                    parent.set_right(Some(&subtree));
+                } else {
+                    parent.set_left(Some(&subtree));
+                }
+            },
+        }
+        subtree.set_parent(parent.as_ref());
+    }
+
+    /// Walks up from the new leaf `node` until it finds a node that holds more
+    /// than 2/3 of its parent's subtree, and rebuilds the subtree of the parent,
+    /// the scapegoat.
+    fn rebalance_from(&mut self, node: RefNode<T>) {
+        let mut child = node;
+        loop {
+            let parent = child
+                .get_parent()
+                .expect("a too deep node should have a scapegoat ancestor");
+            if 3 * child.size() > 2 * parent.size() {
+                self.rebuild(&parent);
+                return;
+            }
+            child = parent;
+        }
+    }
+
+    /// Takes out `node`, which must have at most one child, and links its
+    /// child to its parent.
+    fn splice(&mut self, node: &RefNode<T>) {
+        let child = node.get_left().or_else(|| node.get_right());
+        let parent = node.get_parent();
+        match parent {
+            None => self.root = child.clone(),
+            Some(ref parent) => {
+                if parent.get_left().is_some_and(|left| left.ptr_eq(node)) {
+                    parent.set_left(child.as_ref());
+                } else {
+                    parent.set_right(child.as_ref());
+                }
+            },
+        }
+        if let Some(child) = child {
+            child.set_parent(parent.as_ref());
+        }
+        if let Some(parent) = parent {
+            parent.update_sizes_to_root();
+        }
+        node.set_parent(None);
+        node.set_left(None);
+        node.set_right(None);
+    }
+}
+
+impl<T> Default for ScapegoatTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> ScapegoatTree<T> {
+    /// Returns a reference to the node in `self` that contains `value`, or
+    /// `None` if `value` is not in `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::scapegoat_tree::ScapegoatTree;
+    /// let mut tree = ScapegoatTree::new();
+    /// assert!(tree.find(&0).is_none());
+    /// tree.add(0);
+    /// assert!(tree.find(&0).is_some());
+    /// ```
+    pub fn find<Q>(&self, value: &Q) -> Option<RefNode<T>>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut node_opt = self.root.clone();
+        while let Some(node) = node_opt {
+            let ordering = value.cmp((*node.value()).borrow());
+            node_opt = match ordering {
+                Ordering::Less => node.get_left(),
+                Ordering::Greater => node.get_right(),
+                Ordering::Equal => return Some(node),
+            };
+        }
+        None
+    }
+
+    /// Adds `value` to the tree `self`. Returns `false` if `value` is already
+    /// in `self`, otherwise `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::scapegoat_tree::ScapegoatTree;
+    /// let mut tree = ScapegoatTree::new();
+    /// assert!(tree.add(0));
+    /// assert!(!tree.add(0));
+    /// ```
+    pub fn add(&mut self, value: T) -> bool {
+        let Some(node) = add_leaf(&mut self.root, value) else {
+            return false;
+        };
+        self.n += 1;
+        self.q += 1;
+        if node.depth() > log32(self.q) {
+            self.rebalance_from(node);
+        }
+        true
+    }
+
+    /// Removes `value` from the tree `self`. Returns `false` if `value` is not
+    /// in `self`, otherwise `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::scapegoat_tree::ScapegoatTree;
+    /// let mut tree = ScapegoatTree::new();
+    /// tree.add(0);
+    /// assert!(tree.remove(&0));
+    /// assert!(!tree.remove(&0));
+    /// ```
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let Some(node) = self.find(value) else {
+            return false;
+        };
+        if node.is_fully_branched() {
+            let mut min_node = node.get_right().expect("`node` should have both children");
+            while let Some(left) = min_node.get_left() {
+                min_node = left;
+            }
+            self.splice(&min_node);
+            let min_value = min_node
+                .into_inner_value()
+                .expect("`min_node` should have only 1 reference");
+            node.set(min_value);
+        } else {
+            self.splice(&node);
+        }
+
+        self.n -= 1;
+        if 2 * self.n < self.q {
+            if let Some(root) = self.root.clone() {
+                self.rebuild(&root);
+            }
+            self.q = self.n;
+        }
+        true
+    }
+}
+
+impl<T: Ord + Clone> SSet<T> for ScapegoatTree<T> {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn add(&mut self, x: T) -> bool {
+        self.add(x)
+    }
+
+    fn remove(&mut self, x: &T) -> bool {
+        self.remove(x)
+    }
+
+    fn find(&self, x: &T) -> Option<T> {
+        let mut node_opt = self.root.clone();
+        let mut candidate = None;
+
+        while let Some(node) = node_opt {
+            let ordering = x.cmp(&node.value());
+            node_opt = match ordering {
+                Ordering::Less => {
+                    candidate = Some(node.value().clone());
+                    node.get_left()
+                },
+                Ordering::Greater => node.get_right(),
+                Ordering::Equal => return Some(node.value().clone()),
+            };
+        }
+        candidate
+    }
+}
+
+/// Returns `ceil(log_{3/2}(q))`, the maximum depth allowed in a scapegoat tree
+/// whose counter is `q`.
+fn log32(q: usize) -> usize {
+    const LOG23: f64 = 2.466_303_462_376_431_7;
+    (LOG23 * (q as f64).ln()).ceil() as usize
+}
+
+/// Links `nodes`, sorted in increasing order, into a perfectly balanced tree,
+/// and returns its root.
+fn build_balanced<T>(nodes: &[RefNode<T>]) -> Option<RefNode<T>> {
+    if nodes.is_empty() {
+        return None;
+    }
+    let m = nodes.len() / 2;
+    let root = &nodes[m];
+    let left = build_balanced(&nodes[..m]);
+    let right = build_balanced(&nodes[m + 1..]);
+    for child in left.iter().chain(right.iter()) {
+        child.set_parent(Some(root));
+    }
+    root.set_left(left.as_ref());
+    root.set_right(right.as_ref());
+    Some(RefNode::clone(root))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+    use rand::{Rng, SeedableRng};
+    use rand_pcg::Pcg64Mcg;
+
+    /// Checks the search tree property and the parent links under `node`, and
+    /// returns the number of nodes.
+    fn check_subtree<T: Ord>(node: &RefNode<T>, lo: Option<&T>, hi: Option<&T>) -> usize {
+        let value = node.value();
+        assert!(lo.is_none_or(|lo| *lo < *value));
+        assert!(hi.is_none_or(|hi| *value < *hi));
+        let mut size = 1;
+        if let Some(left) = node.get_left() {
+            assert!(left.get_parent().is_some_and(|parent| parent.ptr_eq(node)));
+            size += check_subtree(&left, lo, Some(&*value));
+        }
+        if let Some(right) = node.get_right() {
+            assert!(right.get_parent().is_some_and(|parent| parent.ptr_eq(node)));
+            size += check_subtree(&right, Some(&*value), hi);
+        }
+        assert_eq!(node.size(), size);
+        size
+    }
+
+    fn check_invariants<T: Ord>(tree: &ScapegoatTree<T>) {
+        let size = tree.root.as_ref().map_or(0, |root| {
+            assert!(root.is_root());
+            check_subtree(root, None, None)
+        });
+        assert_eq!(size, tree.n);
+        assert!(tree.q / 2 <= tree.n && tree.n <= tree.q);
+        let depth = tree.height().saturating_sub(1);
+        assert!(depth <= log32(tree.q), "depth {depth} with q = {}", tree.q);
+    }
+
+    #[test]
+    fn log32_values() {
+        assert_eq!(log32(1), 0);
+        assert_eq!(log32(2), 2);
+        assert_eq!(log32(3), 3);
+        assert_eq!(log32(100), 12);
+    }
+
+    #[test]
+    fn build_balanced_is_perfect() {
+        let nodes = (0..7).map(RefNode::new).collect::<Vec<RefNode<i32>>>();
+        let root = build_balanced(&nodes).unwrap();
+        assert_eq!(*root.value(), 3);
+        assert_eq!(root.height(), 3);
+        assert_eq!(root.in_order().map(|n| *n.value()).collect::<Vec<i32>>(), (0..7).collect::<Vec<i32>>());
+    }
+
+    #[test]
+    fn add_find_remove() {
+        let mut tree = ScapegoatTree::new();
+        for x in [5, 2, 8, 1, 9, 3] {
+            assert!(tree.add(x));
+        }
+        assert!(!tree.add(8));
+        assert_eq!(tree.size(), 6);
+        assert_eq!(*tree.find(&3).unwrap().value(), 3);
+        assert!(tree.find(&4).is_none());
+        check_invariants(&tree);
+
+        assert!(tree.remove(&5));
+        assert!(!tree.remove(&5));
+        assert!(!tree.remove(&7));
+        assert_eq!(tree.size(), 5);
+        check_invariants(&tree);
+    }
+
+    #[test]
+    fn depth_bound_holds_on_sorted_input() {
+        let mut tree = ScapegoatTree::new();
+        for x in 0..2000 {
+            tree.add(x);
+            check_invariants(&tree);
+        }
+        for x in (0..2000).rev() {
+            tree.add(2 * x + 5000);
+        }
+        check_invariants(&tree);
+        for x in 0..1990 {
+            assert!(tree.remove(&x));
+            check_invariants(&tree);
+        }
+        assert_eq!(tree.size(), 2010);
+    }
+
+    #[test]
+    fn depth_bound_holds_on_random_operations() {
+        let mut rng = Pcg64Mcg::seed_from_u64(15);
+        let mut tree = ScapegoatTree::new();
+        let mut btree = BTreeSet::new();
+        for _ in 0..5000 {
+            let x = rng.random_range(0..1000);
+            if rng.random_bool(0.6) {
+                assert_eq!(tree.add(x), btree.insert(x));
+            } else {
+                assert_eq!(tree.remove(&x), btree.remove(&x));
+            }
+            check_invariants(&tree);
+        }
+        assert_eq!(tree.size(), btree.len());
+        for x in 0..1000 {
+            assert_eq!(tree.find(&x).is_some(), btree.contains(&x));
+        }
+    }
+}
@@ -5,6 +5,7 @@
 //! can do. Although limited to one owner like any other mutable reference, `Box` 
 //! is much more flexible than `Rc<RefCell<>>` used for the doubly linked list.
 
+use crate::interfaces::Queue;
 
 /// A safe singly linked list.
 pub struct SLList<T> {
@@ -59,9 +60,9 @@ impl<T> SLList<T> {
     /// # use ods::singly_linked_list::SLList;
     /// let mut list = SLList::initialize();
     /// list.push('a');
-    /// assert_eq!(list.get(0), Some(&'a'));
+    /// assert_eq!(list.iter().next(), Some(&'a'));
     /// list.push('b');
-    /// assert_eq!(list.get(0), Some(&'b'));
+    /// assert_eq!(list.iter().next(), Some(&'b'));
     /// ```
     pub fn push(&mut self, x: T) {
         self.head = Some(Node::new(x, self.head.take()));
@@ -99,7 +100,7 @@ impl<T> SLList<T> {
     /// assert_eq!(list.iter().next(), Some(&'a'));
     /// ```
     pub fn iter(&self) -> Iter<'_, T> {
-        Iter(self.head.as_ref())
+        Iter(self.head.as_deref())
     }
 
     /// Returns an iterator of mutable references to the list's values.
@@ -118,6 +119,22 @@ impl<T> SLList<T> {
 }
 
 
+impl<T> Queue<T> for SLList<T> {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    /// Pushes `x` as the new head, so the list behaves as a LIFO queue.
+    fn add(&mut self, x: T) {
+        self.push(x)
+    }
+
+    fn remove(&mut self) -> Option<T> {
+        self.pop()
+    }
+}
+
+
 impl<T> IntoIterator for SLList<T> {
     type Item = T;
     type IntoIter = IntoIter<T>;
@@ -139,14 +156,14 @@ impl<T> Iterator for IntoIter<T> {
 }
 
 
-pub struct Iter<'a, T>(Option<&'a Box<Node<T>>>);
+pub struct Iter<'a, T>(Option<&'a Node<T>>);
 
 impl<'a, T> Iterator for Iter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
         let node = self.0?;
-        self.0 = node.next.as_ref();
+        self.0 = node.next.as_deref();
         Some(&node.value)
     }
 }
@@ -0,0 +1,998 @@
+//! Skiplists, the randomized linked structures of the book's fourth chapter.
+//!
+//! A skiplist is a sequence of sorted linked lists `L_0, ..., L_h`, where every
+//! list contains a subset of the elements of the one below it. Each element
+//! joins the lists up to a random height, drawn so that about half of the
+//! elements of `L_r` also make it into `L_{r+1}`. Searches start at the top of
+//! the sentinel and move right or down, which takes expected *O(log n)* steps.
+//!
+//! Like `SEList`, the nodes live in a `Vec` and link to each other by index, so
+//! elements can be lent out as plain references. Indices of removed nodes are
+//! reused by later additions. Tower heights come from a `Pcg64Mcg` that can be
+//! seeded, to make the structure of a skiplist reproducible.
+//!
+//! `SkiplistSSet` keeps its elements sorted, while `SkiplistList` keeps them in
+//! the order they are added at, and stores the length of every link so it can
+//! find elements by position.
+
+use std::collections::HashMap;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64Mcg;
+use crate::interfaces::{List, SSet};
+
+
+/// The nodes of a skiplist, stored in a `Vec` and linked by index, along with
+/// the next links of the sentinel, which holds no element.
+#[derive(Debug)]
+struct Arena<T, L> {
+    head: Vec<Option<L>>,
+    nodes: Vec<Option<Node<T, L>>>,
+    free: Vec<usize>,
+}
+
+#[derive(Debug)]
+struct Node<T, L> {
+    value: T,
+    next: Vec<Option<L>>,
+}
+
+impl<T, L: Copy> Arena<T, L> {
+    fn new() -> Self {
+        Self { head: vec![None], nodes: Vec::new(), free: Vec::new() }
+    }
+
+    fn height(&self) -> usize {
+        self.head.len() - 1
+    }
+
+    fn node(&self, u: usize) -> &Node<T, L> {
+        self.nodes[u].as_ref().expect("linked nodes should be in the arena")
+    }
+
+    fn node_mut(&mut self, u: usize) -> &mut Node<T, L> {
+        self.nodes[u].as_mut().expect("linked nodes should be in the arena")
+    }
+
+    /// Returns the link that leaves `u` in `L_r`, where `None` stands for the
+    /// sentinel.
+    fn next(&self, u: Option<usize>, r: usize) -> Option<&L> {
+        match u {
+            None => self.head[r].as_ref(),
+            Some(u) => self.node(u).next[r].as_ref(),
+        }
+    }
+
+    fn set_next(&mut self, u: Option<usize>, r: usize, link: Option<L>) {
+        match u {
+            None => self.head[r] = link,
+            Some(u) => self.node_mut(u).next[r] = link,
+        }
+    }
+
+    /// Grows the sentinel so that it reaches the height `h`.
+    fn grow(&mut self, h: usize) {
+        while self.height() < h {
+            self.head.push(None);
+        }
+    }
+
+    /// Drops the empty lists at the top of the sentinel, leaving at least `L_0`.
+    fn shrink(&mut self) {
+        while self.height() > 0 && self.head[self.height()].is_none() {
+            self.head.pop();
+        }
+    }
+
+    fn alloc(&mut self, value: T, next: Vec<Option<L>>) -> usize {
+        let node = Some(Node { value, next });
+        match self.free.pop() {
+            Some(u) => {
+                self.nodes[u] = node;
+                u
+            },
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            },
+        }
+    }
+
+    fn dealloc(&mut self, u: usize) -> Node<T, L> {
+        let node = self.nodes[u].take().expect("linked nodes should be in the arena");
+        self.free.push(u);
+        node
+    }
+}
+
+/// Returns a random tower height: the number of times in a row that a fair
+/// coin comes up heads.
+fn pick_height(rng: &mut Pcg64Mcg) -> usize {
+    rng.random::<u32>().trailing_ones() as usize
+}
+
+
+/// A sorted set implemented with a skiplist.
+#[derive(Debug)]
+pub struct SkiplistSSet<T> {
+    arena: Arena<T, usize>,
+    size: usize,
+    rng: Pcg64Mcg,
+}
+
+impl<T> SkiplistSSet<T> {
+    /// Creates a new, empty set whose tower heights are drawn from a generator
+    /// seeded by the OS.
+    pub fn initialize() -> Self {
+        Self::from_rng(Pcg64Mcg::from_os_rng())
+    }
+
+    /// Creates a new, empty set whose tower heights are drawn from a generator
+    /// seeded with `state`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::skiplist::SkiplistSSet;
+    /// let mut set1 = SkiplistSSet::with_seed(42);
+    /// let mut set2 = SkiplistSSet::with_seed(42);
+    /// for x in 0..100 {
+    ///     set1.add(x);
+    ///     set2.add(x);
+    /// }
+    /// assert_eq!(set1.height(), set2.height());
+    /// ```
+    pub fn with_seed(state: u64) -> Self {
+        Self::from_rng(Pcg64Mcg::seed_from_u64(state))
+    }
+
+    fn from_rng(rng: Pcg64Mcg) -> Self {
+        Self { arena: Arena::new(), size: 0, rng }
+    }
+
+    /// Returns the number of elements in the set.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns `true` if the set has no elements, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Returns the height of the tallest tower in the set, or 0 if it is empty.
+    pub fn height(&self) -> usize {
+        self.arena.height()
+    }
+
+    /// Returns an iterator over the elements of the set, in increasing order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::skiplist::SkiplistSSet;
+    /// let mut set = SkiplistSSet::with_seed(0);
+    /// for x in [3, 1, 2] {
+    ///     set.add(x);
+    /// }
+    /// assert_eq!(set.iter().collect::<Vec<&i32>>(), [&1, &2, &3]);
+    /// ```
+    pub fn iter(&self) -> SkiplistSSetIterator<'_, T> {
+        SkiplistSSetIterator { arena: &self.arena, next: self.arena.head[0] }
+    }
+}
+
+impl<T> Default for SkiplistSSet<T> {
+    fn default() -> Self {
+        Self::initialize()
+    }
+}
+
+impl<T: Ord> SkiplistSSet<T> {
+    /// Returns the node whose successor in `L_r` is the first one not smaller
+    /// than `x`, or `None` if it is the sentinel.
+    fn step(&self, mut u: Option<usize>, r: usize, x: &T) -> Option<usize> {
+        while let Some(&w) = self.arena.next(u, r)
+            && self.arena.node(w).value < *x
+        {
+            u = Some(w);
+        }
+        u
+    }
+
+    /// Returns the smallest element of the set that is greater than or equal
+    /// to `x`, or `None` if there is no such element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::skiplist::SkiplistSSet;
+    /// let mut set = SkiplistSSet::with_seed(0);
+    /// set.add(1);
+    /// set.add(5);
+    /// assert_eq!(set.find(&1), Some(&1));
+    /// assert_eq!(set.find(&2), Some(&5));
+    /// assert_eq!(set.find(&6), None);
+    /// ```
+    pub fn find(&self, x: &T) -> Option<&T> {
+        let mut u = None;
+        for r in (0..=self.height()).rev() {
+            u = self.step(u, r, x);
+        }
+        self.arena.next(u, 0).map(|&w| &self.arena.node(w).value)
+    }
+
+    /// Returns `true` if `x` is in the set, `false` otherwise.
+    pub fn contains(&self, x: &T) -> bool {
+        self.find(x) == Some(x)
+    }
+
+    /// Adds `x` to the set. Returns `false` if `x` is already in the set,
+    /// otherwise `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::skiplist::SkiplistSSet;
+    /// let mut set = SkiplistSSet::with_seed(0);
+    /// assert!(set.add(0));
+    /// assert!(!set.add(0));
+    /// ```
+    pub fn add(&mut self, x: T) -> bool {
+        let mut stack = vec![None; self.height() + 1];
+        let mut u = None;
+        for r in (0..=self.height()).rev() {
+            u = self.step(u, r, &x);
+            if let Some(&w) = self.arena.next(u, r)
+                && self.arena.node(w).value == x
+            {
+                return false;
+            }
+            stack[r] = u;
+        }
+
+        let h = pick_height(&mut self.rng);
+        self.arena.grow(h);
+        stack.resize(h + 1, None);
+        let next = (0..=h).map(|r| self.arena.next(stack[r], r).copied()).collect();
+        let w = self.arena.alloc(x, next);
+        for (r, &u) in stack.iter().enumerate().take(h + 1) {
+            self.arena.set_next(u, r, Some(w));
+        }
+        self.size += 1;
+        true
+    }
+
+    /// Removes `x` from the set. Returns `false` if `x` is not in the set,
+    /// otherwise `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::skiplist::SkiplistSSet;
+    /// let mut set = SkiplistSSet::with_seed(0);
+    /// set.add(0);
+    /// assert!(set.remove(&0));
+    /// assert!(!set.remove(&0));
+    /// ```
+    pub fn remove(&mut self, x: &T) -> bool {
+        let mut removed = None;
+        let mut u = None;
+        for r in (0..=self.height()).rev() {
+            u = self.step(u, r, x);
+            if let Some(&w) = self.arena.next(u, r)
+                && self.arena.node(w).value == *x
+            {
+                let next = self.arena.node(w).next[r];
+                self.arena.set_next(u, r, next);
+                removed = Some(w);
+            }
+        }
+        match removed {
+            None => false,
+            Some(w) => {
+                self.arena.dealloc(w);
+                self.arena.shrink();
+                self.size -= 1;
+                true
+            },
+        }
+    }
+}
+
+impl<T: Ord + Clone> SSet<T> for SkiplistSSet<T> {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn add(&mut self, x: T) -> bool {
+        self.add(x)
+    }
+
+    fn remove(&mut self, x: &T) -> bool {
+        self.remove(x)
+    }
+
+    fn find(&self, x: &T) -> Option<T> {
+        self.find(x).cloned()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SkiplistSSet<T> {
+    type Item = &'a T;
+    type IntoIter = SkiplistSSetIterator<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct SkiplistSSetIterator<'a, T> {
+    arena: &'a Arena<T, usize>,
+    next: Option<usize>,
+}
+
+impl<'a, T> Iterator for SkiplistSSetIterator<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.arena.node(self.next?);
+        self.next = node.next[0];
+        Some(&node.value)
+    }
+}
+
+
+/// A link of a `SkiplistList`, along with its length: the number of elements
+/// that it skips over in `L_0`, plus one.
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    to: usize,
+    len: usize,
+}
+
+
+/// A list with expected *O(log n)* access, insertion and removal at any
+/// position, implemented with a skiplist.
+#[derive(Debug)]
+pub struct SkiplistList<T> {
+    arena: Arena<T, Edge>,
+    size: usize,
+    rng: Pcg64Mcg,
+}
+
+impl<T> SkiplistList<T> {
+    /// Creates a new, empty list whose tower heights are drawn from a
+    /// generator seeded by the OS.
+    pub fn initialize() -> Self {
+        Self::from_rng(Pcg64Mcg::from_os_rng())
+    }
+
+    /// Creates a new, empty list whose tower heights are drawn from a
+    /// generator seeded with `state`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::skiplist::SkiplistList;
+    /// let list: SkiplistList<i32> = SkiplistList::with_seed(42);
+    /// ```
+    pub fn with_seed(state: u64) -> Self {
+        Self::from_rng(Pcg64Mcg::seed_from_u64(state))
+    }
+
+    fn from_rng(rng: Pcg64Mcg) -> Self {
+        Self { arena: Arena::new(), size: 0, rng }
+    }
+
+    /// Returns the number of elements in the list.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns `true` if the list has no elements, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Returns the height of the tallest tower in the list, or 0 if it is
+    /// empty.
+    pub fn height(&self) -> usize {
+        self.arena.height()
+    }
+
+    /// Returns the node in the position `i - 1` of the list, or `None` if `i`
+    /// is 0 and the node is the sentinel.
+    ///
+    /// The search counts positions from 1, so that the sentinel sits at 0.
+    fn find_pred(&self, i: usize) -> Option<usize> {
+        let mut u = None;
+        let mut j = 0;
+        for r in (0..=self.height()).rev() {
+            while let Some(e) = self.arena.next(u, r)
+                && j + e.len <= i
+            {
+                j += e.len;
+                u = Some(e.to);
+            }
+        }
+        u
+    }
+
+    /// Returns a shared reference to the element in the position `i`, or
+    /// `None` if `i` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::skiplist::SkiplistList;
+    /// let mut list = SkiplistList::with_seed(0);
+    /// list.add(0, 'a');
+    /// assert_eq!(list.get(0), Some(&'a'));
+    /// assert_eq!(list.get(1), None);
+    /// ```
+    pub fn get(&self, i: usize) -> Option<&T> {
+        if i >= self.size {
+            return None;
+        }
+        let e = self.arena.next(self.find_pred(i), 0)?;
+        Some(&self.arena.node(e.to).value)
+    }
+
+    /// Replaces the element in the position `i` with `x` and returns the
+    /// original element. Returns `None` if `i` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::skiplist::SkiplistList;
+    /// let mut list = SkiplistList::with_seed(0);
+    /// list.add(0, 'a');
+    /// assert_eq!(list.set(0, 'b'), Some('a'));
+    /// assert_eq!(list.set(1, 'c'), None);
+    /// ```
+    pub fn set(&mut self, i: usize, x: T) -> Option<T> {
+        if i >= self.size {
+            return None;
+        }
+        let e = *self.arena.next(self.find_pred(i), 0)?;
+        Some(std::mem::replace(&mut self.arena.node_mut(e.to).value, x))
+    }
+
+    /// Inserts `x` in the position `i`, shifting the elements after it. Panics
+    /// if `i` is greater than `self.size()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::skiplist::SkiplistList;
+    /// let mut list = SkiplistList::with_seed(0);
+    /// list.add(0, 'b');
+    /// list.add(0, 'a');
+    /// list.add(2, 'c');
+    /// assert_eq!(list.iter().collect::<String>(), "abc");
+    /// ```
+    pub fn add(&mut self, i: usize, x: T) {
+        if i > self.size {
+            panic!("Index out of bounds: {i}");
+        }
+        let k = pick_height(&mut self.rng);
+        self.arena.grow(k);
+        let w = self.arena.alloc(x, vec![None; k + 1]);
+
+        let mut u = None;
+        let mut j = 0;
+        for r in (0..=self.height()).rev() {
+            while let Some(e) = self.arena.next(u, r)
+                && j + e.len <= i
+            {
+                j += e.len;
+                u = Some(e.to);
+            }
+            let next = self.arena.next(u, r).copied();
+            if r <= k {
+                // `w` takes the position `i + 1`, and pushes `next` one further.
+                let after = next.map(|e| Edge { to: e.to, len: j + e.len - i });
+                self.arena.node_mut(w).next[r] = after;
+                self.arena.set_next(u, r, Some(Edge { to: w, len: i + 1 - j }));
+            }
+            else if let Some(e) = next {
+                self.arena.set_next(u, r, Some(Edge { to: e.to, len: e.len + 1 }));
+            }
+        }
+        self.size += 1;
+    }
+
+    /// Removes the element in the position `i` and returns it, shifting the
+    /// elements after it. Returns `None` if `i` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::skiplist::SkiplistList;
+    /// let mut list = SkiplistList::with_seed(0);
+    /// list.add(0, 'a');
+    /// assert_eq!(list.remove(0), Some('a'));
+    /// assert_eq!(list.remove(0), None);
+    /// ```
+    pub fn remove(&mut self, i: usize) -> Option<T> {
+        if i >= self.size {
+            return None;
+        }
+        let mut removed = None;
+        let mut u = None;
+        let mut j = 0;
+        for r in (0..=self.height()).rev() {
+            while let Some(e) = self.arena.next(u, r)
+                && j + e.len <= i
+            {
+                j += e.len;
+                u = Some(e.to);
+            }
+            let Some(e) = self.arena.next(u, r).copied() else { continue };
+            if j + e.len == i + 1 {
+                let after = self.arena.node(e.to).next[r]
+                    .map(|f| Edge { to: f.to, len: e.len + f.len - 1 });
+                self.arena.set_next(u, r, after);
+                removed = Some(e.to);
+            }
+            else {
+                self.arena.set_next(u, r, Some(Edge { to: e.to, len: e.len - 1 }));
+            }
+        }
+        let w = removed.expect("the node in a valid position should be found");
+        self.arena.shrink();
+        self.size -= 1;
+        Some(self.arena.dealloc(w).value)
+    }
+
+    /// Splits the list in two at the position `i`. Returns a new list with the
+    /// elements from `i` on, and leaves the ones before it in `self`. Panics if
+    /// `i` is greater than `self.size()`.
+    ///
+    /// The towers of the elements keep their heights, so the search structure
+    /// of both halves stays balanced. Their nodes have to move to the arena of
+    /// the new list, which takes *O(n - i)* time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::skiplist::SkiplistList;
+    /// let mut list = SkiplistList::with_seed(0);
+    /// for (i, c) in "abcde".chars().enumerate() {
+    ///     list.add(i, c);
+    /// }
+    /// let tail = list.split_off(2);
+    /// assert_eq!(list.iter().collect::<String>(), "ab");
+    /// assert_eq!(tail.iter().collect::<String>(), "cde");
+    /// ```
+    pub fn split_off(&mut self, i: usize) -> Self {
+        if i > self.size {
+            panic!("Index out of bounds: {i}");
+        }
+        let mut preds = vec![(None, 0); self.height() + 1];
+        let mut u = None;
+        let mut j = 0;
+        for r in (0..=self.height()).rev() {
+            while let Some(e) = self.arena.next(u, r)
+                && j + e.len <= i
+            {
+                j += e.len;
+                u = Some(e.to);
+            }
+            preds[r] = (u, j);
+        }
+
+        let mut other = Self::from_rng(Pcg64Mcg::seed_from_u64(self.rng.random()));
+        let first = self.arena.next(preds[0].0, 0).map(|e| e.to);
+        let moved = other.adopt(&mut self.arena, first);
+        for (r, &(u, j)) in preds.iter().enumerate() {
+            if let Some(e) = self.arena.next(u, r).copied() {
+                other.arena.grow(r);
+                other.arena.head[r] = Some(Edge { to: moved[&e.to], len: j + e.len - i });
+                self.arena.set_next(u, r, None);
+            }
+        }
+        self.arena.shrink();
+        other.size = self.size - i;
+        self.size = i;
+        other
+    }
+
+    /// Moves all the elements of `other` to the end of `self`, leaving `other`
+    /// empty. Takes *O(log n + m)* expected time, where `m` is the size of
+    /// `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::skiplist::SkiplistList;
+    /// let mut list1 = SkiplistList::with_seed(1);
+    /// let mut list2 = SkiplistList::with_seed(2);
+    /// list1.add(0, 'a');
+    /// list2.add(0, 'b');
+    /// list1.append(&mut list2);
+    /// assert_eq!(list1.iter().collect::<String>(), "ab");
+    /// assert!(list2.is_empty());
+    /// ```
+    pub fn append(&mut self, other: &mut Self) {
+        let height = self.height().max(other.height());
+        self.arena.grow(height);
+        let mut lasts = vec![(None, 0); height + 1];
+        let mut u = None;
+        let mut j = 0;
+        for r in (0..=height).rev() {
+            while let Some(e) = self.arena.next(u, r) {
+                j += e.len;
+                u = Some(e.to);
+            }
+            lasts[r] = (u, j);
+        }
+
+        let first = other.arena.head[0].map(|e| e.to);
+        let moved = self.adopt(&mut other.arena, first);
+        for (r, &(u, j)) in lasts.iter().enumerate() {
+            if let Some(e) = other.arena.head.get(r).copied().flatten() {
+                let len = self.size - j + e.len;
+                self.arena.set_next(u, r, Some(Edge { to: moved[&e.to], len }));
+            }
+        }
+        self.arena.shrink();
+        self.size += other.size;
+        other.arena = Arena::new();
+        other.size = 0;
+    }
+
+    /// Moves the nodes of `from` that follow `first` in `L_0`, `first`
+    /// included, to the arena of `self`, and relinks them to each other.
+    /// Returns the new index of every moved node, by its index in `from`.
+    fn adopt(&mut self, from: &mut Arena<T, Edge>, first: Option<usize>) -> HashMap<usize, usize> {
+        let mut moved = HashMap::new();
+        let mut links = Vec::new();
+        let mut v = first;
+        while let Some(w) = v {
+            let node = from.dealloc(w);
+            v = node.next[0].map(|e| e.to);
+            moved.insert(w, self.arena.alloc(node.value, Vec::new()));
+            links.push((w, node.next));
+        }
+        for (w, next) in links {
+            let next = next.into_iter()
+                .map(|e| e.map(|e| Edge { to: moved[&e.to], len: e.len }))
+                .collect();
+            self.arena.node_mut(moved[&w]).next = next;
+        }
+        moved
+    }
+
+    /// Returns an iterator over the elements of the list, from first to last.
+    pub fn iter(&self) -> SkiplistListIterator<'_, T> {
+        SkiplistListIterator { arena: &self.arena, next: self.arena.head[0].map(|e| e.to) }
+    }
+}
+
+impl<T> Default for SkiplistList<T> {
+    fn default() -> Self {
+        Self::initialize()
+    }
+}
+
+impl<T> List<T> for SkiplistList<T> {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        self.get(i)
+    }
+
+    fn set(&mut self, i: usize, x: T) -> Option<T> {
+        self.set(i, x)
+    }
+
+    fn add(&mut self, i: usize, x: T) {
+        self.add(i, x)
+    }
+
+    fn remove(&mut self, i: usize) -> Option<T> {
+        self.remove(i)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SkiplistList<T> {
+    type Item = &'a T;
+    type IntoIter = SkiplistListIterator<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct SkiplistListIterator<'a, T> {
+    arena: &'a Arena<T, Edge>,
+    next: Option<usize>,
+}
+
+impl<'a, T> Iterator for SkiplistListIterator<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.arena.node(self.next?);
+        self.next = node.next[0].map(|e| e.to);
+        Some(&node.value)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn initialize() {
+        let set: SkiplistSSet<i32> = SkiplistSSet::initialize();
+        assert_eq!(set.size(), 0);
+        assert!(set.is_empty());
+        assert_eq!(set.height(), 0);
+        assert_eq!(set.iter().next(), None);
+    }
+
+    #[test]
+    fn pick_height_is_geometric() {
+        let mut rng = Pcg64Mcg::seed_from_u64(1);
+        let mut counts = [0; 4];
+        for _ in 0..10000 {
+            let h = pick_height(&mut rng);
+            if h < counts.len() {
+                counts[h] += 1;
+            }
+        }
+        assert!((4800..5200).contains(&counts[0]));
+        assert!((2300..2700).contains(&counts[1]));
+        assert!((1100..1400).contains(&counts[2]));
+    }
+
+    #[test]
+    fn add_and_find() {
+        let mut set = SkiplistSSet::with_seed(2);
+        for x in [5, 1, 9, 3, 7] {
+            assert!(set.add(x));
+        }
+        assert!(!set.add(9));
+        assert_eq!(set.size(), 5);
+        assert_eq!(set.find(&0), Some(&1));
+        assert_eq!(set.find(&4), Some(&5));
+        assert_eq!(set.find(&9), Some(&9));
+        assert_eq!(set.find(&10), None);
+        assert!(set.contains(&7));
+        assert!(!set.contains(&8));
+    }
+
+    #[test]
+    fn remove() {
+        let mut set = SkiplistSSet::with_seed(3);
+        for x in 0..100 {
+            set.add(x);
+        }
+        for x in (0..100).step_by(2) {
+            assert!(set.remove(&x));
+        }
+        assert!(!set.remove(&0));
+        assert_eq!(set.size(), 50);
+        assert_eq!(set.find(&10), Some(&11));
+
+        for x in (1..100).step_by(2) {
+            assert!(set.remove(&x));
+        }
+        assert!(set.is_empty());
+        assert_eq!(set.height(), 0);
+        assert_eq!(set.arena.free.len(), set.arena.nodes.len());
+    }
+
+    #[test]
+    fn reuses_freed_nodes() {
+        let mut set = SkiplistSSet::with_seed(4);
+        for x in 0..10 {
+            set.add(x);
+        }
+        set.remove(&3);
+        set.remove(&6);
+        set.add(30);
+        set.add(60);
+        assert_eq!(set.arena.nodes.len(), 10);
+        assert!(set.arena.free.is_empty());
+    }
+
+    #[test]
+    fn iter_is_sorted() {
+        let mut set = SkiplistSSet::with_seed(5);
+        for x in [8, 3, 5, 1, 9, 2] {
+            set.add(x);
+        }
+        assert_eq!(set.iter().copied().collect::<Vec<i32>>(), [1, 2, 3, 5, 8, 9]);
+        assert_eq!((&set).into_iter().count(), 6);
+    }
+
+    #[test]
+    fn height_is_logarithmic() {
+        let mut set = SkiplistSSet::with_seed(6);
+        for n in 1..=10000u32 {
+            set.add(n);
+            // The expected height is about log2(n), and it goes past 3 log2(n)
+            // with probability at most 1 / n^2, small enough from n = 64 on.
+            let bound = 3 * n.ilog2() as usize;
+            if n >= 64 {
+                assert!(set.height() <= bound, "height {} for n = {n}", set.height());
+            }
+        }
+    }
+
+    #[test]
+    fn matches_btree_set() {
+        let mut rng = Pcg64Mcg::seed_from_u64(7);
+        let mut set = SkiplistSSet::with_seed(7);
+        let mut btree = BTreeSet::new();
+        for _ in 0..10000 {
+            let x = rng.random_range(0..500);
+            match rng.random_range(0..3) {
+                0 => assert_eq!(set.add(x), btree.insert(x)),
+                1 => assert_eq!(set.remove(&x), btree.remove(&x)),
+                _ => assert_eq!(set.find(&x), btree.range(x..).next()),
+            }
+            assert_eq!(set.size(), btree.len());
+        }
+        assert!(set.iter().eq(btree.iter()));
+    }
+
+    /// Checks that every link of `list` skips over as many positions as its
+    /// length says, and that `L_r` is a sublist of `L_{r-1}`.
+    fn check_lengths<T>(list: &SkiplistList<T>) {
+        let mut position = HashMap::new();
+        let mut v = list.arena.head[0].map(|e| e.to);
+        while let Some(w) = v {
+            position.insert(w, position.len() + 1);
+            v = list.arena.node(w).next[0].map(|e| e.to);
+        }
+        assert_eq!(position.len(), list.size());
+
+        for r in 0..=list.height() {
+            let mut u = None;
+            let mut j = 0;
+            while let Some(e) = list.arena.next(u, r) {
+                assert_eq!(position[&e.to], j + e.len);
+                assert!(list.arena.node(e.to).next.len() > r);
+                j += e.len;
+                u = Some(e.to);
+            }
+        }
+        if list.height() > 0 {
+            assert!(list.arena.head[list.height()].is_some());
+        }
+    }
+
+    #[test]
+    fn list_initialize() {
+        let list: SkiplistList<i32> = SkiplistList::initialize();
+        assert_eq!(list.size(), 0);
+        assert!(list.is_empty());
+        assert_eq!(list.get(0), None);
+    }
+
+    #[test]
+    fn list_add_get_set_remove() {
+        let mut list = SkiplistList::with_seed(10);
+        for x in 0..10 {
+            list.add(list.size() / 2, x);
+        }
+        check_lengths(&list);
+        assert_eq!(list.iter().copied().collect::<Vec<i32>>(), [1, 3, 5, 7, 9, 8, 6, 4, 2, 0]);
+        assert_eq!(list.get(4), Some(&9));
+        assert_eq!(list.get(10), None);
+        assert_eq!(list.set(4, 90), Some(9));
+        assert_eq!(list.set(10, 100), None);
+        assert_eq!(list.remove(4), Some(90));
+        assert_eq!(list.remove(9), None);
+        check_lengths(&list);
+        assert_eq!(list.iter().copied().collect::<Vec<i32>>(), [1, 3, 5, 7, 8, 6, 4, 2, 0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn list_add_out_of_bounds() {
+        let mut list = SkiplistList::with_seed(11);
+        list.add(1, 'a');
+    }
+
+    #[test]
+    fn list_remove_all() {
+        let mut list = SkiplistList::with_seed(12);
+        for x in 0..100 {
+            list.add(x, x);
+        }
+        for x in 0..100 {
+            assert_eq!(list.remove(0), Some(x));
+        }
+        assert!(list.is_empty());
+        assert_eq!(list.height(), 0);
+        assert_eq!(list.arena.free.len(), list.arena.nodes.len());
+    }
+
+    #[test]
+    fn list_split_off_and_append() {
+        let mut list = SkiplistList::with_seed(13);
+        for x in 0..50 {
+            list.add(x, x);
+        }
+        let mut tail = list.split_off(20);
+        check_lengths(&list);
+        check_lengths(&tail);
+        assert_eq!(list.iter().copied().collect::<Vec<usize>>(), (0..20).collect::<Vec<usize>>());
+        assert_eq!(tail.iter().copied().collect::<Vec<usize>>(), (20..50).collect::<Vec<usize>>());
+
+        let empty = tail.split_off(30);
+        assert!(empty.is_empty());
+        let all = list.split_off(0);
+        assert!(list.is_empty());
+        assert_eq!(all.size(), 20);
+
+        list.append(&mut tail);
+        check_lengths(&list);
+        assert!(tail.is_empty());
+        assert_eq!(list.iter().copied().collect::<Vec<usize>>(), (20..50).collect::<Vec<usize>>());
+        list.add(30, 50);
+        assert_eq!(list.get(30), Some(&50));
+    }
+
+    #[test]
+    fn list_matches_vec() {
+        for seed in 0..20 {
+            let mut rng = Pcg64Mcg::seed_from_u64(seed);
+            let mut list = SkiplistList::with_seed(seed);
+            let mut vec = Vec::new();
+            for step in 0..1000 {
+                match rng.random_range(0..10) {
+                    0..4 => {
+                        let i = rng.random_range(0..=vec.len());
+                        list.add(i, step);
+                        vec.insert(i, step);
+                    },
+                    4..6 => {
+                        let i = rng.random_range(0..=vec.len());
+                        let expected = (i < vec.len()).then(|| vec.remove(i));
+                        assert_eq!(list.remove(i), expected);
+                    },
+                    6..8 => {
+                        let i = rng.random_range(0..=vec.len());
+                        let expected = vec.get_mut(i).map(|y| std::mem::replace(y, step));
+                        assert_eq!(list.set(i, step), expected);
+                    },
+                    8 => {
+                        let i = rng.random_range(0..=vec.len());
+                        assert_eq!(list.get(i), vec.get(i));
+                    },
+                    _ => {
+                        let i = rng.random_range(0..=vec.len());
+                        let mut tail = list.split_off(i);
+                        let vec_tail = vec.split_off(i);
+                        assert!(tail.iter().eq(vec_tail.iter()));
+                        // Put the tail back, sometimes after the head.
+                        if rng.random_bool(0.5) {
+                            list.append(&mut tail);
+                            vec.extend(vec_tail);
+                        } else {
+                            tail.append(&mut list);
+                            list = tail;
+                            vec = vec_tail.into_iter().chain(vec).collect();
+                        }
+                    },
+                }
+                assert_eq!(list.size(), vec.len());
+                if step % 50 == 0 {
+                    check_lengths(&list);
+                }
+            }
+            check_lengths(&list);
+            assert!(list.iter().eq(vec.iter()));
+        }
+    }
+}
@@ -0,0 +1,527 @@
+//! A space-efficient linked list whose nodes store blocks of elements.
+//!
+//! Every node holds an `ArrayDeque` block with between `b - 1` and `b + 1`
+//! elements, except for the last node, which may hold fewer. Finding the `i`-th
+//! element therefore walks over *O(1 + min(i, n - i) / b)* nodes, and the list
+//! wastes only *O(b + n / b)* space. Insertions and removals that would break
+//! the block bounds are fixed by the book's `spread` and `gather` operations.
+//!
+//! `DLList` shows that `Rc<RefCell<>>` links can't hand out plain references to
+//! elements in the middle of the list. To keep `get` returning `&T`, the nodes
+//! of `SEList` live in a `Vec` and link to each other by index instead. The
+//! node at index 0 is the dummy node of the book, which closes the list into
+//! a circle.
+
+use crate::array_deque::ArrayDeque;
+use crate::interfaces::List;
+
+
+const DUMMY: usize = 0;
+
+
+/// A space-efficient linked list whose nodes store blocks of elements.
+pub struct SEList<T> {
+    nodes: Vec<Node<T>>,
+    free: Vec<usize>,
+    b: usize,
+    size: usize,
+}
+
+struct Node<T> {
+    block: ArrayDeque<T>,
+    next: usize,
+    prev: usize,
+}
+
+impl<T> Node<T> {
+    fn new(next: usize, prev: usize) -> Self {
+        Self { block: ArrayDeque::initialize(), next, prev }
+    }
+}
+
+
+impl<T> SEList<T> {
+    /// Creates a new, empty list whose blocks hold around `b` elements. Panics
+    /// if `b` is less than 2.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::space_efficient_list::SEList;
+    /// let list: SEList<i32> = SEList::initialize(4);
+    /// ```
+    pub fn initialize(b: usize) -> Self {
+        assert!(b > 1, "SEList block size must be greater than 1");
+        Self { nodes: vec![Node::new(DUMMY, DUMMY)], free: Vec::new(), b, size: 0 }
+    }
+
+    /// Returns the number of elements in the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::space_efficient_list::SEList;
+    /// let list: SEList<i32> = SEList::initialize(4);
+    /// assert_eq!(list.size(), 0);
+    /// ```
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns a shared reference to the element in the position `i`, or `None`
+    /// if `i` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::space_efficient_list::SEList;
+    /// let mut list = SEList::initialize(4);
+    /// list.add(0, 'a');
+    /// assert_eq!(list.get(0), Some(&'a'));
+    /// assert_eq!(list.get(1), None);
+    /// ```
+    pub fn get(&self, i: usize) -> Option<&T> {
+        if self.is_out_of_indexing_bounds(i) {
+            return None;
+        }
+        let (u, j) = self.get_location(i);
+        self.nodes[u].block.get(j)
+    }
+
+    /// Replaces the element in the position `i` with `x` and returns the
+    /// original element. Returns `None` if `i` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::space_efficient_list::SEList;
+    /// let mut list = SEList::initialize(4);
+    /// list.add(0, 'a');
+    /// assert_eq!(list.set(0, 'b'), Some('a'));
+    /// assert_eq!(list.get(0), Some(&'b'));
+    /// ```
+    pub fn set(&mut self, i: usize, x: T) -> Option<T> {
+        if self.is_out_of_indexing_bounds(i) {
+            return None;
+        }
+        let (u, j) = self.get_location(i);
+        let y = self.nodes[u].block.get_mut(j)?;
+        Some(std::mem::replace(y, x))
+    }
+
+    /// Inserts `x` in the position `i`, shifting all the elements after it.
+    /// Panics if `i` is out of bounds.
+    ///
+    /// Notice that `i = self.size()` is a valid location and is equivalent to
+    /// inserting at the end of the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::space_efficient_list::SEList;
+    /// let mut list = SEList::initialize(4);
+    /// list.add(0, 'b');
+    /// list.add(0, 'a');
+    /// list.add(2, 'c');
+    /// assert_eq!(list.iter().collect::<Vec<&char>>(), [&'a', &'b', &'c']);
+    /// ```
+    pub fn add(&mut self, i: usize, x: T) {
+        if self.is_out_of_inserting_bounds(i) {
+            panic!("Index out of bounds: {i}");
+        }
+        if i == self.size() {
+            self.add_last(x);
+            return;
+        }
+        let (u, j) = self.get_location(i);
+        let mut v = u;
+        let mut r = 0;
+        while r < self.b && v != DUMMY && self.block_size(v) == self.b + 1 {
+            v = self.nodes[v].next;
+            r += 1;
+        }
+        if r == self.b {
+            self.spread(u);
+            v = u;
+        }
+        if v == DUMMY {
+            v = self.add_before(v);
+        }
+        while v != u {
+            let w = self.nodes[v].prev;
+            self.move_last_to_front(w, v);
+            v = w;
+        }
+        self.nodes[u].block.add(j, x);
+        self.size += 1;
+    }
+
+    /// Removes the element in the position `i`, shifting all the elements after
+    /// it. Returns the element if `i` is not out of bounds, `None` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::space_efficient_list::SEList;
+    /// let mut list = SEList::initialize(4);
+    /// list.add(0, 'a');
+    /// assert_eq!(list.remove(0), Some('a'));
+    /// assert_eq!(list.remove(0), None);
+    /// ```
+    pub fn remove(&mut self, i: usize) -> Option<T> {
+        if self.is_out_of_indexing_bounds(i) {
+            return None;
+        }
+        let (u, j) = self.get_location(i);
+        let mut v = u;
+        let mut r = 0;
+        while r < self.b && v != DUMMY && self.block_size(v) == self.b - 1 {
+            v = self.nodes[v].next;
+            r += 1;
+        }
+        if r == self.b {
+            self.gather(u);
+        }
+        let x = self.nodes[u].block.remove(j);
+
+        let mut v = u;
+        while self.block_size(v) < self.b - 1 && self.nodes[v].next != DUMMY {
+            let w = self.nodes[v].next;
+            self.move_first_to_back(w, v);
+            v = w;
+        }
+        if self.block_size(v) == 0 {
+            self.remove_node(v);
+        }
+        self.size -= 1;
+        x
+    }
+
+    /// Returns an iterator of shared references to the list's elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::space_efficient_list::SEList;
+    /// let mut list = SEList::initialize(4);
+    /// list.add(0, 'a');
+    /// assert_eq!(list.iter().next(), Some(&'a'));
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { list: self, node: self.nodes[DUMMY].next, index: 0 }
+    }
+
+    /// Returns the node that stores the position `i` and the position of the
+    /// element within the node's block. Walks from the closest end of the list.
+    fn get_location(&self, mut i: usize) -> (usize, usize) {
+        if i < self.size() / 2 {
+            let mut u = self.nodes[DUMMY].next;
+            while i >= self.block_size(u) {
+                i -= self.block_size(u);
+                u = self.nodes[u].next;
+            }
+            (u, i)
+        } else {
+            let mut u = DUMMY;
+            let mut index = self.size();
+            while i < index {
+                u = self.nodes[u].prev;
+                index -= self.block_size(u);
+            }
+            (u, i - index)
+        }
+    }
+
+    fn add_last(&mut self, x: T) {
+        let mut last = self.nodes[DUMMY].prev;
+        if last == DUMMY || self.block_size(last) == self.b + 1 {
+            last = self.add_before(DUMMY);
+        }
+        let block = &mut self.nodes[last].block;
+        block.add(block.size(), x);
+        self.size += 1;
+    }
+
+    /// Turns `b` full nodes starting at `u` into `b + 1` nodes of `b` elements.
+    fn spread(&mut self, u: usize) {
+        let mut w = u;
+        for _ in 0..self.b {
+            w = self.nodes[w].next;
+        }
+        w = self.add_before(w);
+        while w != u {
+            let v = self.nodes[w].prev;
+            while self.block_size(w) < self.b {
+                self.move_last_to_front(v, w);
+            }
+            w = v;
+        }
+    }
+
+    /// Turns `b` nodes of `b - 1` elements starting at `u` into `b - 1` nodes
+    /// of `b` elements.
+    fn gather(&mut self, u: usize) {
+        let mut w = u;
+        for _ in 0..self.b - 1 {
+            let v = self.nodes[w].next;
+            while self.block_size(w) < self.b {
+                self.move_first_to_back(v, w);
+            }
+            w = v;
+        }
+        self.remove_node(w);
+    }
+
+    fn move_last_to_front(&mut self, from: usize, to: usize) {
+        let last = self.block_size(from) - 1;
+        let x = self.nodes[from].block
+            .remove(last)
+            .expect("`from` block should not be empty");
+
+        self.nodes[to].block.add(0, x);
+    }
+
+    fn move_first_to_back(&mut self, from: usize, to: usize) {
+        let x = self.nodes[from].block
+            .remove(0)
+            .expect("`from` block should not be empty");
+
+        let block = &mut self.nodes[to].block;
+        block.add(block.size(), x);
+    }
+
+    /// Links a new node with an empty block right before `w` and returns it.
+    fn add_before(&mut self, w: usize) -> usize {
+        let prev = self.nodes[w].prev;
+        let u = match self.free.pop() {
+            Some(u) => {
+                self.nodes[u] = Node::new(w, prev);
+                u
+            },
+            None => {
+                self.nodes.push(Node::new(w, prev));
+                self.nodes.len() - 1
+            },
+        };
+        self.nodes[prev].next = u;
+        self.nodes[w].prev = u;
+        u
+    }
+
+    fn remove_node(&mut self, w: usize) {
+        let Node { next, prev, .. } = self.nodes[w];
+        self.nodes[prev].next = next;
+        self.nodes[next].prev = prev;
+        self.free.push(w);
+    }
+
+    fn block_size(&self, u: usize) -> usize {
+        self.nodes[u].block.size()
+    }
+
+    fn is_out_of_indexing_bounds(&self, i: usize) -> bool {
+        i >= self.size()
+    }
+
+    fn is_out_of_inserting_bounds(&self, i: usize) -> bool {
+        i > self.size()
+    }
+}
+
+
+impl<T> List<T> for SEList<T> {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        self.get(i)
+    }
+
+    fn set(&mut self, i: usize, x: T) -> Option<T> {
+        self.set(i, x)
+    }
+
+    fn add(&mut self, i: usize, x: T) {
+        self.add(i, x)
+    }
+
+    fn remove(&mut self, i: usize) -> Option<T> {
+        self.remove(i)
+    }
+}
+
+
+pub struct Iter<'a, T> {
+    list: &'a SEList<T>,
+    node: usize,
+    index: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.node != DUMMY {
+            let node = &self.list.nodes[self.node];
+            if let Some(x) = node.block.get(self.index) {
+                self.index += 1;
+                return Some(x);
+            }
+            self.node = node.next;
+            self.index = 0;
+        }
+        None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_pcg::Pcg64Mcg;
+
+    fn block_sizes<T>(list: &SEList<T>) -> Vec<usize> {
+        let mut sizes = Vec::new();
+        let mut u = list.nodes[DUMMY].next;
+        while u != DUMMY {
+            sizes.push(list.block_size(u));
+            u = list.nodes[u].next;
+        }
+        sizes
+    }
+
+    fn blocks_are_bounded<T>(list: &SEList<T>) -> bool {
+        let sizes = block_sizes(list);
+        let (last, rest) = match sizes.split_last() {
+            None => return true,
+            Some(split) => split,
+        };
+        let bounded = |&size: &usize| size >= list.b - 1 && size <= list.b + 1;
+        rest.iter().all(bounded) && *last > 0 && *last <= list.b + 1
+    }
+
+    fn build_test_list(b: usize, n: usize) -> SEList<usize> {
+        let mut list = SEList::initialize(b);
+        for x in 0..n {
+            list.add(x, x);
+        }
+        list
+    }
+
+    #[test]
+    fn initialize_returns_empty_list() {
+        let list = SEList::<i32>::initialize(3);
+        assert_eq!(list.size(), 0);
+        assert_eq!(list.iter().count(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn initialize_small_block_panics() {
+        let _ = SEList::<i32>::initialize(1);
+    }
+
+    #[test]
+    fn add_last_fills_blocks() {
+        let list = build_test_list(3, 10);
+        assert_eq!(block_sizes(&list), [4, 4, 2]);
+        assert!(list.iter().copied().eq(0..10));
+    }
+
+    #[test]
+    fn add_spreads_full_blocks() {
+        let mut list = build_test_list(3, 12);
+        assert_eq!(block_sizes(&list), [4, 4, 4]);
+        list.add(0, 100);
+        assert_eq!(block_sizes(&list), [4, 3, 3, 3]);
+        assert_eq!(list.get(0), Some(&100));
+        assert!(list.iter().skip(1).copied().eq(0..12));
+    }
+
+    #[test]
+    fn remove_gathers_sparse_blocks() {
+        let mut list = build_test_list(3, 12);
+        list.add(0, 100);
+        list.remove(0);
+        list.remove(0);
+        assert_eq!(block_sizes(&list), [2, 3, 3, 3]);
+        list.remove(0);
+        assert_eq!(block_sizes(&list), [2, 2, 3, 3]);
+        list.remove(0);
+        assert_eq!(block_sizes(&list), [2, 2, 2, 3]);
+        list.remove(0);
+        assert_eq!(block_sizes(&list), [2, 3, 3]);
+        assert!(list.iter().copied().eq(4..12));
+    }
+
+    #[test]
+    fn get_returns_some_reference() {
+        let list = build_test_list(2, 7);
+        for i in 0..7 {
+            assert_eq!(list.get(i), Some(&i));
+        }
+        assert!(list.get(7).is_none());
+    }
+
+    #[test]
+    fn set_returns_prior_element() {
+        let mut list = build_test_list(2, 7);
+        assert_eq!(list.set(1, 10), Some(1));
+        assert_eq!(list.set(6, 60), Some(6));
+        assert_eq!(list.set(7, 70), None);
+        assert_eq!(list.iter().copied().collect::<Vec<usize>>(), [0, 10, 2, 3, 4, 5, 60]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn add_out_of_bounds_panics() {
+        let mut list = build_test_list(2, 3);
+        list.add(4, 4);
+    }
+
+    #[test]
+    fn remove_out_of_bounds_returns_none() {
+        let mut list = build_test_list(2, 3);
+        assert!(list.remove(3).is_none());
+        assert_eq!(list.size(), 3);
+    }
+
+    #[test]
+    fn remove_frees_and_reuses_nodes() {
+        let mut list = build_test_list(2, 9);
+        while list.size() > 0 {
+            list.remove(0);
+        }
+        assert_eq!(block_sizes(&list), []);
+        let allocated = list.nodes.len();
+        for x in 0..9 {
+            list.add(x, x);
+        }
+        assert_eq!(list.nodes.len(), allocated);
+    }
+
+    #[test]
+    fn matches_vec() {
+        let mut rng = Pcg64Mcg::seed_from_u64(1999);
+        for b in 2..6 {
+            let mut list = SEList::initialize(b);
+            let mut vec = Vec::new();
+
+            for x in 0..1500 {
+                let size = list.size();
+                if size > 0 && rng.random_bool(0.45) {
+                    let i = rng.random_range(0..size);
+                    assert_eq!(list.remove(i), Some(vec.remove(i)));
+                } else {
+                    let i = rng.random_range(0..=size);
+                    list.add(i, x);
+                    vec.insert(i, x);
+                }
+                assert!(blocks_are_bounded(&list));
+            }
+            assert!(list.iter().eq(vec.iter()));
+        }
+    }
+}
@@ -0,0 +1,455 @@
+//! A treap, the randomized binary search tree of the book's seventh chapter.
+//!
+//! Every node gets a random priority when it is added, and the tree is kept
+//! both a binary search tree for the values and a heap for the priorities,
+//! with the smallest priority at the root. The shape of a treap is then the
+//! same as if its values had been added to a `BinarySearchTree` in a random
+//! order, whatever the order they were actually added in, so its expected
+//! depth is *O(log n)* even for sorted input.
+//!
+//! The heap property is restored with rotations, which move nodes up and down
+//! the tree without parent links, so nodes are simply owned by their parent
+//! through a `Box`.
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64Mcg;
+use crate::interfaces::SSet;
+
+
+type Link<T> = Option<Box<Node<T>>>;
+
+#[derive(Debug)]
+struct Node<T> {
+    value: T,
+    priority: u64,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+/// A treap data structure.
+#[derive(Debug)]
+pub struct Treap<T> {
+    root: Link<T>,
+    size: usize,
+    rng: Pcg64Mcg,
+}
+
+impl<T> Treap<T> {
+    /// Creates a new, empty treap whose priorities are drawn from a generator
+    /// seeded by the OS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::treap::Treap;
+    /// let treap: Treap<i32> = Treap::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::from_rng(Pcg64Mcg::from_os_rng())
+    }
+
+    /// Creates a new, empty treap whose priorities are drawn from a generator
+    /// seeded with `state`, so that its shape is reproducible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::treap::Treap;
+    /// let mut treap1 = Treap::with_seed(42);
+    /// let mut treap2 = Treap::with_seed(42);
+    /// for x in 0..100 {
+    ///     treap1.add(x);
+    ///     treap2.add(x);
+    /// }
+    /// assert_eq!(treap1.height(), treap2.height());
+    /// ```
+    pub fn with_seed(state: u64) -> Self {
+        Self::from_rng(Pcg64Mcg::seed_from_u64(state))
+    }
+
+    fn from_rng(rng: Pcg64Mcg) -> Self {
+        Self { root: None, size: 0, rng }
+    }
+
+    /// Returns the number of nodes contained in `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::treap::Treap;
+    /// let treap: Treap<i32> = Treap::new();
+    /// assert_eq!(treap.size(), 0);
+    /// ```
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns `true` if the size of the treap is zero, `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::treap::Treap;
+    /// let treap: Treap<i32> = Treap::new();
+    /// assert!(treap.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Returns the number of nodes in the longest path from the root to a
+    /// leaf, or 0 if the treap is empty.
+    ///
+    /// The height is computed recursively, which is fine because it is
+    /// *O(log n)* with high probability.
+    pub fn height(&self) -> usize {
+        fn height<T>(link: &Link<T>) -> usize {
+            match link {
+                None => 0,
+                Some(node) => 1 + height(&node.left).max(height(&node.right)),
+            }
+        }
+        height(&self.root)
+    }
+}
+
+impl<T> Default for Treap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> Treap<T> {
+    /// Returns a reference to the value in `self` equal to `value`, or `None`
+    /// if `value` is not in `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::treap::Treap;
+    /// let mut treap = Treap::new();
+    /// assert!(treap.find(&0).is_none());
+    /// treap.add(0);
+    /// assert_eq!(treap.find(&0), Some(&0));
+    ///
+    /// let mut treap = Treap::new();
+    /// treap.add(String::from("a"));
+    /// assert!(treap.find("a").is_some());
+    /// ```
+    pub fn find<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut link = &self.root;
+        while let Some(node) = link {
+            link = match value.cmp(node.value.borrow()) {
+                Ordering::Less => &node.left,
+                Ordering::Greater => &node.right,
+                Ordering::Equal => return Some(&node.value),
+            };
+        }
+        None
+    }
+
+    /// Returns `true` if `value` is in `self`, otherwise `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::treap::Treap;
+    /// let mut treap = Treap::new();
+    /// assert!(!treap.contains(&0));
+    /// treap.add(0);
+    /// assert!(treap.contains(&0));
+    /// ```
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(value).is_some()
+    }
+
+    /// Adds `value` to the treap `self`. Returns `false` if `value` is already
+    /// in `self`, otherwise `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::treap::Treap;
+    /// let mut treap = Treap::new();
+    /// assert!(treap.add(0));
+    /// assert!(!treap.add(0));
+    /// ```
+    pub fn add(&mut self, value: T) -> bool {
+        let priority = self.rng.random();
+        let added = Self::add_at(&mut self.root, value, priority);
+        if added {
+            self.size += 1;
+        }
+        added
+    }
+
+    /// Adds `value` to the subtree at `link` as a leaf, and then rotates it up
+    /// while its priority is smaller than its parent's.
+    fn add_at(link: &mut Link<T>, value: T, priority: u64) -> bool {
+        let Some(node) = link else {
+            *link = Some(Box::new(Node { value, priority, left: None, right: None }));
+            return true;
+        };
+        match value.cmp(&node.value) {
+            Ordering::Less => {
+                let added = Self::add_at(&mut node.left, value, priority);
+                if node.left.as_ref().is_some_and(|left| left.priority < node.priority) {
+                    Self::rotate_right(link);
+                }
+                added
+            },
+            Ordering::Greater => {
+                let added = Self::add_at(&mut node.right, value, priority);
+                if node.right.as_ref().is_some_and(|right| right.priority < node.priority) {
+                    Self::rotate_left(link);
+                }
+                added
+            },
+            Ordering::Equal => false,
+        }
+    }
+
+    /// Removes `value` from the treap `self`. Returns `false` if `value` is not
+    /// in `self`, otherwise `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ods::treap::Treap;
+    /// let mut treap = Treap::new();
+    /// treap.add(0);
+    /// assert!(treap.remove(&0));
+    /// assert!(!treap.remove(&0));
+    /// ```
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let removed = Self::remove_at(&mut self.root, value);
+        if removed {
+            self.size -= 1;
+        }
+        removed
+    }
+
+    /// Finds `value` in the subtree at `link`, and rotates it down, always
+    /// lifting its child with the smaller priority, until it becomes a leaf
+    /// and can be dropped.
+    fn remove_at<Q>(link: &mut Link<T>, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let Some(node) = link else {
+            return false;
+        };
+        match value.cmp(node.value.borrow()) {
+            Ordering::Less => Self::remove_at(&mut node.left, value),
+            Ordering::Greater => Self::remove_at(&mut node.right, value),
+            Ordering::Equal => match (&node.left, &node.right) {
+                (None, None) => {
+                    *link = None;
+                    true
+                },
+                (Some(left), Some(right)) if left.priority < right.priority => {
+                    Self::rotate_right(link);
+                    let node = link.as_mut().expect("a rotated subtree has a root");
+                    Self::remove_at(&mut node.right, value)
+                },
+                (_, Some(_)) => {
+                    Self::rotate_left(link);
+                    let node = link.as_mut().expect("a rotated subtree has a root");
+                    Self::remove_at(&mut node.left, value)
+                },
+                (Some(_), None) => {
+                    Self::rotate_right(link);
+                    let node = link.as_mut().expect("a rotated subtree has a root");
+                    Self::remove_at(&mut node.right, value)
+                },
+            },
+        }
+    }
+
+    /// Makes the left child of the root at `link` the new root of the subtree.
+    fn rotate_right(link: &mut Link<T>) {
+        let mut node = link.take().expect("only non-empty subtrees are rotated");
+        let mut left = node.left.take().expect("rotating right needs a left child");
+        node.left = left.right.take();
+        left.right = Some(node);
+        *link = Some(left);
+    }
+
+    /// Makes the right child of the root at `link` the new root of the
+    /// subtree.
+    fn rotate_left(link: &mut Link<T>) {
+        let mut node = link.take().expect("only non-empty subtrees are rotated");
+        let mut right = node.right.take().expect("rotating left needs a right child");
+        node.right = right.left.take();
+        right.left = Some(node);
+        *link = Some(right);
+    }
+}
+
+impl<T: Ord + Clone> SSet<T> for Treap<T> {
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn add(&mut self, x: T) -> bool {
+        self.add(x)
+    }
+
+    fn remove(&mut self, x: &T) -> bool {
+        self.remove(x)
+    }
+
+    fn find(&self, x: &T) -> Option<T> {
+        let mut link = &self.root;
+        let mut candidate = None;
+        while let Some(node) = link {
+            link = match x.cmp(&node.value) {
+                Ordering::Less => {
+                    candidate = Some(&node.value);
+                    &node.left
+                },
+                Ordering::Greater => &node.right,
+                Ordering::Equal => return Some(node.value.clone()),
+            };
+        }
+        candidate.cloned()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    /// Checks the search tree property for the values and the heap property
+    /// for the priorities, and returns the number of nodes.
+    fn check_invariants<T: Ord>(link: &Link<T>, lo: Option<&T>, hi: Option<&T>) -> usize {
+        let Some(node) = link else { return 0 };
+        assert!(lo.is_none_or(|lo| *lo < node.value));
+        assert!(hi.is_none_or(|hi| node.value < *hi));
+        for child in [&node.left, &node.right].into_iter().flatten() {
+            assert!(node.priority <= child.priority);
+        }
+        1 + check_invariants(&node.left, lo, Some(&node.value))
+            + check_invariants(&node.right, Some(&node.value), hi)
+    }
+
+    #[test]
+    fn height_counts_nodes() {
+        let mut treap = Treap::with_seed(1);
+        treap.add(0);
+        assert_eq!(treap.height(), 1);
+        treap.add(1);
+        assert_eq!(treap.height(), 2);
+    }
+
+    #[test]
+    fn new() {
+        let treap: Treap<i32> = Treap::new();
+        assert!(treap.is_empty());
+        assert_eq!(treap.size(), 0);
+        assert_eq!(treap.height(), 0);
+    }
+
+    #[test]
+    fn add_find_remove() {
+        let mut treap = Treap::with_seed(1);
+        for x in [5, 2, 8, 1, 9, 3] {
+            assert!(treap.add(x));
+        }
+        assert!(!treap.add(8));
+        assert_eq!(treap.size(), 6);
+        assert_eq!(treap.find(&3), Some(&3));
+        assert_eq!(treap.find(&4), None);
+        assert_eq!(check_invariants(&treap.root, None, None), 6);
+
+        assert!(treap.remove(&5));
+        assert!(!treap.remove(&5));
+        assert!(!treap.remove(&7));
+        assert_eq!(treap.size(), 5);
+        assert_eq!(treap.find(&5), None);
+        assert_eq!(check_invariants(&treap.root, None, None), 5);
+    }
+
+    #[test]
+    fn lookups_take_borrowed_values() {
+        let mut treap = Treap::with_seed(2);
+        for fruit in ["kiwi", "apple", "pear"] {
+            treap.add(String::from(fruit));
+        }
+        assert!(treap.contains("kiwi"));
+        assert!(!treap.contains("plum"));
+        assert_eq!(treap.find("apple").map(String::as_str), Some("apple"));
+        assert!(treap.remove("kiwi"));
+        assert!(!treap.remove("kiwi"));
+        assert_eq!(treap.size(), 2);
+        assert_eq!(check_invariants(&treap.root, None, None), 2);
+    }
+
+    #[test]
+    fn rotations_keep_order() {
+        let leaf = |value| Some(Box::new(Node { value, priority: 0, left: None, right: None }));
+        let mut link = Some(Box::new(Node { value: 2, priority: 0, left: leaf(1), right: leaf(3) }));
+        Treap::rotate_right(&mut link);
+        let root = link.as_ref().unwrap();
+        assert_eq!(root.value, 1);
+        assert_eq!(root.right.as_ref().unwrap().value, 2);
+        assert_eq!(root.right.as_ref().unwrap().right.as_ref().unwrap().value, 3);
+
+        Treap::rotate_left(&mut link);
+        let root = link.as_ref().unwrap();
+        assert_eq!(root.value, 2);
+        assert_eq!(root.left.as_ref().unwrap().value, 1);
+        assert_eq!(root.right.as_ref().unwrap().value, 3);
+    }
+
+    #[test]
+    fn height_is_logarithmic_on_sorted_input() {
+        let n = 100_000;
+        let mut ascending = Treap::with_seed(2);
+        let mut descending = Treap::with_seed(3);
+        for x in 0..n {
+            ascending.add(x);
+            descending.add(n - x);
+        }
+        // The expected depth of a node is at most 2 ln(n) + O(1), about 23 for
+        // n = 100000, and the height stays within a small multiple of it.
+        let bound = 3 * (n as f64).log2() as usize;
+        assert!(ascending.height() <= bound, "height {}", ascending.height());
+        assert!(descending.height() <= bound, "height {}", descending.height());
+        assert_eq!(check_invariants(&ascending.root, None, None), n as usize);
+    }
+
+    #[test]
+    fn matches_btree_set() {
+        let mut rng = Pcg64Mcg::seed_from_u64(4);
+        let mut treap = Treap::with_seed(4);
+        let mut btree = BTreeSet::new();
+        for _ in 0..10000 {
+            let x = rng.random_range(0..500);
+            match rng.random_range(0..3) {
+                0 => assert_eq!(treap.add(x), btree.insert(x)),
+                1 => assert_eq!(treap.remove(&x), btree.remove(&x)),
+                _ => assert_eq!(treap.find(&x), btree.get(&x)),
+            }
+            assert_eq!(treap.size(), btree.len());
+        }
+        assert_eq!(check_invariants(&treap.root, None, None), btree.len());
+    }
+}
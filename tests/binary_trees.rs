@@ -1,4 +1,10 @@
+use std::time::Instant;
+use ods::arena_binary_search_tree::ArenaBinarySearchTree;
 use ods::binary_search_tree::BinarySearchTree;
+use ods::interfaces::SSet;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64Mcg;
 
 #[test]
 fn binary_search_tree() {
@@ -10,7 +16,7 @@ fn binary_search_tree() {
     tree.add(10);
     tree.add(15);
     tree.add(5);
-    let refnode_10 = tree.find(10).expect("10 should be in tree");
+    let refnode_10 = tree.find(&10).expect("10 should be in tree");
     assert_eq!(refnode_10.size(), 3);
     assert_eq!(refnode_10.depth(), 1);
     assert_eq!(refnode_10.height(), 2);
@@ -18,11 +24,84 @@ fn binary_search_tree() {
     tree.add(17);
     assert_eq!(refnode_10.height(), 3);
 
-    tree.remove(15);
-    let refnode_17 = tree.find(17).expect("17 should be in tree");
+    tree.remove(&15);
+    let refnode_17 = tree.find(&17).expect("17 should be in tree");
     assert_eq!(refnode_17.depth(), 2);
     assert_eq!(refnode_17.size(), 1);
 
-    tree.remove(0);
+    tree.remove(&0);
     assert_eq!(tree.size(), 4);
 }
+
+#[test]
+fn binary_search_tree_ordered_queries() {
+    let mut tree = BinarySearchTree::new();
+    for x in [50, 20, 80, 10, 30, 70, 90] {
+        tree.add(x);
+    }
+    assert_eq!(*tree.min().unwrap().value(), 10);
+    assert_eq!(*tree.max().unwrap().value(), 90);
+    assert_eq!(*tree.ceiling(&31).unwrap().value(), 50);
+    assert_eq!(*tree.floor(&69).unwrap().value(), 50);
+
+    let node = tree.find(&30).unwrap();
+    assert_eq!(*tree.successor(&node).unwrap().value(), 50);
+    assert_eq!(*tree.predecessor(&node).unwrap().value(), 20);
+}
+
+/// Returns a random workload for the binary search tree backends: the keys to
+/// add, and the half of them to remove afterwards.
+fn backend_workload(n: usize) -> (Vec<u64>, Vec<u64>) {
+    let mut rng = Pcg64Mcg::seed_from_u64(23);
+    let keys: Vec<u64> = (0..n).map(|_| rng.random_range(0..n as u64 * 4)).collect();
+    let mut removals = keys.clone();
+    removals.shuffle(&mut rng);
+    removals.truncate(n / 2);
+    (keys, removals)
+}
+
+/// Adds `keys` to `tree`, looks up every key below `4 * keys.len()`, and then
+/// removes `removals`. Returns how many additions, lookups and removals
+/// succeeded.
+fn run_workload<S: SSet<u64>>(tree: &mut S, keys: &[u64], removals: &[u64]) -> [usize; 3] {
+    let added = keys.iter().filter(|&&key| tree.add(key)).count();
+    let found = (0..keys.len() as u64 * 4)
+        .filter(|key| tree.find(key) == Some(*key))
+        .count();
+    let removed = removals.iter().filter(|key| tree.remove(key)).count();
+    [added, found, removed]
+}
+
+#[test]
+fn binary_search_tree_backends_agree() {
+    let (keys, removals) = backend_workload(10_000);
+    let mut rc_tree = BinarySearchTree::new();
+    let mut arena_tree = ArenaBinarySearchTree::new();
+    assert_eq!(
+        run_workload(&mut rc_tree, &keys, &removals),
+        run_workload(&mut arena_tree, &keys, &removals),
+    );
+    assert!(rc_tree.iter().eq(arena_tree.iter()));
+    rc_tree.check_invariants();
+    arena_tree.check_invariants();
+}
+
+/// Compares how long the binary search tree backends take on the same random
+/// workload. Run it with
+/// `cargo test --release binary_search_tree_backend_timings -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn binary_search_tree_backend_timings() {
+    const N: usize = 100_000;
+    let (keys, removals) = backend_workload(N);
+
+    let start = Instant::now();
+    run_workload(&mut BinarySearchTree::new(), &keys, &removals);
+    let rc_elapsed = start.elapsed();
+
+    let start = Instant::now();
+    run_workload(&mut ArenaBinarySearchTree::new(), &keys, &removals);
+    let arena_elapsed = start.elapsed();
+
+    eprintln!("{N} operations of each kind: Rc backend {rc_elapsed:?}, arena backend {arena_elapsed:?}");
+}